@@ -0,0 +1,110 @@
+//! 网格演化性能基准测试
+//! 比较位压缩网格与原先逐细胞存储实现的单代计算耗时
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use game_of_life::game::{CellState, Grid};
+
+/// 原先的网格实现：每个细胞一个枚举，每代克隆整个向量
+struct LegacyGrid {
+    width: usize,
+    height: usize,
+    cells: Vec<CellState>,
+}
+
+impl LegacyGrid {
+    fn from_grid(grid: &Grid) -> Self {
+        let cells = (0..grid.height())
+            .flat_map(|y| (0..grid.width()).map(move |x| (x, y)))
+            .map(|(x, y)| *grid.get_cell(x, y))
+            .collect();
+        Self {
+            width: grid.width(),
+            height: grid.height(),
+            cells,
+        }
+    }
+
+    fn count_neighbors(&self, x: usize, y: usize) -> usize {
+        let mut count = 0;
+        for dy in -1i64..=1 {
+            for dx in -1i64..=1 {
+                if dx == 0 && dy == 0 {
+                    continue;
+                }
+                let nx = x as i64 + dx;
+                let ny = y as i64 + dy;
+                if nx >= 0
+                    && ny >= 0
+                    && (nx as usize) < self.width
+                    && (ny as usize) < self.height
+                    && self.cells[ny as usize * self.width + nx as usize] == CellState::Alive
+                {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    fn next_generation(&mut self) {
+        let mut new_cells = self.cells.clone();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let index = y * self.width + x;
+                new_cells[index] = match (self.cells[index], self.count_neighbors(x, y)) {
+                    (CellState::Alive, 2) | (CellState::Alive, 3) => CellState::Alive,
+                    (CellState::Dead, 3) => CellState::Alive,
+                    _ => CellState::Dead,
+                };
+            }
+        }
+        self.cells = new_cells;
+    }
+}
+
+fn bench_next_generation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("next_generation");
+    group.sample_size(10);
+
+    for size in [256usize, 1024, 4096] {
+        let mut grid = Grid::new(size, size);
+        grid.randomize_with_seed(42, 0.3);
+
+        group.bench_with_input(BenchmarkId::new("bit_packed", size), &size, |b, _| {
+            let mut grid = grid.clone();
+            b.iter(|| {
+                grid.next_generation();
+                black_box(grid.count_alive_cells())
+            })
+        });
+
+        let threads = game_of_life::game::default_thread_count();
+        if threads > 1 {
+            group.bench_with_input(BenchmarkId::new("bit_packed_parallel", size), &size, |b, _| {
+                let mut grid = grid.clone();
+                grid.set_threads(threads);
+                b.iter(|| {
+                    grid.next_generation();
+                    black_box(grid.count_alive_cells())
+                })
+            });
+        }
+
+        // 原实现在 4096x4096 上单代耗时过长，只比较较小的尺寸
+        if size <= 1024 {
+            group.bench_with_input(BenchmarkId::new("legacy", size), &size, |b, _| {
+                let mut legacy = LegacyGrid::from_grid(&grid);
+                b.iter(|| {
+                    legacy.next_generation();
+                    black_box(legacy.cells.len())
+                })
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_next_generation);
+criterion_main!(benches);
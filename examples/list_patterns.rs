@@ -0,0 +1,34 @@
+//! 列出所有预设图案，并用周期检测器确认振荡器和飞船的周期
+//!
+//! 运行：`cargo run --example list_patterns --no-default-features`
+
+use game_of_life::{get_all_patterns, Grid, PeriodDetector};
+
+fn main() {
+    for (category, patterns) in get_all_patterns() {
+        println!("{}:", category);
+        for pattern in patterns {
+            // 放在足够大的网格中央，逐代观察最多100代
+            let mut grid = Grid::new(80, 80);
+            grid.load_pattern(pattern.data, 30, 30);
+            let mut detector = PeriodDetector::default();
+            let mut periodicity = None;
+            for generation in 0..=100 {
+                if generation > 0 {
+                    grid.next_generation();
+                }
+                periodicity = detector.observe(&grid, generation);
+                if periodicity.is_some() {
+                    break;
+                }
+            }
+
+            let detected =
+                periodicity.map_or_else(|| "no period within 100".to_string(), |p| p.to_string());
+            println!(
+                "  {:<20} {:<45} {}",
+                pattern.name, pattern.description, detected
+            );
+        }
+    }
+}
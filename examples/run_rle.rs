@@ -0,0 +1,60 @@
+//! 读取 RLE 字符串，用 HashLife 推进很多代，再用位压缩网格记录前几代的人口
+//!
+//! 运行：`cargo run --example run_rle --no-default-features`
+
+use game_of_life::{
+    export_to_rle_string, import_from_rle_string, CellState, HashLife, LifeEngine,
+    PopulationStatistics,
+};
+
+/// Gosper 滑翔机枪
+const GOSPER_GUN: &str = "#N Gosper glider gun
+x = 36, y = 9, rule = B3/S23
+24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4b
+obo$10bo5bo7bo$11bo3bo$12b2o!
+";
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let pattern = import_from_rle_string(GOSPER_GUN)?;
+
+    // 前60代的人口，每30代增加一个滑翔机
+    let mut grid = pattern.to_grid()?.new_like(80, 60);
+    for (y, row) in pattern.data.iter().enumerate() {
+        for (x, &alive) in row.iter().enumerate() {
+            if alive {
+                grid.set_cell(x + 2, y + 2, CellState::Alive);
+            }
+        }
+    }
+    let mut statistics = PopulationStatistics::new(61);
+    for _ in 0..=60 {
+        statistics.add_population(grid.count_alive_cells());
+        grid.next_generation();
+    }
+    println!(
+        "First 60 generations: min {:?}, max {:?}, average {:.1}",
+        statistics.get_min_population(),
+        statistics.get_max_population(),
+        statistics.get_average_population().unwrap_or(0.0)
+    );
+
+    // HashLife 在无界平面上一次推进 2^20 代
+    let mut universe = HashLife::new();
+    universe.set_rule(pattern.parse_rule()?)?;
+    for (y, row) in pattern.data.iter().enumerate() {
+        for (x, &alive) in row.iter().enumerate() {
+            if alive {
+                universe.set_cell(x as i64, y as i64, CellState::Alive);
+            }
+        }
+    }
+    universe.advance(1 << 20);
+    println!(
+        "After 2^20 generations: {} live cells",
+        universe.count_alive_cells()
+    );
+
+    // 重新导出为 RLE
+    println!("{}", export_to_rle_string(&pattern));
+    Ok(())
+}
@@ -0,0 +1,41 @@
+//! 生成随机汤，演化到稳定后进行对象普查，输出 apgcode 统计
+//!
+//! 运行：`cargo run --example soup_census --no-default-features -- [种子]`
+
+use game_of_life::{take_census, Grid, PeriodDetector};
+
+fn main() {
+    let seed = std::env::args()
+        .nth(1)
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(42);
+
+    // 16x16 的随机汤放在大网格中央
+    let mut grid = Grid::new(256, 256);
+    grid.randomize_region(seed, 0.5, Default::default(), (120, 120, 16, 16));
+
+    // 演化到整个网格出现周期（最多5000代）
+    let mut detector = PeriodDetector::default();
+    let mut generation = 0;
+    while generation < 5000 && detector.observe(&grid, generation).is_none() {
+        grid.next_generation();
+        generation += 1;
+    }
+
+    let census = take_census(&grid);
+    println!(
+        "Soup {} settled after {} generations into {} objects:",
+        seed,
+        generation,
+        census.total_objects()
+    );
+    for entry in &census.entries {
+        println!(
+            "{:>5}  {:<24} {:<14} {}",
+            entry.count,
+            entry.apgcode,
+            entry.kind_label(),
+            entry.name.unwrap_or("")
+        );
+    }
+}
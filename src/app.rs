@@ -0,0 +1,1427 @@
+//! 图形界面应用模块
+//! 包含应用程序状态 `GameOfLifeApp` 以及 eframe 的主循环，只在启用 `gui` 特性时编译
+
+mod ui;
+
+use crate::theme::{ColorTheme, ThemeManager};
+use crate::ui_state::{Tool, UiStateManager};
+use eframe::egui;
+use game_of_life::breakpoints::{Breakpoints, Sample};
+use game_of_life::census::{self, Census, CensusColumn};
+use game_of_life::game::{self, Algorithm, BoundingBox, CellState, Grid, LifeEngine};
+use game_of_life::hashlife::{self, HashLife};
+use game_of_life::history::History;
+use game_of_life::macrocell::{self, MacrocellInfo, MacrocellPattern};
+use game_of_life::patterns;
+use game_of_life::period::{PeriodDetector, Periodicity};
+use game_of_life::random::{self, SoupSettings, Symmetry};
+use game_of_life::rules::{self, Rule};
+use game_of_life::save_load;
+use game_of_life::search::{Category, SearchDatabase, SoupRecord};
+use game_of_life::selection::Selection;
+use game_of_life::stamp::{Stamp, StampMode, Transform};
+use game_of_life::statistics::PopulationStatistics;
+use game_of_life::timeline::Timeline;
+use game_of_life::topology::Topology;
+use game_of_life::universe::SparseUniverse;
+
+/// 康威生命游戏应用程序的主结构体
+/// 包含游戏状态、UI设置和控制参数
+pub struct GameOfLifeApp {
+    /// 游戏网格，存储细胞状态
+    grid: Grid,
+    /// 游戏是否正在运行（自动更新）
+    is_running: bool,
+    /// 上次更新的时间戳，用于控制更新频率
+    last_update: std::time::Instant,
+    /// 更新间隔时间
+    update_interval: std::time::Duration,
+    /// 网格宽度设置（用于UI调节）
+    grid_width: usize,
+    /// 网格高度设置（用于UI调节）
+    grid_height: usize,
+    /// 更新速度设置（FPS）
+    update_speed: f32,
+    /// 随机化时的细胞密度
+    density: f32,
+    /// 随机种子输入框中的文本
+    seed_input: String,
+    /// 是否固定使用输入框中的种子（否则每次随机化生成新种子）
+    fixed_seed: bool,
+    /// 随机汤的对称性
+    symmetry: Symmetry,
+    /// 是否只随机化网格中的一个矩形区域
+    random_region_enabled: bool,
+    /// 随机化区域 (x, y, 宽, 高)
+    random_region: (usize, usize, usize, usize),
+    /// 生成当前网格内容的随机汤参数（保存时写入RLE注释）
+    last_soup: Option<SoupSettings>,
+    /// 放置预设或加载图案时与已有细胞的合并方式
+    stamp_mode: StampMode,
+    /// 放置前对图案的旋转和镜像
+    stamp_transform: Transform,
+    /// 是否把图案放在网格中央（否则放在 stamp_position）
+    stamp_centered: bool,
+    /// 不居中时图案左上角在网格中的位置
+    stamp_position: (usize, usize),
+    /// 正在交互式放置的图案（名称和未变换的图案），来自预设或粘贴
+    placing: Option<(String, Stamp)>,
+    /// 当前的矩形选区
+    selection: Option<Selection>,
+    /// 最近一次复制或剪切的图案
+    clipboard: Option<Stamp>,
+    /// 当前迭代次数（代数）
+    generation: u64,
+    /// 规则输入框中的文本（B/S记法）
+    rule_input: String,
+    /// 当前使用的演化算法
+    algorithm: Algorithm,
+    /// HashLife 宇宙
+    hashlife: HashLife,
+    /// 稀疏瓦片宇宙
+    sparse: SparseUniverse,
+    /// HashLife 每步推进 2^step_exponent 代
+    step_exponent: u32,
+    /// 使用无界宇宙时，网格窗口左上角在宇宙中的坐标
+    view_origin: (i64, i64),
+    /// 存活区域移出窗口时是否自动移动窗口
+    follow_live_region: bool,
+    /// 上次与无界宇宙同步时网格的修订号
+    synced_revision: Option<u64>,
+    
+    /// 撤销/重做历史
+    history: History,
+
+    /// 最近若干代的时间线，用于回放
+    timeline: Timeline,
+
+    /// 周期检测器
+    period_detector: PeriodDetector,
+
+    /// 运行时的暂停条件
+    breakpoints: Breakpoints,
+
+    /// 最近一次对象普查的结果
+    census: Option<Census>,
+
+    /// 普查表的排序：(列, 是否降序)
+    census_sort: (CensusColumn, bool),
+
+    /// 打开的随机汤搜索数据库
+    search_results: Option<SearchDatabase>,
+
+    /// 周期检测器最近一次观察时网格的修订号
+    period_revision: Option<u64>,
+
+    /// 人口统计管理器
+    statistics: PopulationStatistics,
+    /// 主题管理器
+    theme_manager: ThemeManager,
+    /// UI状态管理器
+    ui_state: UiStateManager,
+}
+
+/// 为GameOfLifeApp实现Default trait
+/// 提供应用程序的默认配置
+impl Default for GameOfLifeApp {
+    fn default() -> Self {
+        // 设置默认网格尺寸
+        let grid_width = 60;
+        let grid_height = 40;
+
+        // 创建网格并进行随机初始化
+        let mut grid = Grid::new(grid_width, grid_height);
+        grid.set_threads(game::default_thread_count());
+        let density = 0.3;
+        let seed = grid.randomize(density);
+
+        // 初始化人口统计
+        let mut statistics = PopulationStatistics::new(200);
+        let initial_population = grid.count_alive_cells();
+        statistics.add_population(initial_population);
+
+        Self {
+            grid,
+            is_running: false,                      // 初始状态为暂停
+            last_update: std::time::Instant::now(), // 记录当前时间
+            update_interval: std::time::Duration::from_millis(100), // 默认100ms更新一次（10 FPS）
+            grid_width,
+            grid_height,
+            update_speed: 10.0, // 默认10 FPS
+            density,
+            seed_input: seed.to_string(),
+            fixed_seed: false,
+            symmetry: Symmetry::C1,
+            random_region_enabled: false,
+            random_region: (0, 0, grid_width / 2, grid_height / 2),
+            last_soup: Some(SoupSettings {
+                seed,
+                density,
+                symmetry: Symmetry::C1,
+            }),
+            stamp_mode: StampMode::default(),
+            stamp_transform: Transform::default(),
+            stamp_centered: true,
+            stamp_position: (0, 0),
+            placing: None,
+            selection: None,
+            clipboard: None,
+            generation: 0,      // 初始代数为0
+            rule_input: Rule::conway().to_string(),
+            algorithm: Algorithm::default(),
+            hashlife: HashLife::new(),
+            sparse: SparseUniverse::new(),
+            step_exponent: 0,
+            view_origin: (0, 0),
+            follow_live_region: true,
+            synced_revision: None,
+            
+            history: History::default(),
+            timeline: Timeline::default(),
+            period_detector: PeriodDetector::default(),
+            breakpoints: Breakpoints::default(),
+            census: None,
+            census_sort: (CensusColumn::Count, true),
+            search_results: None,
+            period_revision: None,
+            statistics,
+            theme_manager: ThemeManager::new(ColorTheme::Dark),
+            ui_state: UiStateManager::new(),
+        }
+    }
+}
+
+impl GameOfLifeApp {
+    /// 设置状态信息
+    fn set_status(&mut self, message: String) {
+        self.ui_state.set_status(message);
+    }
+
+    /// 检查并清除过期的状态信息
+    fn update_status(&mut self) {
+        self.ui_state.update_status();
+    }
+
+
+    /// 获取当前有效的细胞大小（考虑缩放）
+    fn effective_cell_size(&self) -> f32 {
+        self.ui_state.effective_cell_size()
+    }
+
+    /// 处理缩放操作
+    fn handle_zoom(&mut self, delta: f32, mouse_pos: Option<egui::Pos2>) {
+        self.ui_state.handle_zoom(delta, mouse_pos);
+    }
+
+    /// 缩放并平移视口，使所有存活细胞完整显示；无界宇宙中先把窗口移到存活区域的中心
+    pub fn fit_pattern_to_window(&mut self) {
+        if self.algorithm.is_unbounded() {
+            self.sync_universe();
+            if let Some(bounds) = self.unbounded_engine().and_then(|engine| engine.bounding_box()) {
+                self.center_view_on(bounds);
+                self.render_universe();
+            }
+        }
+        match LifeEngine::bounding_box(&self.grid) {
+            Some(bounds) => self.ui_state.fit_cells((
+                bounds.min_x as usize,
+                bounds.min_y as usize,
+                bounds.width() as usize,
+                bounds.height() as usize,
+            )),
+            None => self.set_status("No live cells to fit".to_string()),
+        }
+    }
+
+    /// 缩放并平移视口，使选区完整显示
+    pub fn zoom_to_selection(&mut self) {
+        if let Some(selection) = self.selection.and_then(|selection| selection.clip_to(&self.grid)) {
+            self.ui_state.fit_cells(selection.region());
+        }
+    }
+
+    /// 获取当前主题的颜色配置（支持动画过渡）
+    fn get_theme_colors(&self) -> (egui::Color32, egui::Color32, egui::Color32) {
+        self.theme_manager.get_theme_colors()
+    }
+
+    /// 开始主题切换动画
+    pub fn start_theme_transition(&mut self, new_theme: ColorTheme) {
+        self.theme_manager.start_theme_transition(new_theme);
+    }
+
+    /// 更新主题切换动画
+    fn update_theme_transition(&mut self) {
+        self.theme_manager.update_theme_transition();
+    }
+
+    /// 设置UI主题（支持动画过渡）
+    fn set_ui_theme(&self, ctx: &egui::Context) {
+        self.theme_manager.apply_ui_theme(ctx);
+    }
+
+    /// 保存游戏状态到图案文件（格式由选择的扩展名决定）
+    fn save_game(&mut self) {
+        let mut dialog = rfd::FileDialog::new();
+        for format in save_load::PatternFormat::ALL {
+            dialog = dialog.add_filter(format!("{} Files", format.name()), format.extensions());
+        }
+        if let Some(path) = dialog.set_file_name("pattern.rle").save_file() {
+            // 网格来自随机汤时，把种子等参数写入注释以便复现
+            let mut pattern =
+                save_load::RlePattern::from_grid(&self.grid, "Exported Pattern".to_string());
+            if let Some(soup) = self.last_soup {
+                pattern.comment = soup.to_comment();
+            }
+            // 记录代数；无界宇宙中还记录窗口在宇宙中的位置
+            pattern.generation = self.generation;
+            if self.algorithm.is_unbounded() {
+                pattern.position = Some(self.view_origin);
+            }
+            // HashLife 下保存宏细胞文件时写出整个宇宙，而不只是网格窗口
+            let whole_universe = self.algorithm == Algorithm::HashLife
+                && save_load::PatternFormat::from_path(&path)
+                    == Some(save_load::PatternFormat::Macrocell);
+            let result = if whole_universe {
+                self.sync_universe();
+                let info = MacrocellInfo {
+                    name: pattern.name,
+                    author: pattern.author,
+                    comment: pattern.comment,
+                    generation: pattern.generation,
+                };
+                macrocell::save_macrocell_file(&path, &self.hashlife, &info)
+            } else {
+                save_load::save_pattern_file(&path, &pattern)
+            };
+            match result {
+                Ok(_) => {
+                    self.set_status(format!("Pattern saved to: {:?}", path));
+                }
+                Err(e) => {
+                    self.set_status(format!("Save failed: {}", e));
+                }
+            }
+        }
+    }
+
+    /// 从图案文件加载图案（按内容自动识别格式）
+    fn load_game(&mut self) {
+        let extensions: Vec<&str> = save_load::PatternFormat::ALL
+            .iter()
+            .flat_map(|format| format.extensions().iter().copied())
+            .collect();
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("Pattern Files", &extensions)
+            .add_filter("All Files", &["*"])
+            .pick_file()
+        {
+            let content = match std::fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(e) => {
+                    self.set_status(format!("Failed to load pattern file: {}", e));
+                    return;
+                }
+            };
+            // 宏细胞文件直接载入 HashLife 宇宙，不转换为稠密图案
+            let format = save_load::PatternFormat::detect(&content);
+            let result = if format == save_load::PatternFormat::Macrocell {
+                macrocell::import_from_macrocell_string(&content)
+                    .map(|pattern| self.load_macrocell_pattern(pattern, &path))
+            } else {
+                save_load::import_pattern_string_with_mode(&content, save_load::ParseMode::Lenient)
+                    .map(|(pattern, warnings)| self.load_rle_pattern(pattern, &warnings, &path))
+            };
+            if let Err(e) = result {
+                self.set_status(format!("Failed to load pattern file: {}", e));
+            }
+        }
+    }
+
+    /// 加载RLE图案，解析时的警告显示在状态栏中
+    fn load_rle_pattern(
+        &mut self,
+        pattern: save_load::RlePattern,
+        parse_warnings: &[save_load::RleWarning],
+        path: &std::path::Path,
+    ) {
+        self.history.begin("Load pattern", &self.grid, self.generation, self.view_origin);
+
+        let mut warnings = Vec::new();
+        if let Some(first) = parse_warnings.first() {
+            warnings.push(format!("{} parse warning(s), first at {}", parse_warnings.len(), first));
+        }
+
+        // 解析规则字段中的拓扑后缀；后缀声明了尺寸时使用该尺寸
+        let (topology, topology_size) = match pattern.parse_topology() {
+            Ok(result) => result,
+            Err(e) => {
+                warnings.push(format!("kept boundary {}: {}", self.grid.topology().name(), e));
+                (self.grid.topology(), None)
+            }
+        };
+
+        // 先按当前变换旋转、镜像图案；合并前记录宇宙是否为空
+        let stamp = Stamp::from_rle(&pattern).transformed(self.stamp_transform);
+        let was_empty = self.is_universe_empty();
+
+        // 有界网格放不下图案（或拓扑后缀声明了尺寸）时扩大网格，原有细胞保留在原位；
+        // 无界宇宙中图案完整放入宇宙，网格只是窗口，无需扩大
+        let unbounded = self.algorithm.is_unbounded();
+        let (new_width, new_height) = if unbounded {
+            (self.grid.width(), self.grid.height())
+        } else {
+            topology_size.unwrap_or((
+                stamp.width().max(self.grid.width()),
+                stamp.height().max(self.grid.height()),
+            ))
+        };
+        if (new_width, new_height) != (self.grid.width(), self.grid.height()) {
+            let mut new_grid = self.grid.new_like(new_width, new_height);
+            for y in 0..self.grid.height().min(new_height) {
+                for x in 0..self.grid.width().min(new_width) {
+                    if self.grid.is_alive(x, y) {
+                        new_grid.set_cell(x, y, CellState::Alive);
+                    }
+                }
+            }
+            self.grid = new_grid;
+            self.grid_width = new_width;
+            self.grid_height = new_height;
+        }
+        if let Err(e) = self.grid.set_topology(topology) {
+            warnings.push(format!("kept boundary {}: {}", self.grid.topology().name(), e));
+        }
+        if !unbounded && (stamp.width() > new_width || stamp.height() > new_height) {
+            warnings.push(format!(
+                "pattern ({}x{}) clipped to the declared {}x{} grid",
+                stamp.width(),
+                stamp.height(),
+                new_width,
+                new_height
+            ));
+        }
+
+        // 切换到图案头部中声明的规则；无界宇宙不接受 B0 规则
+        let rule = pattern.parse_rule().map_err(|e| e.to_string()).and_then(|rule| {
+            if unbounded {
+                rule.check_unbounded()?;
+            }
+            Ok(rule)
+        });
+        match rule {
+            Ok(rule) => self.grid.set_rule(rule),
+            Err(e) => warnings.push(format!("kept rule {}: {}", self.grid.rule(), e)),
+        }
+        self.rule_input = self.grid.rule().to_string();
+
+        // Golly 导出的文件在 #CXRLE 中记录了代数；合并到已有细胞中时保留当前代数
+        if was_empty {
+            self.generation = pattern.generation;
+        }
+
+        // 注释中记录了随机汤参数时，恢复这些参数以便重新生成
+        self.last_soup = SoupSettings::from_comment(&pattern.comment);
+        if let Some(soup) = self.last_soup {
+            self.seed_input = soup.seed.to_string();
+            self.density = soup.density;
+            self.symmetry = soup.symmetry;
+        }
+
+        if unbounded {
+            // 居中放置且文件记录了位置时，图案左上角放在记录的位置；否则相对于当前窗口放置
+            let (left, top) = match pattern.position {
+                Some(position) if self.stamp_centered => position,
+                _ => {
+                    let (x, y) = self.stamp_origin(&stamp);
+                    (self.view_origin.0 + x, self.view_origin.1 + y)
+                }
+            };
+            let (rule, mode) = (*self.grid.rule(), self.stamp_mode);
+            if let Some(engine) = self.unbounded_engine_mut() {
+                if let Err(e) = engine.set_rule(rule) {
+                    warnings.push(format!("kept rule {}: {}", engine.rule(), e));
+                }
+                engine.stamp(&stamp, left, top, mode);
+            }
+            // 图案不在当前窗口内时，把窗口移到图案中心
+            let mut bounds = BoundingBox::around(left, top);
+            bounds.include(left + stamp.width() as i64 - 1, top + stamp.height() as i64 - 1);
+            let origin = self.view_origin;
+            if !bounds.is_inside(origin, (origin.0 + new_width as i64, origin.1 + new_height as i64)) {
+                self.center_view_on(bounds);
+            }
+            self.render_universe();
+        } else {
+            let (x, y) = self.stamp_origin(&stamp);
+            self.grid.stamp(&stamp, x, y, self.stamp_mode);
+        }
+
+        let mut info = if pattern.name.is_empty() {
+            format!("Pattern loaded from: {:?}", path)
+        } else {
+            format!("Pattern '{}' loaded from: {:?}", pattern.name, path)
+        };
+        if !warnings.is_empty() {
+            info.push_str(&format!(" ({})", warnings.join("; ")));
+        }
+        self.set_status(info);
+        self.history.commit(&self.grid, self.generation, self.view_origin);
+    }
+
+    /// 加载宏细胞图案：切换到 HashLife 算法，整个图案保留在宇宙中，窗口以图案为中心
+    fn load_macrocell_pattern(&mut self, pattern: MacrocellPattern, path: &std::path::Path) {
+        self.history.begin("Load pattern", &self.grid, self.generation, self.view_origin);
+
+        let rule = *pattern.universe.rule();
+        let mut new_grid = self.grid.new_like(self.grid.width(), self.grid.height());
+        new_grid.set_rule(rule);
+        self.grid = new_grid;
+        self.rule_input = rule.to_string();
+        self.algorithm = Algorithm::HashLife;
+        self.generation = pattern.info.generation;
+        self.last_soup = None;
+
+        self.reset_universe();
+        self.hashlife = pattern.universe;
+        if let Some(bounds) = self.hashlife.bounding_box() {
+            self.center_view_on(bounds);
+        }
+        self.render_universe();
+
+        let name = if pattern.info.name.is_empty() {
+            String::new()
+        } else {
+            format!(" '{}'", pattern.info.name)
+        };
+        self.set_status(format!(
+            "Pattern{} loaded from: {:?} ({} cells, switched to HashLife)",
+            name,
+            path,
+            self.hashlife.population()
+        ));
+        self.history.commit(&self.grid, self.generation, self.view_origin);
+    }
+
+    /// 切换演化规则
+    pub fn apply_rule(&mut self, rule: Rule) {
+        // 无界宇宙无法模拟 B0 规则，保留原规则
+        if let Err(e) = self.check_algorithm_rule(self.algorithm, &rule) {
+            self.rule_input = self.grid.rule().to_string();
+            self.set_status(format!("Rule not changed: {}", e));
+            return;
+        }
+        self.grid.set_rule(rule);
+        self.rule_input = rule.to_string();
+        self.set_status(format!("Rule set to {}", rule));
+    }
+
+    /// 检查规则能否用于指定的算法：无界算法不支持 B0 规则
+    fn check_algorithm_rule(&self, algorithm: Algorithm, rule: &Rule) -> Result<(), String> {
+        if algorithm.is_unbounded() {
+            rule.check_unbounded()
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 切换演化算法
+    pub fn apply_algorithm(&mut self, algorithm: Algorithm) {
+        if algorithm == self.algorithm {
+            return;
+        }
+        if let Err(e) = self.check_algorithm_rule(algorithm, self.grid.rule()) {
+            self.set_status(format!("{} not selected: {}", algorithm.name(), e));
+            return;
+        }
+        self.algorithm = algorithm;
+        self.reset_universe();
+
+        let mut info = format!("Algorithm set to {}", algorithm.name());
+        if algorithm.is_unbounded() && self.grid.topology() != Topology::Bounded {
+            info.push_str(" (the universe is unbounded, boundary setting is ignored)");
+        }
+        self.set_status(info);
+    }
+
+    /// 当前使用的无界宇宙（位压缩网格算法下为 `None`）
+    pub fn unbounded_engine(&self) -> Option<&dyn LifeEngine> {
+        match self.algorithm {
+            Algorithm::BitPacked => None,
+            Algorithm::HashLife => Some(&self.hashlife),
+            Algorithm::SparseTiles => Some(&self.sparse),
+        }
+    }
+
+    /// 当前使用的演化引擎：无界宇宙，或者位压缩网格本身
+    fn engine(&self) -> &dyn LifeEngine {
+        self.unbounded_engine().unwrap_or(&self.grid)
+    }
+
+    /// 当前使用的无界宇宙（可变引用）
+    fn unbounded_engine_mut(&mut self) -> Option<&mut dyn LifeEngine> {
+        match self.algorithm {
+            Algorithm::BitPacked => None,
+            Algorithm::HashLife => Some(&mut self.hashlife),
+            Algorithm::SparseTiles => Some(&mut self.sparse),
+        }
+    }
+
+    /// 推进一步：网格和稀疏瓦片推进一代，HashLife 推进 2^k 代
+    pub fn step_simulation(&mut self) {
+        self.history.begin("Step", &self.grid, self.generation, self.view_origin);
+        self.record_timeline();
+        // 网格在上次观察后被编辑过，周期检测从当前状态重新开始
+        if self.period_revision != Some(self.grid.revision()) {
+            self.period_detector.reset();
+            self.observe_period();
+        }
+        let generations = match self.algorithm {
+            Algorithm::BitPacked => {
+                self.grid.next_generation();
+                1
+            }
+            Algorithm::HashLife => {
+                self.sync_universe();
+                self.hashlife.advance_pow2(self.step_exponent);
+                1u64 << self.step_exponent
+            }
+            Algorithm::SparseTiles => {
+                self.sync_universe();
+                self.sparse.next_generation();
+                1
+            }
+        };
+        if self.algorithm.is_unbounded() {
+            if self.follow_live_region {
+                self.follow_live_cells();
+            }
+            self.render_universe();
+        }
+        self.generation = self.generation.saturating_add(generations);
+        self.update_population_history();
+        self.record_timeline();
+        self.observe_period();
+        self.history.commit(&self.grid, self.generation, self.view_origin);
+    }
+
+    /// 用当前状态更新周期检测
+    ///
+    /// 使用无界宇宙且与网格同步时检测整个宇宙，否则检测网格
+    fn observe_period(&mut self) {
+        let synced = self.synced_revision == Some(self.grid.revision());
+        let engine: &dyn LifeEngine = match self.algorithm {
+            Algorithm::HashLife if synced => &self.hashlife,
+            Algorithm::SparseTiles if synced => &self.sparse,
+            _ => &self.grid,
+        };
+        self.period_detector.observe(engine, self.generation);
+        self.period_revision = Some(self.grid.revision());
+    }
+
+    /// 对当前所有活细胞进行对象普查
+    ///
+    /// 使用无界宇宙且与网格同步时普查整个宇宙，否则普查网格
+    pub fn run_census(&mut self) {
+        let synced = self.synced_revision == Some(self.grid.revision());
+        let engine: &dyn LifeEngine = match self.algorithm {
+            Algorithm::HashLife if synced => &self.hashlife,
+            Algorithm::SparseTiles if synced => &self.sparse,
+            _ => &self.grid,
+        };
+        let mut census = census::take_census(engine);
+        census.sort_by(self.census_sort.0, self.census_sort.1);
+        self.set_status(format!(
+            "Census at generation {}: {} objects, {} kinds",
+            self.generation,
+            census.total_objects(),
+            census.entries.len()
+        ));
+        self.census = Some(census);
+    }
+
+    /// 按指定列排序普查表，再次点击同一列时切换升序/降序
+    pub fn sort_census(&mut self, column: CensusColumn) {
+        let descending = if self.census_sort.0 == column {
+            !self.census_sort.1
+        } else {
+            column == CensusColumn::Count
+        };
+        self.census_sort = (column, descending);
+        if let Some(census) = &mut self.census {
+            census.sort_by(column, descending);
+        }
+    }
+
+    /// 把普查结果导出为 CSV 文件
+    pub fn export_census(&mut self) {
+        let csv = match &self.census {
+            Some(census) => census.to_csv(),
+            None => return,
+        };
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("CSV Files", &["csv"])
+            .set_file_name("census.csv")
+            .save_file()
+        {
+            match std::fs::write(&path, csv) {
+                Ok(_) => self.set_status(format!("Census exported to: {:?}", path)),
+                Err(e) => self.set_status(format!("Export failed: {}", e)),
+            }
+        }
+    }
+
+    /// 当前状态检测到的周期性；网格在最近一步之后被编辑过时返回 `None`
+    pub fn detected_period(&self) -> Option<Periodicity> {
+        if self.period_revision == Some(self.grid.revision()) {
+            self.period_detector.result()
+        } else {
+            None
+        }
+    }
+
+    /// 运行时推进一步，触发断点时暂停并说明原因
+    fn run_step(&mut self) {
+        let before = self.breakpoint_sample();
+        // 监视区域使用引擎坐标，窗口在这一步中跟随存活细胞移动也不影响比较
+        self.sync_universe();
+        let region_before = self.breakpoints.capture_region(self.engine());
+        self.step_simulation();
+        let after = self.breakpoint_sample();
+        let reason = self
+            .breakpoints
+            .check(before, after, region_before.as_deref(), self.engine());
+        if let Some(reason) = reason {
+            self.is_running = false;
+            self.set_status(format!("Paused at generation {}: {}", self.generation, reason));
+        }
+    }
+
+    /// 断点检查所需的当前状态
+    fn breakpoint_sample(&self) -> Sample {
+        Sample {
+            generation: self.generation,
+            population: self.get_current_population(),
+            periodicity: self.detected_period(),
+        }
+    }
+
+    /// 把当前网格记录到时间线
+    fn record_timeline(&mut self) {
+        let population = self.get_current_population();
+        self.timeline.record(&self.grid, self.generation, self.view_origin, population);
+    }
+
+    /// 跳转到时间线上的第 `index` 帧，并同步代数和人口统计
+    ///
+    /// 使用无界宇宙时时间线只记录窗口内的细胞：窗口回到该帧记录时的位置，
+    /// 宇宙被清空后在下一步从窗口重新载入，窗口之外的细胞不会停留在之后的代数
+    pub fn jump_to_frame(&mut self, index: usize) {
+        self.is_running = false;
+        self.history.begin("Rewind", &self.grid, self.generation, self.view_origin);
+        if let Some((generation, origin)) = self.timeline.jump(index, &mut self.grid) {
+            if self.algorithm.is_unbounded() {
+                self.reset_universe();
+            }
+            self.view_origin = origin;
+            self.generation = generation;
+            self.grid_width = self.grid.width();
+            self.grid_height = self.grid.height();
+            self.rule_input = self.grid.rule().to_string();
+            self.synced_revision = None;
+            self.statistics.set_history(self.timeline.populations(index));
+            self.set_status(format!("Rewound to generation {}", generation));
+        }
+        self.history.commit(&self.grid, self.generation, self.view_origin);
+    }
+
+    /// 将网格上的修改（绘制、规则切换等）同步到无界宇宙
+    fn sync_universe(&mut self) {
+        let (rule, revision, origin) = (*self.grid.rule(), self.grid.revision(), self.view_origin);
+        let synced = self.synced_revision == Some(revision);
+        let grid = &self.grid;
+        let engine: &mut dyn LifeEngine = match self.algorithm {
+            Algorithm::BitPacked => return,
+            Algorithm::HashLife => &mut self.hashlife,
+            Algorithm::SparseTiles => &mut self.sparse,
+        };
+        let rule_error = engine.set_rule(rule).err().map(|e| (e, *engine.rule()));
+        if !synced {
+            engine.load_window(grid, origin);
+            self.synced_revision = Some(revision);
+        }
+        // 撤销等操作恢复了带 B0 规则的网格时，网格沿用宇宙中的规则
+        if let Some((e, kept)) = rule_error {
+            self.grid.set_rule(kept);
+            self.rule_input = kept.to_string();
+            self.set_status(format!("Kept rule {}: {}", kept, e));
+        }
+    }
+
+    /// 将无界宇宙中的窗口区域绘制到网格
+    fn render_universe(&mut self) {
+        let grid = &mut self.grid;
+        let engine: &dyn LifeEngine = match self.algorithm {
+            Algorithm::BitPacked => return,
+            Algorithm::HashLife => &self.hashlife,
+            Algorithm::SparseTiles => &self.sparse,
+        };
+        engine.render_window(grid, self.view_origin);
+        self.synced_revision = Some(self.grid.revision());
+    }
+
+    /// 存活区域离开窗口时，把窗口移动到存活区域的中心
+    fn follow_live_cells(&mut self) {
+        let (width, height) = (self.grid.width() as i64, self.grid.height() as i64);
+        let origin = self.view_origin;
+        let bounds = match self.unbounded_engine().and_then(|engine| engine.bounding_box()) {
+            Some(bounds) => bounds,
+            None => return,
+        };
+        if !bounds.is_inside(origin, (origin.0 + width, origin.1 + height)) {
+            self.center_view_on(bounds);
+        }
+    }
+
+    /// 把窗口移动到矩形区域的中心
+    fn center_view_on(&mut self, bounds: BoundingBox) {
+        let (width, height) = (self.grid.width() as i64, self.grid.height() as i64);
+        self.view_origin = (
+            bounds.min_x + (bounds.max_x - bounds.min_x) / 2 - width / 2,
+            bounds.min_y + (bounds.max_y - bounds.min_y) / 2 - height / 2,
+        );
+    }
+
+    /// 当前没有任何存活细胞（无界算法下检查整个宇宙）
+    fn is_universe_empty(&mut self) -> bool {
+        self.sync_universe();
+        match self.unbounded_engine() {
+            Some(engine) => engine.count_alive_cells() == 0,
+            None => self.grid.count_alive_cells() == 0,
+        }
+    }
+
+    /// 图案左上角在网格中的位置：居中放置，或放在指定位置
+    fn stamp_origin(&self, stamp: &Stamp) -> (i64, i64) {
+        let (x, y) = if self.stamp_centered {
+            (
+                self.grid.width().saturating_sub(stamp.width()) / 2,
+                self.grid.height().saturating_sub(stamp.height()) / 2,
+            )
+        } else {
+            self.stamp_position
+        };
+        (x as i64, y as i64)
+    }
+
+    /// 开始交互式放置图案：图案的虚影跟随鼠标，点击盖章，Esc 取消
+    pub fn start_placement(&mut self, name: &str, stamp: Stamp) {
+        self.placing = Some((name.to_string(), stamp));
+        self.set_status(format!(
+            "Placing '{}': click to stamp, R/Shift+R rotate, F/V flip, Esc to finish",
+            name
+        ));
+    }
+
+    /// 结束交互式放置
+    pub fn cancel_placement(&mut self) {
+        if self.placing.take().is_some() {
+            self.set_status("Placement finished".to_string());
+        }
+    }
+
+    /// 正在放置的图案（已应用当前的旋转和镜像），没有在放置时返回 `None`
+    pub fn placement_stamp(&self) -> Option<Stamp> {
+        self.placing
+            .as_ref()
+            .map(|(_, stamp)| stamp.transformed(self.stamp_transform))
+    }
+
+    /// 图案中心对准 (x, y) 处细胞时图案左上角的坐标
+    pub fn placement_origin(stamp: &Stamp, (x, y): (usize, usize)) -> (i64, i64) {
+        (
+            x as i64 - stamp.width() as i64 / 2,
+            y as i64 - stamp.height() as i64 / 2,
+        )
+    }
+
+    /// 把正在放置的图案以 (x, y) 处细胞为中心按当前合并方式盖到网格上；网格原本为空时代数和统计重新开始
+    pub fn place_at(&mut self, x: usize, y: usize) {
+        let stamp = match self.placement_stamp() {
+            Some(stamp) => stamp,
+            None => return,
+        };
+        self.history.begin("Place pattern", &self.grid, self.generation, self.view_origin);
+        let was_empty = self.is_universe_empty();
+        let (left, top) = Self::placement_origin(&stamp, (x, y));
+        self.grid.stamp(&stamp, left, top, self.stamp_mode);
+        self.last_soup = None;
+        if was_empty {
+            self.generation = 0;
+            self.clear_population_history();
+        }
+        self.update_population_history();
+        self.history.commit(&self.grid, self.generation, self.view_origin);
+    }
+
+    /// 对选区执行一次可撤销的编辑；编辑返回移动后的选区（旋转、平移时选区跟随细胞移动）
+    pub fn edit_selection(
+        &mut self,
+        label: &'static str,
+        edit: impl FnOnce(&mut Grid, Selection) -> Option<Selection>,
+    ) {
+        // 网格尺寸可能在选区之后改变
+        let selection = match self.selection.and_then(|selection| selection.clip_to(&self.grid)) {
+            Some(selection) => selection,
+            None => {
+                self.selection = None;
+                return;
+            }
+        };
+        self.history.begin(label, &self.grid, self.generation, self.view_origin);
+        self.selection = edit(&mut self.grid, selection);
+        self.last_soup = None;
+        self.history.commit(&self.grid, self.generation, self.view_origin);
+    }
+
+    /// 用当前密度随机填充选区
+    pub fn random_fill_selection(&mut self) {
+        let (seed, density) = (random::seed_from_clock(), self.density);
+        self.edit_selection("Random fill", |grid, selection| {
+            selection.randomize(grid, seed, density);
+            Some(selection)
+        });
+    }
+
+    /// 复制选区到内部剪贴板，并以 RLE 文本写入系统剪贴板
+    pub fn copy_selection(&mut self, ctx: &egui::Context) {
+        let stamp = match self.selection.and_then(|selection| selection.clip_to(&self.grid)) {
+            Some(selection) => selection.copy(&self.grid),
+            None => return,
+        };
+        ctx.copy_text(save_load::export_to_rle_string(&stamp.to_rle(self.grid.rule())));
+        self.set_status(format!(
+            "Copied {}x{} selection ({} cells)",
+            stamp.width(),
+            stamp.height(),
+            stamp.population()
+        ));
+        self.clipboard = Some(stamp);
+    }
+
+    /// 复制选区后清除其中的细胞
+    pub fn cut_selection(&mut self, ctx: &egui::Context) {
+        self.copy_selection(ctx);
+        self.edit_selection("Cut", |grid, selection| {
+            selection.clear(grid);
+            Some(selection)
+        });
+    }
+
+    /// 粘贴内部剪贴板中的图案：虚影跟随鼠标，点击放置
+    pub fn paste_clipboard(&mut self) {
+        if let Some(stamp) = self.clipboard.clone() {
+            self.start_placement("clipboard", stamp);
+        }
+    }
+
+    /// 粘贴系统剪贴板中的文本（RLE、plaintext 或 Life 1.05/1.06）
+    pub fn paste_text(&mut self, text: &str) {
+        match save_load::import_pattern_string_with_mode(text, save_load::ParseMode::Lenient) {
+            Ok((pattern, _)) => {
+                let stamp = Stamp::from_rle(&pattern);
+                self.clipboard = Some(stamp.clone());
+                let name = if pattern.name.is_empty() { "clipboard" } else { &pattern.name };
+                self.start_placement(name, stamp);
+            }
+            Err(e) => self.set_status(format!("Clipboard does not contain a pattern: {}", e)),
+        }
+    }
+
+    /// 丢弃无界宇宙的内容，下一步时从网格重新载入
+    fn reset_universe(&mut self) {
+        self.hashlife.clear();
+        self.sparse.clear();
+        self.view_origin = (0, 0);
+        self.synced_revision = None;
+    }
+
+    /// 清空网格
+    pub fn clear_grid(&mut self) {
+        self.history.begin("Clear", &self.grid, self.generation, self.view_origin);
+        self.grid.clear();
+        self.last_soup = None;
+        self.reset_universe();
+        self.generation = 0;
+        self.clear_population_history();
+        self.history.commit(&self.grid, self.generation, self.view_origin);
+    }
+
+    /// 按当前的种子、密度和对称性设置随机化网格（或其中的一个区域）
+    pub fn randomize_grid(&mut self) {
+        let seed = if self.fixed_seed {
+            match self.seed_input.trim().parse() {
+                Ok(seed) => seed,
+                Err(_) => {
+                    self.set_status(format!("Invalid seed: {}", self.seed_input));
+                    return;
+                }
+            }
+        } else {
+            random::seed_from_clock()
+        };
+        self.seed_input = seed.to_string();
+        self.history.begin("Randomize", &self.grid, self.generation, self.view_origin);
+
+        let soup = SoupSettings {
+            seed,
+            density: self.density,
+            symmetry: self.symmetry,
+        };
+        if self.random_region_enabled {
+            // 只修改一个区域：相当于一次编辑，保留代数和统计历史
+            self.grid
+                .randomize_region(seed, soup.density, soup.symmetry, self.random_region);
+            self.last_soup = None;
+            self.update_population_history();
+        } else {
+            let region = (0, 0, self.grid.width(), self.grid.height());
+            self.grid
+                .randomize_region(seed, soup.density, soup.symmetry, region);
+            self.last_soup = Some(soup);
+            self.reset_universe();
+            self.generation = 0;
+            self.clear_population_history();
+            self.update_population_history();
+        }
+        self.set_status(format!("Randomized with seed {} ({})", seed, soup.symmetry));
+        self.history.commit(&self.grid, self.generation, self.view_origin);
+    }
+
+    /// 打开随机汤搜索数据库（由 `game_of_life search` 生成）
+    pub fn open_search_results(&mut self) {
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("Soup Database", &["json"])
+            .pick_file()
+        {
+            match SearchDatabase::load(&path) {
+                Ok(database) => {
+                    self.set_status(format!(
+                        "Loaded {} searched soups from: {:?}",
+                        database.soups_searched, path
+                    ));
+                    self.search_results = Some(database);
+                }
+                Err(e) => self.set_status(format!("Failed to load soup database: {}", e)),
+            }
+        }
+    }
+
+    /// 在网格中央重新生成数据库中的一个随机汤
+    ///
+    /// 同时填好随机汤设置（固定种子、只随机化该区域），之后点击 Random 会得到同一个随机汤
+    pub fn load_soup(&mut self, record: &SoupRecord) {
+        let size = record.soup_size;
+        if size > self.grid.width() || size > self.grid.height() {
+            self.set_status(format!(
+                "Soup {} needs a grid of at least {}x{}",
+                record.seed, size, size
+            ));
+            return;
+        }
+        self.history.begin("Load soup", &self.grid, self.generation, self.view_origin);
+
+        if let Some(rule) = self
+            .search_results
+            .as_ref()
+            .and_then(|database| Rule::parse(&database.rule).ok())
+        {
+            self.grid.set_rule(rule);
+            self.rule_input = rule.to_string();
+        }
+        let region = (
+            (self.grid.width() - size) / 2,
+            (self.grid.height() - size) / 2,
+            size,
+            size,
+        );
+        self.seed_input = record.seed.to_string();
+        self.fixed_seed = true;
+        self.density = record.density;
+        self.symmetry = record.symmetry;
+        self.random_region_enabled = true;
+        self.random_region = region;
+
+        self.grid.clear();
+        self.grid
+            .randomize_region(record.seed, record.density, record.symmetry, region);
+        self.last_soup = None;
+        self.reset_universe();
+        self.generation = 0;
+        self.clear_population_history();
+        self.update_population_history();
+        self.set_status(format!(
+            "Soup {} loaded (settles after {} generations in an unbounded universe)",
+            record.seed, record.lifespan
+        ));
+        self.history.commit(&self.grid, self.generation, self.view_origin);
+    }
+
+    /// 撤销最近一次操作
+    pub fn undo(&mut self) {
+        // 拖动绘制进行中时不撤销，避免打断正在记录的操作
+        if self.history.is_recording() {
+            return;
+        }
+        match self.history.undo(&mut self.grid) {
+            Some((label, generation, origin)) => {
+                self.restore_from_history(generation, origin, format!("Undo: {}", label))
+            }
+            None => self.set_status("Nothing to undo".to_string()),
+        }
+    }
+
+    /// 重做最近一次撤销的操作
+    pub fn redo(&mut self) {
+        if self.history.is_recording() {
+            return;
+        }
+        match self.history.redo(&mut self.grid) {
+            Some((label, generation, origin)) => {
+                self.restore_from_history(generation, origin, format!("Redo: {}", label))
+            }
+            None => self.set_status("Nothing to redo".to_string()),
+        }
+    }
+
+    /// 撤销或重做后同步与网格相关的状态
+    ///
+    /// 使用无界宇宙时窗口回到记录时的位置，宇宙被清空后在下一步从窗口重新载入，
+    /// 避免把过去的窗口写到其他位置或与之后的细胞混在一起
+    fn restore_from_history(&mut self, generation: u64, origin: (i64, i64), message: String) {
+        if self.algorithm.is_unbounded() {
+            self.reset_universe();
+        }
+        self.view_origin = origin;
+        self.generation = generation;
+        self.grid_width = self.grid.width();
+        self.grid_height = self.grid.height();
+        self.rule_input = self.grid.rule().to_string();
+        self.synced_revision = None;
+        self.set_status(message);
+    }
+
+    /// 切换边界拓扑
+    pub fn apply_topology(&mut self, topology: Topology) {
+        match self.grid.set_topology(topology) {
+            Ok(()) => self.set_status(format!("Boundary set to {}", topology.name())),
+            Err(e) => self.set_status(e),
+        }
+    }
+
+    /// 更新人口统计历史
+    fn update_population_history(&mut self) {
+        let current_population = self.get_current_population();
+        self.statistics.add_population(current_population);
+    }
+
+    /// 清除人口统计历史
+    pub fn clear_population_history(&mut self) {
+        self.statistics.clear_history();
+    }
+
+    /// 获取当前活细胞数量
+    ///
+    /// 使用无界宇宙且宇宙与网格同步时，统计整个宇宙（包括窗口之外）的细胞
+    pub fn get_current_population(&self) -> usize {
+        match self.unbounded_engine() {
+            Some(engine) if self.synced_revision == Some(self.grid.revision()) => {
+                engine.count_alive_cells()
+            }
+            _ => self.grid.count_alive_cells(),
+        }
+    }
+
+    /// 获取人口历史记录的引用
+    pub fn get_population_history(&self) -> &Vec<usize> {
+        self.statistics.get_history()
+    }
+
+    /// 获取统计显示状态
+    pub fn show_statistics(&self) -> bool {
+        self.statistics.is_statistics_visible()
+    }
+
+    /// 设置统计显示状态
+    pub fn set_show_statistics(&mut self, show: bool) {
+        self.statistics.set_statistics_visible(show);
+    }
+
+}
+
+/// 为GameOfLifeApp实现eframe::App trait
+/// 这是egui应用程序的核心接口
+impl eframe::App for GameOfLifeApp {
+    /// 应用程序的主更新函数，每帧都会被调用
+    ///
+    /// # 参数
+    /// * `ctx` - egui上下文，用于创建UI和控制重绘
+    /// * `_frame` - 窗口框架信息（本例中未使用）
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // 更新主题切换动画
+        self.update_theme_transition();
+
+        // 应用UI主题
+        self.set_ui_theme(ctx);
+
+        // 更新状态信息（清除过期的状态）
+        self.update_status();
+
+        // 处理键盘快捷键（文本框获得焦点时不处理，避免输入规则时触发快捷键）
+        let text_input_focused = ctx.wants_keyboard_input();
+        ctx.input(|i| {
+            if text_input_focused {
+                return;
+            }
+
+            // 放置图案时：R/Shift+R 旋转，F/V 翻转，Esc 结束放置
+            let placing = self.placing.is_some();
+            if placing {
+                if i.key_pressed(egui::Key::Escape) {
+                    self.cancel_placement();
+                }
+                if i.key_pressed(egui::Key::R) {
+                    if i.modifiers.shift {
+                        self.stamp_transform.rotate_counterclockwise();
+                    } else {
+                        self.stamp_transform.rotate_clockwise();
+                    }
+                }
+                if i.key_pressed(egui::Key::F) {
+                    self.stamp_transform.flip_horizontal();
+                }
+                if i.key_pressed(egui::Key::V) {
+                    self.stamp_transform.flip_vertical();
+                }
+            }
+
+            // 有选区时：Delete 清除选区内容，方向键平移，Esc 取消选区
+            if self.selection.is_some() && !placing {
+                if i.key_pressed(egui::Key::Delete) || i.key_pressed(egui::Key::Backspace) {
+                    self.edit_selection("Clear selection", |grid, selection| {
+                        selection.clear(grid);
+                        Some(selection)
+                    });
+                }
+                for (key, dx, dy) in [
+                    (egui::Key::ArrowLeft, -1, 0),
+                    (egui::Key::ArrowRight, 1, 0),
+                    (egui::Key::ArrowUp, 0, -1),
+                    (egui::Key::ArrowDown, 0, 1),
+                ] {
+                    if i.key_pressed(key) {
+                        self.edit_selection("Shift selection", |grid, selection| {
+                            selection.shift(grid, dx, dy)
+                        });
+                    }
+                }
+                if i.key_pressed(egui::Key::Escape) {
+                    self.selection = None;
+                }
+            }
+
+            // T - 切换主题
+            if i.key_pressed(egui::Key::T) {
+                self.theme_manager.toggle_theme();
+            }
+            
+            // Space - 开始/暂停（松开时切换，按住空格拖动平移视口时不切换）
+            if i.key_released(egui::Key::Space) && !self.ui_state.take_space_panned() {
+                self.is_running = !self.is_running;
+                self.last_update = std::time::Instant::now();
+            }
+
+            // Home - 缩放到所有存活细胞
+            if i.key_pressed(egui::Key::Home) {
+                self.fit_pattern_to_window();
+            }
+            
+            // S - 单步执行
+            if i.key_pressed(egui::Key::S) {
+                self.step_simulation();
+            }
+            
+            // C - 清空网格
+            if i.key_pressed(egui::Key::C) {
+                self.clear_grid();
+            }
+            
+            // R - 随机化（放置图案时 R 用于旋转）
+            if i.key_pressed(egui::Key::R) && self.placing.is_none() {
+                self.randomize_grid();
+            }
+            
+            // Ctrl+Z - 撤销，Ctrl+Shift+Z / Ctrl+Y - 重做
+            if i.modifiers.ctrl && i.key_pressed(egui::Key::Z) {
+                if i.modifiers.shift {
+                    self.redo();
+                } else {
+                    self.undo();
+                }
+            }
+            if i.modifiers.ctrl && i.key_pressed(egui::Key::Y) {
+                self.redo();
+            }
+
+            // Ctrl+S - 保存
+            if i.modifiers.ctrl && i.key_pressed(egui::Key::S) {
+                self.save_game();
+            }
+            
+            // Ctrl+O - 加载
+            if i.modifiers.ctrl && i.key_pressed(egui::Key::O) {
+                self.load_game();
+            }
+        });
+
+        // 剪贴板：Ctrl+C / Ctrl+X 以 RLE 文本复制选区，Ctrl+V 粘贴系统剪贴板中的图案
+        if !text_input_focused {
+            let events = ctx.input(|i| i.events.clone());
+            for event in events {
+                match event {
+                    egui::Event::Copy => self.copy_selection(ctx),
+                    egui::Event::Cut => self.cut_selection(ctx),
+                    egui::Event::Paste(text) => self.paste_text(&text),
+                    _ => {}
+                }
+            }
+        }
+
+        // 检查是否需要自动更新游戏状态
+        if self.is_running && self.last_update.elapsed() >= self.update_interval {
+            self.run_step(); // 计算下一步、更新人口统计并检查断点
+            self.last_update = std::time::Instant::now(); // 更新时间戳
+            ctx.request_repaint(); // 请求重绘界面
+        }
+
+        // 创建左侧控制面板
+        egui::SidePanel::left("controls").show(ctx, |ui| {
+            self.render_control_panel(ui);
+        });
+
+        // 创建右侧统计面板（仅在显示统计时）
+        if self.show_statistics() {
+            egui::SidePanel::right("statistics").show(ctx, |ui| {
+                self.render_statistics_panel(ui);
+            });
+        }
+
+        // 创建网格下方的时间线面板
+        egui::TopBottomPanel::bottom("timeline").show(ctx, |ui| {
+            self.render_timeline_panel(ui);
+        });
+
+        // 创建中央面板用于显示游戏网格
+        egui::CentralPanel::default().show(ctx, |ui| {
+            self.render_game_grid(ui);
+        });
+
+        // 如果游戏正在运行，请求在下一个更新间隔后重绘
+        if self.is_running {
+            ctx.request_repaint_after(self.update_interval);
+        }
+
+        // 如果正在进行主题切换动画，请求持续重绘
+        if self.theme_manager.is_transitioning() {
+            ctx.request_repaint();
+        }
+    }
+}
+
+/// 打开窗口并运行图形界面
+pub fn run() -> Result<(), eframe::Error> {
+    // 配置应用程序窗口选项
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_inner_size([800.0, 600.0]) // 设置窗口初始大小
+            .with_title("Conway's Game of Life"), // 设置窗口标题
+        ..Default::default()
+    };
+
+    // 启动native应用程序
+    eframe::run_native(
+        "Conway's Game of Life",                                // 应用程序名称
+        options,                                                // 窗口配置选项
+        Box::new(|_cc| Ok(Box::new(GameOfLifeApp::default()))), // 创建应用程序实例的闭包
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game_of_life::patterns::spaceships;
+
+    /// 20x20 窗口右下角放一个滑翔机，使用 HashLife 并开启窗口跟随
+    fn glider_app() -> GameOfLifeApp {
+        let mut app = GameOfLifeApp {
+            grid: Grid::new(20, 20),
+            ..GameOfLifeApp::default()
+        };
+        app.grid.load_pattern(spaceships::GLIDER.data, 15, 15);
+        app.apply_algorithm(Algorithm::HashLife);
+        app.step_exponent = 2;
+        app.follow_live_region = true;
+        app
+    }
+
+    /// 推进直到窗口跟随滑翔机移动
+    fn step_until_window_moves(app: &mut GameOfLifeApp) {
+        let origin = app.view_origin;
+        for _ in 0..20 {
+            app.step_simulation();
+            if app.view_origin != origin {
+                return;
+            }
+        }
+        panic!("window never followed the glider");
+    }
+
+    #[test]
+    fn test_undo_step_restores_window_origin() {
+        let mut app = glider_app();
+        step_until_window_moves(&mut app);
+        let (moved_grid, moved_origin) = (app.grid.clone(), app.view_origin);
+
+        app.undo();
+        step_until_window_moves(&mut app);
+        // 撤销后重新推进得到相同的窗口，宇宙中没有重复或错位的细胞
+        assert_eq!(app.view_origin, moved_origin);
+        assert!(app.grid.diff(&moved_grid).unwrap().is_empty());
+        assert_eq!(app.hashlife.population(), 5);
+
+        app.undo();
+        app.redo();
+        assert_eq!(app.view_origin, moved_origin);
+        app.step_simulation();
+        assert_eq!(app.hashlife.population(), 5);
+    }
+
+    #[test]
+    fn test_rewind_restores_window_origin() {
+        let mut app = glider_app();
+        let start_origin = app.view_origin;
+        step_until_window_moves(&mut app);
+        let (moved_grid, moved_origin) = (app.grid.clone(), app.view_origin);
+        for _ in 0..5 {
+            app.step_simulation();
+        }
+
+        // 回到第一帧后窗口回到原来的位置，重新推进得到相同的结果
+        app.jump_to_frame(0);
+        assert_eq!((app.generation, app.view_origin), (0, start_origin));
+        step_until_window_moves(&mut app);
+        assert_eq!(app.view_origin, moved_origin);
+        assert!(app.grid.diff(&moved_grid).unwrap().is_empty());
+        assert_eq!(app.hashlife.population(), 5);
+    }
+}
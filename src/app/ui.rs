@@ -0,0 +1,1503 @@
+/// UI组件模块
+/// 包含所有用户界面相关的渲染和交互逻辑
+use super::CellState;
+use super::{
+    hashlife, patterns, rules, Algorithm, Category, CensusColumn, ColorTheme, GameOfLifeApp, Rule,
+    Selection, Stamp, StampMode, Symmetry, Tool, Topology,
+};
+use eframe::egui;
+
+/// 控制面板相关的UI渲染
+impl GameOfLifeApp {
+    /// 渲染左侧控制面板
+    pub fn render_control_panel(&mut self, ui: &mut egui::Ui) {
+        // 添加垂直滚动区域包装整个控制面板
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                ui.heading("Conway's Game of Life");
+
+                // 显示当前迭代次数
+                ui.label(format!("Generation: {}", self.generation));
+                if let Some(soup) = self.last_soup {
+                    ui.label(
+                        egui::RichText::new(format!("Soup seed: {} ({})", soup.seed, soup.symmetry))
+                            .small()
+                            .color(egui::Color32::GRAY),
+                    );
+                }
+                
+                // 显示控制提示
+                ui.label(egui::RichText::new("🎮 Controls:")
+                        .size(11.0)
+                        .strong());
+                ui.label(egui::RichText::new("Space: Play/Pause | S: Step | C: Clear | R: Random")
+                        .size(9.0)
+                        .color(egui::Color32::GRAY));
+                ui.label(egui::RichText::new("T: Theme | Ctrl+S: Save | Ctrl+O: Load")
+                        .size(9.0)
+                        .color(egui::Color32::GRAY));
+                ui.label(egui::RichText::new("Ctrl+Z: Undo | Ctrl+Shift+Z: Redo")
+                        .size(9.0)
+                        .color(egui::Color32::GRAY));
+                ui.label(egui::RichText::new("Ctrl+Scroll: Zoom | Drag: Draw")
+                        .size(9.0)
+                        .color(egui::Color32::GRAY));
+                ui.label(egui::RichText::new("Middle/Space+Drag: Pan | Home: Fit")
+                        .size(9.0)
+                        .color(egui::Color32::GRAY));
+                ui.separator();
+
+                // 游戏控制区域 默认展开
+                egui::CollapsingHeader::new("Game Controls")
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        self.render_game_controls(ui);
+                    });
+
+                ui.add_space(5.0);
+
+                // 视觉设置区域
+                ui.collapsing("Visual Settings", |ui| {
+                    self.render_visual_settings(ui);
+                });
+
+                ui.add_space(5.0);
+
+                // 模拟设置区域
+                ui.collapsing("Simulation Settings", |ui| {
+                    self.render_simulation_settings(ui);
+                });
+
+                ui.add_space(5.0);
+
+                // 断点区域
+                ui.collapsing("Breakpoints", |ui| {
+                    self.render_breakpoint_settings(ui);
+                });
+
+                ui.add_space(5.0);
+
+                // 编辑工具和选区操作区域
+                ui.collapsing("Editing", |ui| {
+                    self.render_editing_panel(ui);
+                });
+
+                ui.add_space(5.0);
+
+                // 预设图案区域
+                ui.collapsing("Pattern Presets", |ui| {
+                    self.render_presets_panel(ui);
+                });
+
+                ui.add_space(5.0);
+
+                // 随机汤搜索结果区域
+                ui.collapsing("Soup Search", |ui| {
+                    self.render_soup_search(ui);
+                });
+
+                ui.add_space(5.0);
+
+                // 统计信息区域
+                ui.collapsing("Statistics", |ui| {
+                    self.render_statistics_controls(ui);
+                });
+
+                // 在面板底部添加一些额外空间
+                ui.add_space(20.0);
+            });
+    }
+
+    /// 渲染游戏控制按钮
+    pub fn render_game_controls(&mut self, ui: &mut egui::Ui) {
+        // 游戏控制按钮（水平布局）
+        ui.horizontal(|ui| {
+            // 开始/暂停按钮
+            if ui
+                .button(if self.is_running { "Pause" } else { "Start" })
+                .clicked()
+            {
+                self.is_running = !self.is_running;
+                self.last_update = std::time::Instant::now();
+            }
+
+            // 单步执行按钮
+            if ui.button("Step").clicked() {
+                self.step_simulation();
+            }
+        });
+
+        ui.add_space(5.0);
+
+        // 撤销/重做按钮（水平布局）
+        ui.horizontal(|ui| {
+            let undo_label = self.history.undo_label();
+            let undo = ui
+                .add_enabled(undo_label.is_some(), egui::Button::new("Undo"))
+                .on_hover_text(format!("Undo {}", undo_label.unwrap_or("")));
+            if undo.clicked() {
+                self.undo();
+            }
+
+            let redo_label = self.history.redo_label();
+            let redo = ui
+                .add_enabled(redo_label.is_some(), egui::Button::new("Redo"))
+                .on_hover_text(format!("Redo {}", redo_label.unwrap_or("")));
+            if redo.clicked() {
+                self.redo();
+            }
+        });
+
+        ui.add_space(5.0);
+
+        // 网格操作按钮（水平布局）
+        ui.horizontal(|ui| {
+            // 清空网格按钮
+            if ui.button("Clear").clicked() {
+                self.clear_grid();
+            }
+
+            // 随机化网格按钮
+            if ui.button("Random").clicked() {
+                self.randomize_grid();
+            }
+        });
+
+        ui.add_space(5.0);
+
+        // 文件操作按钮（水平布局）
+        ui.horizontal(|ui| {
+            // 保存按钮
+            if ui.button("Save").clicked() {
+                self.save_game();
+            }
+
+            // 加载按钮
+            if ui.button("Load").clicked() {
+                self.load_game();
+            }
+        });
+
+        // 显示保存/加载状态信息
+        if let Some(status) = self.ui_state.status_message() {
+            ui.add_space(5.0);
+            ui.label(egui::RichText::new(status).small().color(egui::Color32::GRAY));
+        }
+    }
+
+    /// 渲染视觉设置面板
+    pub fn render_visual_settings(&mut self, ui: &mut egui::Ui) {
+        // 颜色主题选择
+        ui.label("Color Theme:");
+        ui.horizontal(|ui| {
+            let current_theme = self.theme_manager.current_theme();
+            if ui.selectable_label(current_theme == ColorTheme::Light, "Light").clicked() {
+                self.start_theme_transition(ColorTheme::Light);
+            }
+            if ui.selectable_label(current_theme == ColorTheme::Dark, "Dark").clicked() {
+                self.start_theme_transition(ColorTheme::Dark);
+            }
+        });
+
+        ui.add_space(5.0);
+
+        // 网格线显示开关
+        let mut show_grid_lines = self.ui_state.show_grid_lines();
+        if ui.checkbox(&mut show_grid_lines, "Show Grid Lines").changed() {
+            self.ui_state.set_show_grid_lines(show_grid_lines);
+        }
+
+        ui.add_space(5.0);
+
+        // 缩放控制
+        let zoom_level = self.ui_state.zoom_level();
+        ui.label(format!("Zoom Level: {:.1}x", zoom_level));
+        let mut new_zoom = zoom_level;
+        if ui
+            .add(egui::Slider::new(&mut new_zoom, 0.1..=5.0).text("Zoom"))
+            .changed()
+        {
+            // 滑块缩放以视口中心为锚点
+            self.ui_state.handle_zoom(new_zoom - zoom_level, None);
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button("Fit Pattern").on_hover_text("Home").clicked() {
+                self.fit_pattern_to_window();
+            }
+            if ui
+                .add_enabled(self.selection.is_some(), egui::Button::new("Zoom to Selection"))
+                .clicked()
+            {
+                self.zoom_to_selection();
+            }
+        });
+
+        // 重置缩放和平移按钮
+        if ui.button("Reset View").clicked() {
+            self.ui_state.reset_view();
+        }
+        ui.label(
+            egui::RichText::new("Scroll or middle-drag / Space+drag to pan")
+                .small()
+                .color(egui::Color32::GRAY),
+        );
+    }
+
+    /// 渲染模拟设置面板
+    pub fn render_simulation_settings(&mut self, ui: &mut egui::Ui) {
+        // 更新速度调节滑块
+        ui.label("Update Speed (FPS):");
+        if ui
+            .add(egui::Slider::new(&mut self.update_speed, 1.0..=30.0))
+            .changed()
+        {
+            // 当速度改变时，重新计算更新间隔
+            self.update_interval =
+                std::time::Duration::from_millis((1000.0 / self.update_speed) as u64);
+        }
+
+        ui.add_space(5.0);
+
+        // 网格尺寸调节滑块
+        ui.label("Grid Width:");
+        ui.add(egui::Slider::new(&mut self.grid_width, 10..=200));
+
+        ui.label("Grid Height:");
+        ui.add(egui::Slider::new(&mut self.grid_height, 10..=150));
+
+        ui.add_space(5.0);
+
+        // 演化算法选择
+        self.render_algorithm_settings(ui);
+
+        ui.add_space(5.0);
+
+        // 演化规则选择
+        self.render_rule_settings(ui);
+
+        ui.add_space(5.0);
+
+        // 边界拓扑选择
+        ui.label("Boundary:");
+        let current_topology = self.grid.topology();
+        egui::ComboBox::from_id_source("boundary_topology")
+            .selected_text(current_topology.name())
+            .show_ui(ui, |ui| {
+                for topology in Topology::ALL {
+                    if ui
+                        .selectable_label(current_topology == topology, topology.name())
+                        .clicked()
+                    {
+                        self.apply_topology(topology);
+                    }
+                }
+            });
+
+        ui.add_space(5.0);
+
+        // 演化线程数调节滑块
+        ui.label("Threads:");
+        let max_threads = std::thread::available_parallelism().map_or(1, |n| n.get()).max(1);
+        let mut threads = self.grid.threads();
+        if ui
+            .add(egui::Slider::new(&mut threads, 1..=max_threads))
+            .on_hover_text("Rows are split into bands stepped in parallel on large grids")
+            .changed()
+        {
+            self.grid.set_threads(threads);
+        }
+
+        ui.add_space(5.0);
+
+        // 撤销历史的内存预算
+        ui.label("Undo Memory (MB):");
+        let mut budget_mb = self.history.budget() / (1024 * 1024);
+        if ui
+            .add(egui::Slider::new(&mut budget_mb, 1..=1024))
+            .on_hover_text("Oldest undo steps are dropped when history exceeds this budget")
+            .changed()
+        {
+            self.history.set_budget(budget_mb * 1024 * 1024);
+        }
+        ui.label(
+            egui::RichText::new(format!(
+                "{} undo steps (~{:.1} MB)",
+                self.history.undo_count(),
+                self.history.memory_usage() as f64 / (1024.0 * 1024.0)
+            ))
+            .small()
+            .color(egui::Color32::GRAY),
+        );
+
+        ui.add_space(5.0);
+
+        // 随机密度调节滑块
+        ui.label("Random Density:");
+        ui.add(egui::Slider::new(&mut self.density, 0.0..=1.0));
+
+        ui.add_space(5.0);
+
+        // 随机汤设置：种子、对称性和区域
+        self.render_soup_settings(ui);
+
+        ui.add_space(10.0);
+
+        // 应用网格设置按钮
+        if ui.button("Apply Grid Settings").clicked() {
+            // 创建新的网格并随机化（保留当前规则、边界拓扑和线程设置）
+            self.history.begin("Resize grid", &self.grid, self.generation, self.view_origin);
+            let topology = self.grid.topology();
+            self.grid = self.grid.new_like(self.grid_width, self.grid_height);
+            if self.grid.topology() != topology {
+                self.set_status(format!(
+                    "{} boundary requires a square grid, switched to Bounded",
+                    topology.name()
+                ));
+            }
+            self.randomize_grid();
+            self.history.commit(&self.grid, self.generation, self.view_origin);
+        }
+    }
+
+    /// 渲染随机汤设置（种子、对称性、随机化区域）
+    pub fn render_soup_settings(&mut self, ui: &mut egui::Ui) {
+        ui.label("Random Seed:");
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.seed_input).desired_width(140.0));
+            ui.checkbox(&mut self.fixed_seed, "Fixed")
+                .on_hover_text("Reuse this seed instead of drawing a new one on every randomize");
+        });
+
+        ui.label("Symmetry:");
+        egui::ComboBox::from_id_source("soup_symmetry")
+            .selected_text(self.symmetry.name())
+            .show_ui(ui, |ui| {
+                for symmetry in Symmetry::ALL {
+                    ui.selectable_value(&mut self.symmetry, symmetry, symmetry.name());
+                }
+            });
+
+        ui.checkbox(&mut self.random_region_enabled, "Randomize region only");
+        if self.random_region_enabled {
+            let (width, height) = (self.grid.width(), self.grid.height());
+            let (x, y, w, h) = &mut self.random_region;
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(x).prefix("x: ").range(0..=width));
+                ui.add(egui::DragValue::new(y).prefix("y: ").range(0..=height));
+            });
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(w).prefix("w: ").range(1..=width));
+                ui.add(egui::DragValue::new(h).prefix("h: ").range(1..=height));
+            });
+        }
+    }
+
+    /// 渲染随机汤搜索数据库：按类别列出记录，点击 Load 重新生成
+    pub fn render_soup_search(&mut self, ui: &mut egui::Ui) {
+        if ui
+            .button("Open Results...")
+            .on_hover_text("Open a database written by `game_of_life search`")
+            .clicked()
+        {
+            self.open_search_results();
+        }
+
+        let database = match &self.search_results {
+            Some(database) => database,
+            None => {
+                ui.label(
+                    egui::RichText::new("No soup database loaded")
+                        .small()
+                        .color(egui::Color32::GRAY),
+                );
+                return;
+            }
+        };
+        ui.label(format!(
+            "{} soups searched ({}), next seed {}",
+            database.soups_searched, database.rule, database.next_seed
+        ));
+
+        let mut selected = None;
+        egui::ScrollArea::vertical()
+            .id_source("soup_search_results")
+            .max_height(250.0)
+            .show(ui, |ui| {
+                for category in Category::ALL {
+                    let records = database.records(category);
+                    if records.is_empty() {
+                        continue;
+                    }
+                    ui.label(egui::RichText::new(category.name()).strong());
+                    egui::Grid::new(("soup_search_grid", category.name()))
+                        .striped(true)
+                        .num_columns(3)
+                        .show(ui, |ui| {
+                            for record in records {
+                                ui.label(format!("seed {}", record.seed));
+                                let objects = if record.rare_objects.is_empty() {
+                                    format!("{} gens", record.lifespan)
+                                } else {
+                                    format!(
+                                        "{} gens, {}",
+                                        record.lifespan,
+                                        record.rare_objects.join(" ")
+                                    )
+                                };
+                                ui.label(egui::RichText::new(objects).small());
+                                if ui.small_button("Load").clicked() {
+                                    selected = Some(record.clone());
+                                }
+                                ui.end_row();
+                            }
+                        });
+                }
+            });
+
+        if let Some(record) = selected {
+            self.load_soup(&record);
+        }
+    }
+
+    /// 渲染演化算法选择控件
+    pub fn render_algorithm_settings(&mut self, ui: &mut egui::Ui) {
+        ui.label("Algorithm:");
+        let current_algorithm = self.algorithm;
+        egui::ComboBox::from_id_source("algorithm")
+            .selected_text(current_algorithm.name())
+            .show_ui(ui, |ui| {
+                for algorithm in Algorithm::ALL {
+                    if ui
+                        .selectable_label(current_algorithm == algorithm, algorithm.name())
+                        .clicked()
+                    {
+                        self.apply_algorithm(algorithm);
+                    }
+                }
+            });
+
+        if !self.algorithm.is_unbounded() {
+            return;
+        }
+
+        // 窗口位置与跟随设置
+        ui.label(
+            egui::RichText::new(format!(
+                "Window origin: ({}, {})",
+                self.view_origin.0, self.view_origin.1
+            ))
+            .small()
+            .color(egui::Color32::GRAY),
+        );
+        let live_region = match self.unbounded_engine().and_then(|engine| engine.bounding_box()) {
+            Some(bounds) => format!("Live region: {}x{}", bounds.width(), bounds.height()),
+            None => "Live region: empty".to_string(),
+        };
+        ui.label(egui::RichText::new(live_region).small().color(egui::Color32::GRAY));
+        ui.checkbox(&mut self.follow_live_region, "Follow live region")
+            .on_hover_text("Move the window when live cells leave it");
+
+        if self.algorithm != Algorithm::HashLife {
+            ui.label(
+                egui::RichText::new(format!("Tiles: {}", self.sparse.tile_count()))
+                    .small()
+                    .color(egui::Color32::GRAY),
+            );
+            return;
+        }
+
+        // 每步推进的代数：2^k
+        ui.label(format!("Step: 2^{} generations", self.step_exponent));
+        ui.add(
+            egui::Slider::new(&mut self.step_exponent, 0..=hashlife::MAX_STEP_EXPONENT)
+                .text("k"),
+        );
+
+        // 内存上限（MB），超过后进行垃圾回收
+        let mut limit_mb = self.hashlife.memory_limit() / (1024 * 1024);
+        if ui
+            .add(egui::Slider::new(&mut limit_mb, 16..=4096).text("MB limit"))
+            .on_hover_text("Unreachable nodes are collected when the estimate exceeds the limit")
+            .changed()
+        {
+            self.hashlife.set_memory_limit(limit_mb * 1024 * 1024);
+        }
+        ui.label(
+            egui::RichText::new(format!(
+                "Nodes: {} (~{:.1} MB)",
+                self.hashlife.node_count(),
+                self.hashlife.memory_usage() as f64 / (1024.0 * 1024.0)
+            ))
+            .small()
+            .color(egui::Color32::GRAY),
+        );
+    }
+
+    /// 渲染规则选择控件
+    pub fn render_rule_settings(&mut self, ui: &mut egui::Ui) {
+        ui.label("Rule:");
+
+        // 预设规则下拉框
+        let current_rule = *self.grid.rule();
+        let selected_text = match current_rule.preset_name() {
+            Some(name) => format!("{} ({})", name, current_rule),
+            None => format!("Custom ({})", current_rule),
+        };
+        egui::ComboBox::from_id_source("rule_presets")
+            .selected_text(selected_text)
+            .show_ui(ui, |ui| {
+                for (name, notation) in rules::RULE_PRESETS {
+                    if let Ok(rule) = Rule::parse(notation) {
+                        let label = format!("{} ({})", name, notation);
+                        if ui.selectable_label(current_rule == rule, label).clicked() {
+                            self.apply_rule(rule);
+                        }
+                    }
+                }
+            });
+
+        // 自定义规则输入框
+        ui.horizontal(|ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.rule_input)
+                    .desired_width(100.0)
+                    .hint_text("B3/S23"),
+            );
+            let submitted =
+                response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if ui.button("Apply Rule").clicked() || submitted {
+                match Rule::parse(&self.rule_input) {
+                    Ok(rule) => self.apply_rule(rule),
+                    Err(e) => self.set_status(format!("Invalid rule: {}", e)),
+                }
+            }
+        });
+    }
+
+    /// 渲染编辑工具选择和选区操作
+    pub fn render_editing_panel(&mut self, ui: &mut egui::Ui) {
+        ui.label("Tool:");
+        ui.horizontal_wrapped(|ui| {
+            let current_tool = self.ui_state.tool();
+            for tool in Tool::ALL {
+                if ui.selectable_label(current_tool == tool, tool.name()).clicked() {
+                    self.ui_state.set_tool(tool);
+                }
+            }
+        });
+        match self.ui_state.tool() {
+            Tool::Draw => {
+                let mut brush_size = self.ui_state.brush_size();
+                if ui
+                    .add(egui::Slider::new(&mut brush_size, 1..=16).text("Brush size"))
+                    .changed()
+                {
+                    self.ui_state.set_brush_size(brush_size);
+                }
+            }
+            Tool::Shape(_) => {
+                ui.label(
+                    egui::RichText::new("Drag to draw, right-drag to erase")
+                        .small()
+                        .color(egui::Color32::GRAY),
+                );
+            }
+            Tool::FloodFill => {
+                ui.label(
+                    egui::RichText::new("Click to flip the connected region under the cursor")
+                        .small()
+                        .color(egui::Color32::GRAY),
+                );
+            }
+            Tool::Select => {}
+        }
+        ui.separator();
+
+        let selection = self.selection;
+        match selection {
+            Some(selection) => ui.label(format!(
+                "Selection: {}x{} at ({}, {})",
+                selection.width, selection.height, selection.x, selection.y
+            )),
+            None => ui.label(
+                egui::RichText::new("Drag with the Select tool to select a rectangle")
+                    .small()
+                    .color(egui::Color32::GRAY),
+            ),
+        };
+        ui.horizontal(|ui| {
+            if ui.button("Select All").clicked() {
+                self.selection = Some(Selection::whole(&self.grid));
+            }
+            if ui.add_enabled(selection.is_some(), egui::Button::new("Deselect")).clicked() {
+                self.selection = None;
+            }
+        });
+
+        ui.add_enabled_ui(selection.is_some(), |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("Copy").on_hover_text("Ctrl+C").clicked() {
+                    self.copy_selection(ui.ctx());
+                }
+                if ui.button("Cut").on_hover_text("Ctrl+X").clicked() {
+                    self.cut_selection(ui.ctx());
+                }
+            });
+            ui.horizontal(|ui| {
+                if ui.button("Clear").on_hover_text("Delete").clicked() {
+                    self.edit_selection("Clear selection", |grid, selection| {
+                        selection.clear(grid);
+                        Some(selection)
+                    });
+                }
+                if ui.button("Clear Outside").clicked() {
+                    self.edit_selection("Clear outside", |grid, selection| {
+                        selection.clear_outside(grid);
+                        Some(selection)
+                    });
+                }
+            });
+            ui.horizontal(|ui| {
+                if ui.button("Fill").clicked() {
+                    self.edit_selection("Fill selection", |grid, selection| {
+                        selection.fill(grid);
+                        Some(selection)
+                    });
+                }
+                if ui
+                    .button("Random Fill")
+                    .on_hover_text("Uses the density from the simulation settings")
+                    .clicked()
+                {
+                    self.random_fill_selection();
+                }
+            });
+            ui.horizontal(|ui| {
+                if ui.button("⟲").on_hover_text("Rotate 90° counterclockwise").clicked() {
+                    self.edit_selection("Rotate selection", |grid, selection| {
+                        selection.rotate_counterclockwise(grid)
+                    });
+                }
+                if ui.button("⟳").on_hover_text("Rotate 90° clockwise").clicked() {
+                    self.edit_selection("Rotate selection", |grid, selection| {
+                        selection.rotate_clockwise(grid)
+                    });
+                }
+                if ui.button("↔").on_hover_text("Flip horizontally").clicked() {
+                    self.edit_selection("Flip selection", |grid, selection| {
+                        selection.flip_horizontal(grid);
+                        Some(selection)
+                    });
+                }
+                if ui.button("↕").on_hover_text("Flip vertically").clicked() {
+                    self.edit_selection("Flip selection", |grid, selection| {
+                        selection.flip_vertical(grid);
+                        Some(selection)
+                    });
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("Shift:");
+                for (label, dx, dy) in [("←", -1, 0), ("→", 1, 0), ("↑", 0, -1), ("↓", 0, 1)] {
+                    if ui.button(label).on_hover_text("Arrow keys").clicked() {
+                        self.edit_selection("Shift selection", |grid, selection| {
+                            selection.shift(grid, dx, dy)
+                        });
+                    }
+                }
+            });
+        });
+
+        if ui
+            .add_enabled(self.clipboard.is_some(), egui::Button::new("Paste"))
+            .on_hover_text("Ctrl+V pastes RLE or plaintext from the system clipboard")
+            .clicked()
+        {
+            self.paste_clipboard();
+        }
+    }
+
+    /// 渲染图案放置设置（合并方式、旋转镜像和位置），预设按钮和加载图案文件都使用这些设置
+    pub fn render_stamp_settings(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Merge:");
+            egui::ComboBox::from_id_source("stamp_mode")
+                .selected_text(self.stamp_mode.name())
+                .show_ui(ui, |ui| {
+                    for mode in StampMode::ALL {
+                        ui.selectable_value(&mut self.stamp_mode, mode, mode.name());
+                    }
+                });
+        });
+
+        ui.horizontal(|ui| {
+            if ui.button("⟲").on_hover_text("Rotate 90° counterclockwise").clicked() {
+                self.stamp_transform.rotate_counterclockwise();
+            }
+            if ui.button("⟳").on_hover_text("Rotate 90° clockwise").clicked() {
+                self.stamp_transform.rotate_clockwise();
+            }
+            if ui.button("↔").on_hover_text("Flip horizontally").clicked() {
+                self.stamp_transform.flip_horizontal();
+            }
+            if ui.button("↕").on_hover_text("Flip vertically").clicked() {
+                self.stamp_transform.flip_vertical();
+            }
+            ui.label(self.stamp_transform.describe());
+        });
+
+        ui.checkbox(&mut self.stamp_centered, "Load files at grid centre");
+        if !self.stamp_centered {
+            let (width, height) = (self.grid.width(), self.grid.height());
+            let (x, y) = &mut self.stamp_position;
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(x).prefix("x: ").range(0..=width.saturating_sub(1)));
+                ui.add(egui::DragValue::new(y).prefix("y: ").range(0..=height.saturating_sub(1)));
+            });
+        }
+        ui.label(
+            egui::RichText::new("Presets follow the mouse: click to stamp, R/F/V to transform, Esc to finish")
+                .small()
+                .italics(),
+        );
+    }
+
+    /// 渲染预设面板
+    pub fn render_presets_panel(&mut self, ui: &mut egui::Ui) {
+        self.render_stamp_settings(ui);
+        ui.separator();
+
+        // 直接渲染预设列表，不需要单独的滚动区域
+        // 因为整个控制面板已经有滚动了
+        for (category_name, patterns) in patterns::get_all_patterns() {
+            ui.collapsing(category_name, |ui| {
+                for pattern in patterns {
+                    if ui.button(pattern.name).clicked() {
+                        self.start_placement(pattern.name, Stamp::from_lines(pattern.data));
+                    }
+                    // 显示图案描述
+                    ui.label(egui::RichText::new(pattern.description).small().italics());
+                    ui.add_space(3.0);
+                }
+            });
+        }
+    }
+}
+
+/// 游戏网格相关的UI渲染
+impl GameOfLifeApp {
+    /// 渲染游戏网格并处理鼠标交互
+    ///
+    /// 网格绘制在整个中央区域中，位置由 `UiStateManager` 中的视口偏移和缩放决定
+    pub fn render_game_grid(&mut self, ui: &mut egui::Ui) {
+        // 分配整个可用区域作为视口
+        let (response, painter) = ui.allocate_painter(
+            ui.available_size(),
+            egui::Sense::click_and_drag(), // 允许鼠标点击和拖动交互
+        );
+        let viewport = response.rect;
+        self.ui_state.set_viewport_size(viewport.size());
+
+        if response.hovered() {
+            let (ctrl_pressed, scroll_delta, smooth_scroll) =
+                ui.input(|i| (i.modifiers.ctrl, i.raw_scroll_delta.y, i.smooth_scroll_delta));
+            if ctrl_pressed && scroll_delta != 0.0 {
+                // Ctrl + 鼠标滚轮：以鼠标所在位置为中心缩放
+                let mouse_pos = response.hover_pos().map(|pos| (pos - viewport.min).to_pos2());
+                self.handle_zoom(scroll_delta * 0.001, mouse_pos);
+            } else if smooth_scroll != egui::Vec2::ZERO {
+                // 鼠标滚轮平移视口
+                self.ui_state.pan(smooth_scroll);
+            }
+        }
+
+        // 中键拖动或按住空格拖动时平移视口，不进行编辑（已经开始的编辑拖动不会被打断）
+        let editing = self.ui_state.is_dragging() || self.ui_state.drag_anchor().is_some();
+        let space_down = ui.input(|i| i.key_down(egui::Key::Space));
+        let panning = response.dragged_by(egui::PointerButton::Middle)
+            || (space_down && !editing && response.dragged_by(egui::PointerButton::Primary));
+        if panning {
+            self.ui_state.pan(response.drag_delta());
+            if space_down {
+                self.ui_state.set_space_panned();
+            }
+        }
+
+        let grid_rect = self.grid_screen_rect(viewport);
+        if !panning {
+            // 处理鼠标交互
+            self.handle_mouse_interaction(&response, grid_rect);
+        }
+
+        // 只在视口内绘制
+        let painter = painter.with_clip_rect(viewport);
+
+        // 绘制网格
+        self.draw_grid(grid_rect, &painter);
+
+        // 标出选区和正在拖出的图形
+        self.draw_selection(grid_rect, &painter);
+        self.draw_shape_preview(grid_rect, &painter);
+
+        // 放置图案时在鼠标下绘制半透明的虚影
+        self.draw_placement_ghost(&response, grid_rect, &painter);
+    }
+
+    /// 网格在屏幕上占据的矩形：视口左上角加上平移偏移
+    fn grid_screen_rect(&self, viewport: egui::Rect) -> egui::Rect {
+        let effective_cell_size = self.effective_cell_size();
+        egui::Rect::from_min_size(
+            viewport.min + self.ui_state.view_offset(),
+            egui::Vec2::new(self.grid.width() as f32, self.grid.height() as f32) * effective_cell_size,
+        )
+    }
+
+    /// 屏幕坐标所在的网格细胞，不在网格内时返回 `None`
+    fn screen_to_cell(&self, grid_rect: egui::Rect) -> impl Fn(egui::Pos2) -> Option<(usize, usize)> + Copy {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        let effective_cell_size = self.effective_cell_size();
+        move |pos| {
+            if !grid_rect.contains(pos) {
+                return None;
+            }
+            let cell = (pos - grid_rect.min) / effective_cell_size;
+            let (x, y) = (cell.x as usize, cell.y as usize);
+            (x < width && y < height).then_some((x, y))
+        }
+    }
+
+    /// 处理鼠标交互事件
+    pub fn handle_mouse_interaction(&mut self, response: &egui::Response, grid_rect: egui::Rect) {
+        // 处理鼠标事件的辅助函数：将鼠标坐标转换为网格坐标
+        let mouse_to_grid = self.screen_to_cell(grid_rect);
+
+        // 放置图案时点击盖章，不绘制细胞
+        if self.placing.is_some() {
+            if response.clicked() {
+                if let Some((x, y)) = response.interact_pointer_pos().and_then(mouse_to_grid) {
+                    self.place_at(x, y);
+                }
+            }
+            return;
+        }
+
+        // 选择工具：拖动拉出矩形选区，单击取消选区
+        if self.ui_state.tool() == Tool::Select {
+            if response.drag_started() {
+                if let Some(cell) = response.interact_pointer_pos().and_then(mouse_to_grid) {
+                    self.ui_state.set_drag_anchor(Some(cell));
+                    self.selection = Some(Selection::from_corners(cell, cell));
+                }
+            }
+            if response.dragged() {
+                let cell = response.interact_pointer_pos().and_then(mouse_to_grid);
+                if let (Some(anchor), Some(cell)) = (self.ui_state.drag_anchor(), cell) {
+                    self.selection = Some(Selection::from_corners(anchor, cell));
+                }
+            }
+            if response.drag_stopped() {
+                self.ui_state.set_drag_anchor(None);
+            }
+            if response.clicked() {
+                self.selection = None;
+            }
+            return;
+        }
+
+        // 泛洪填充：点击把与该细胞状态相同的连通区域翻转
+        if self.ui_state.tool() == Tool::FloodFill {
+            if response.clicked() {
+                if let Some((x, y)) = response.interact_pointer_pos().and_then(mouse_to_grid) {
+                    let state = if self.grid.is_alive(x, y) { CellState::Dead } else { CellState::Alive };
+                    self.history.begin("Flood fill", &self.grid, self.generation, self.view_origin);
+                    self.grid.flood_fill(x, y, state);
+                    self.history.commit(&self.grid, self.generation, self.view_origin);
+                }
+            }
+            return;
+        }
+
+        // 图形工具：拖动确定两个角，松开时绘制；右键拖动用死细胞擦除
+        if let Tool::Shape(shape) = self.ui_state.tool() {
+            if response.drag_started() {
+                if let Some(cell) = response.interact_pointer_pos().and_then(mouse_to_grid) {
+                    let erase = response.dragged_by(egui::PointerButton::Secondary);
+                    self.ui_state.set_drag_state(if erase { CellState::Dead } else { CellState::Alive });
+                    self.ui_state.set_drag_anchor(Some(cell));
+                    self.ui_state.set_last_drag_cell(Some(cell));
+                }
+            }
+            if response.dragged() {
+                if let Some(cell) = response.interact_pointer_pos().and_then(mouse_to_grid) {
+                    self.ui_state.set_last_drag_cell(Some(cell));
+                }
+            }
+            if response.drag_stopped() {
+                let anchor = self.ui_state.drag_anchor();
+                if let (Some(a), Some(b), Some(state)) =
+                    (anchor, self.ui_state.last_drag_cell(), self.ui_state.drag_state())
+                {
+                    self.history.begin(shape.name(), &self.grid, self.generation, self.view_origin);
+                    self.grid.draw_shape(shape, a, b, state);
+                    self.history.commit(&self.grid, self.generation, self.view_origin);
+                }
+                self.ui_state.set_drag_anchor(None);
+                self.ui_state.set_last_drag_cell(None);
+                self.ui_state.set_dragging(false);
+            }
+            return;
+        }
+
+        let brush_size = self.ui_state.brush_size();
+
+        // 处理鼠标按下事件（开始拖动）
+        if response.drag_started() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                if let Some((x, y)) = mouse_to_grid(pos) {
+                    // 开始拖动时，记住当前细胞的状态，并决定拖动时要绘制的状态
+                    let current_state = *self.grid.get_cell(x, y);
+                    let drag_state = match current_state {
+                        CellState::Alive => CellState::Dead, // 如果当前是存活，拖动时绘制死亡
+                        CellState::Dead => CellState::Alive, // 如果当前是死亡，拖动时绘制存活
+                    };
+                    self.ui_state.set_drag_state(drag_state);
+                    self.ui_state.set_dragging(true);
+                    // 整个拖动过程作为一次可撤销的操作
+                    self.history.begin("Draw", &self.grid, self.generation, self.view_origin);
+                    // 用笔刷绘制第一个位置
+                    self.grid.paint_brush(x, y, brush_size, drag_state);
+                    self.ui_state.set_last_drag_cell(Some((x, y)));
+                }
+            }
+        }
+
+        // 处理拖动过程中的事件
+        if self.ui_state.is_dragging() && response.dragged() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                if let Some((x, y)) = mouse_to_grid(pos) {
+                    // 在拖动过程中，沿上次位置到当前位置的线段用笔刷绘制，鼠标移动较快时也不会断开
+                    if let Some(state) = self.ui_state.drag_state() {
+                        let from = self.ui_state.last_drag_cell().unwrap_or((x, y));
+                        self.grid.paint_brush_line(from, (x, y), brush_size, state);
+                        self.ui_state.set_last_drag_cell(Some((x, y)));
+                    }
+                }
+            }
+        }
+
+        // 处理鼠标释放事件（结束拖动）
+        if response.drag_stopped() && self.ui_state.is_dragging() {
+            self.ui_state.set_dragging(false);
+            self.ui_state.set_last_drag_cell(None);
+            self.history.commit(&self.grid, self.generation, self.view_origin);
+        }
+
+        // 处理简单点击事件（非拖动）
+        if response.clicked() && !self.ui_state.is_dragging() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                if let Some((x, y)) = mouse_to_grid(pos) {
+                    // 简单点击时切换细胞状态；笔刷较大时把整个笔刷设为与该细胞相反的状态
+                    self.history.begin(
+                        "Toggle cell",
+                        &self.grid,
+                        self.generation,
+                        self.view_origin,
+                    );
+                    if brush_size > 1 {
+                        let state = if self.grid.is_alive(x, y) { CellState::Dead } else { CellState::Alive };
+                        self.grid.paint_brush(x, y, brush_size, state);
+                    } else {
+                        self.grid.toggle_cell(x, y);
+                    }
+                    self.history.commit(&self.grid, self.generation, self.view_origin);
+                }
+            }
+        }
+    }
+
+    /// 绘制游戏网格
+    pub fn draw_grid(&self, grid_rect: egui::Rect, painter: &egui::Painter) {
+        let effective_cell_size = self.effective_cell_size();
+        let (alive_color, dead_color, grid_line_color) = self.get_theme_colors();
+
+        // 只绘制视口内可见的细胞
+        let visible = painter.clip_rect().intersect(grid_rect);
+        if !visible.is_positive() {
+            return;
+        }
+        let first = (visible.min - grid_rect.min) / effective_cell_size;
+        let last = (visible.max - grid_rect.min) / effective_cell_size;
+        let columns = first.x as usize..(last.x.ceil() as usize).min(self.grid.width());
+        let rows = first.y as usize..(last.y.ceil() as usize).min(self.grid.height());
+
+        // 绘制网格中的每个细胞
+        for y in rows {
+            for x in columns.clone() {
+                // 计算每个细胞的绘制矩形
+                let rect = egui::Rect::from_min_size(
+                    grid_rect.left_top()
+                        + egui::Vec2::new(x as f32 * effective_cell_size, y as f32 * effective_cell_size),
+                    egui::Vec2::splat(effective_cell_size),
+                );
+
+                // 根据细胞状态和主题选择颜色
+                let color = match self.grid.get_cell(x, y) {
+                    CellState::Alive => alive_color,
+                    CellState::Dead => dead_color,
+                };
+
+                // 绘制填充的矩形（细胞）
+                painter.rect_filled(rect, 0.0, color);
+                
+                // 根据设置决定是否绘制网格线
+                if self.ui_state.show_grid_lines() {
+                    let line_width = if effective_cell_size < 5.0 { 0.2 } else { 0.5 };
+                    painter.rect_stroke(rect, 0.0, egui::Stroke::new(line_width, grid_line_color));
+                }
+            }
+        }
+
+        // 标出断点的监视区域（引擎坐标，无界宇宙中换算为窗口坐标）
+        if self.breakpoints.region_enabled {
+            let (x, y, width, height) = self.breakpoints.region;
+            let (x, y) = (x - self.view_origin.0, y - self.view_origin.1);
+            let region = egui::Rect::from_min_size(
+                grid_rect.left_top()
+                    + egui::Vec2::new(x as f32, y as f32) * effective_cell_size,
+                egui::Vec2::new(width as f32, height as f32) * effective_cell_size,
+            );
+            painter.rect_stroke(
+                region,
+                0.0,
+                egui::Stroke::new(2.0, egui::Color32::from_rgb(255, 140, 0)),
+            );
+        }
+    }
+
+    /// 绘制选区的边框和半透明底色
+    pub fn draw_selection(&self, grid_rect: egui::Rect, painter: &egui::Painter) {
+        let selection = match self.selection.and_then(|selection| selection.clip_to(&self.grid)) {
+            Some(selection) => selection,
+            None => return,
+        };
+        let effective_cell_size = self.effective_cell_size();
+        let rect = egui::Rect::from_min_size(
+            grid_rect.left_top()
+                + egui::Vec2::new(selection.x as f32, selection.y as f32) * effective_cell_size,
+            egui::Vec2::new(selection.width as f32, selection.height as f32) * effective_cell_size,
+        );
+        let color = egui::Color32::from_rgb(80, 160, 255);
+        painter.rect_filled(rect, 0.0, color.gamma_multiply(0.15));
+        painter.rect_stroke(rect, 0.0, egui::Stroke::new(1.5, color));
+    }
+
+    /// 拖动图形工具时，用半透明的主题颜色预览松开鼠标后将绘制的细胞
+    pub fn draw_shape_preview(&self, grid_rect: egui::Rect, painter: &egui::Painter) {
+        let shape = match self.ui_state.tool() {
+            Tool::Shape(shape) => shape,
+            _ => return,
+        };
+        let (a, b) = match (self.ui_state.drag_anchor(), self.ui_state.last_drag_cell()) {
+            (Some(a), Some(b)) => (a, b),
+            _ => return,
+        };
+        let (alive_color, dead_color, _) = self.get_theme_colors();
+        let color = match self.ui_state.drag_state() {
+            Some(CellState::Dead) => dead_color,
+            _ => alive_color,
+        }
+        .gamma_multiply(0.6);
+        let effective_cell_size = self.effective_cell_size();
+        for (x, y) in shape.cells(a, b) {
+            let rect = egui::Rect::from_min_size(
+                grid_rect.left_top() + egui::Vec2::new(x as f32, y as f32) * effective_cell_size,
+                egui::Vec2::splat(effective_cell_size),
+            );
+            painter.rect_filled(rect, 0.0, color);
+        }
+    }
+
+    /// 在鼠标所在位置绘制正在放置的图案的虚影，颜色取自当前主题
+    pub fn draw_placement_ghost(
+        &self,
+        response: &egui::Response,
+        grid_rect: egui::Rect,
+        painter: &egui::Painter,
+    ) {
+        let stamp = match self.placement_stamp() {
+            Some(stamp) => stamp,
+            None => return,
+        };
+        let cell = match response.hover_pos().and_then(self.screen_to_cell(grid_rect)) {
+            Some(cell) => cell,
+            None => return,
+        };
+        let effective_cell_size = self.effective_cell_size();
+
+        let (alive_color, dead_color, _) = self.get_theme_colors();
+        // AND-NOT 擦除细胞，用死亡细胞的颜色表示；其他模式用存活细胞的颜色
+        let ghost_color = match self.stamp_mode {
+            StampMode::AndNot => dead_color,
+            _ => alive_color,
+        }
+        .gamma_multiply(0.5);
+
+        let (left, top) = Self::placement_origin(&stamp, cell);
+        let origin = grid_rect.left_top()
+            + egui::Vec2::new(left as f32, top as f32) * effective_cell_size;
+        let outline = egui::Rect::from_min_size(
+            origin,
+            egui::Vec2::new(stamp.width() as f32, stamp.height() as f32) * effective_cell_size,
+        );
+        // 只在网格范围内绘制
+        let painter = painter.with_clip_rect(grid_rect.intersect(painter.clip_rect()));
+        stamp.for_each_cell(|x, y, alive| {
+            if alive {
+                let rect = egui::Rect::from_min_size(
+                    origin + egui::Vec2::new(x as f32, y as f32) * effective_cell_size,
+                    egui::Vec2::splat(effective_cell_size),
+                );
+                painter.rect_filled(rect, 0.0, ghost_color);
+            }
+        });
+        painter.rect_stroke(outline, 0.0, egui::Stroke::new(1.0, alive_color));
+    }
+
+    /// 渲染运行时的暂停条件
+    pub fn render_breakpoint_settings(&mut self, ui: &mut egui::Ui) {
+        ui.label(
+            egui::RichText::new("Pause the running simulation when:")
+                .small()
+                .color(egui::Color32::GRAY),
+        );
+        let breakpoints = &mut self.breakpoints;
+        ui.checkbox(&mut breakpoints.on_extinction, "Population reaches zero");
+        ui.checkbox(&mut breakpoints.on_period, "A period is detected");
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut breakpoints.population_enabled, "Population crosses");
+            ui.add_enabled(
+                breakpoints.population_enabled,
+                egui::DragValue::new(&mut breakpoints.population_threshold),
+            );
+        });
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut breakpoints.generation_enabled, "Generation reaches");
+            ui.add_enabled(
+                breakpoints.generation_enabled,
+                egui::DragValue::new(&mut breakpoints.target_generation),
+            );
+        });
+
+        ui.checkbox(&mut breakpoints.region_enabled, "A cell in region changes");
+        ui.add_enabled_ui(breakpoints.region_enabled, |ui| {
+            let (x, y, width, height) = &mut breakpoints.region;
+            ui.horizontal(|ui| {
+                ui.label("x:");
+                ui.add(egui::DragValue::new(x));
+                ui.label("y:");
+                ui.add(egui::DragValue::new(y));
+            });
+            ui.horizontal(|ui| {
+                ui.label("w:");
+                ui.add(egui::DragValue::new(width).range(1..=usize::MAX));
+                ui.label("h:");
+                ui.add(egui::DragValue::new(height).range(1..=usize::MAX));
+            });
+        });
+    }
+
+    /// 渲染统计控制（在左侧面板中）
+    pub fn render_statistics_controls(&mut self, ui: &mut egui::Ui) {
+        // 显示当前活细胞数量
+        let current_population = self.get_current_population();
+        ui.label(format!("Live Cells: {}", current_population));
+        
+        ui.add_space(5.0);
+        
+        // 显示统计开关
+        let mut show_statistics = self.show_statistics();
+        if ui.checkbox(&mut show_statistics, "Show Statistics Panel").changed() {
+            self.set_show_statistics(show_statistics);
+        }
+        
+        ui.add_space(5.0);
+        
+        // 清除历史按钮
+        if ui.button("Clear History").clicked() {
+            self.clear_population_history();
+        }
+    }
+
+    /// 渲染统计信息面板（在右侧面板中）
+    pub fn render_statistics_panel(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Population Statistics");
+        
+        ui.add_space(10.0);
+        
+        // 显示当前活细胞数量
+        let current_population = self.get_current_population();
+        ui.label(format!("Current Live Cells: {}", current_population));
+        
+        // 显示历史记录长度
+        ui.label(format!("Generations Recorded: {}", self.statistics.get_history_length()));
+        
+        // 显示最大和最小人口
+        if self.statistics.has_data() {
+            if let Some(max_pop) = self.statistics.get_max_population() {
+                ui.label(format!("Max Population: {}", max_pop));
+            }
+            if let Some(min_pop) = self.statistics.get_min_population() {
+                ui.label(format!("Min Population: {}", min_pop));
+            }
+            if let Some(avg_pop) = self.statistics.get_average_population() {
+                ui.label(format!("Average Population: {:.1}", avg_pop));
+            }
+            
+            // 显示趋势信息
+            if let Some(trend) = self.statistics.get_population_trend(5) {
+                let trend_text = match trend {
+                    1 => "📈 Growing",
+                    -1 => "📉 Declining", 
+                    0 => "➡️ Stable",
+                    _ => "❓ Unknown"
+                };
+                ui.label(format!("Trend: {}", trend_text));
+            }
+            
+            // 显示稳定性
+            if self.statistics.is_stable(10, 5) {
+                ui.label("🔒 Population Stable");
+            }
+
+            // 显示基于状态哈希的周期检测结果
+            match self.detected_period() {
+                Some(periodicity) => {
+                    ui.label(format!("🔁 Periodicity: {}", periodicity));
+                }
+                None => {
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "Periodicity: none detected ({} generations checked)",
+                            self.period_detector.observed_generations()
+                        ))
+                        .color(egui::Color32::GRAY),
+                    );
+                }
+            }
+        }
+        
+        ui.add_space(15.0);
+        
+        // 如果有历史数据，则绘制图表
+        if self.statistics.has_data() {
+            ui.label("Population History:");
+            ui.add_space(5.0);
+            self.render_population_chart(ui);
+        } else {
+            ui.label("No population data yet. Start the simulation to see the chart.");
+        }
+
+        ui.add_space(15.0);
+        self.render_census(ui);
+    }
+
+    /// 渲染对象普查表
+    pub fn render_census(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Object Census:");
+            if ui
+                .button("Take Census")
+                .on_hover_text("Split the live cells into objects and identify them by apgcode")
+                .clicked()
+            {
+                self.run_census();
+            }
+            if ui
+                .add_enabled(self.census.is_some(), egui::Button::new("Export CSV"))
+                .clicked()
+            {
+                self.export_census();
+            }
+        });
+
+        let census = match &self.census {
+            Some(census) => census,
+            None => return,
+        };
+        ui.label(format!(
+            "{} objects, {} kinds",
+            census.total_objects(),
+            census.entries.len()
+        ));
+
+        let (sort_column, descending) = self.census_sort;
+        let mut clicked_column = None;
+        egui::ScrollArea::vertical()
+            .id_source("census_table")
+            .max_height(250.0)
+            .show(ui, |ui| {
+                egui::Grid::new("census_grid")
+                    .striped(true)
+                    .num_columns(CensusColumn::ALL.len())
+                    .show(ui, |ui| {
+                        // 点击表头排序
+                        for column in CensusColumn::ALL {
+                            let arrow = match (column == sort_column, descending) {
+                                (true, true) => " ⏷",
+                                (true, false) => " ⏶",
+                                (false, _) => "",
+                            };
+                            if ui
+                                .selectable_label(
+                                    column == sort_column,
+                                    format!("{}{}", column.title(), arrow),
+                                )
+                                .clicked()
+                            {
+                                clicked_column = Some(column);
+                            }
+                        }
+                        ui.end_row();
+
+                        for entry in &census.entries {
+                            ui.label(entry.name.unwrap_or("-"));
+                            ui.label(egui::RichText::new(&entry.apgcode).monospace());
+                            ui.label(entry.kind_label());
+                            ui.label(entry.count.to_string());
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        if let Some(column) = clicked_column {
+            self.sort_census(column);
+        }
+    }
+
+    /// 渲染人口增长图表
+    pub fn render_population_chart(&self, ui: &mut egui::Ui) {
+        use egui_plot::{Line, Plot, PlotPoints};
+        
+        let history = self.get_population_history();
+        if history.is_empty() {
+            return;
+        }
+        
+        // 准备图表数据
+        let points: PlotPoints = history
+            .iter()
+            .enumerate()
+            .map(|(i, &population)| [i as f64, population as f64])
+            .collect();
+        
+        let line = Line::new(points)
+            .color(egui::Color32::from_rgb(100, 200, 100))
+            .name("Population");
+        
+        // 创建图表
+        Plot::new("population_chart")
+            .view_aspect(1.5)
+            .height(200.0)
+            .allow_zoom(true)
+            .allow_drag(true)
+            .show_axes([true, true])
+            .x_axis_label("Generation")
+            .y_axis_label("Population")
+            .show(ui, |plot_ui| {
+                plot_ui.line(line);
+            });
+    }
+
+    /// 渲染网格下方的时间线滑块
+    pub fn render_timeline_panel(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Timeline:");
+            let len = self.timeline.len();
+            if self.timeline.is_empty() {
+                ui.label(
+                    egui::RichText::new("Run the simulation to record generations")
+                        .color(egui::Color32::GRAY),
+                );
+            } else {
+                // 网格在记录之后被编辑过时，滑块停在最后一帧
+                let current = self.timeline.current_index(&self.grid);
+                let mut index = current.unwrap_or(len - 1);
+                let mut target = None;
+
+                if ui
+                    .add_enabled(index > 0, egui::Button::new("⏮"))
+                    .on_hover_text("Oldest recorded generation")
+                    .clicked()
+                {
+                    target = Some(0);
+                }
+                if ui
+                    .add_enabled(index > 0, egui::Button::new("◀"))
+                    .on_hover_text("Previous recorded generation")
+                    .clicked()
+                {
+                    target = Some(index - 1);
+                }
+
+                let timeline = &self.timeline;
+                let slider = egui::Slider::new(&mut index, 0..=len - 1)
+                    .show_value(false)
+                    .custom_formatter(|value, _| {
+                        timeline
+                            .generation_at(value as usize)
+                            .map_or_else(String::new, |generation| generation.to_string())
+                    });
+                ui.spacing_mut().slider_width = (ui.available_width() - 260.0).max(100.0);
+                if ui.add(slider).changed() {
+                    target = Some(index);
+                }
+
+                if ui
+                    .add_enabled(index + 1 < len, egui::Button::new("▶"))
+                    .on_hover_text("Next recorded generation")
+                    .clicked()
+                {
+                    target = Some(index + 1);
+                }
+                if ui
+                    .add_enabled(index + 1 < len, egui::Button::new("⏭"))
+                    .on_hover_text("Latest recorded generation")
+                    .clicked()
+                {
+                    target = Some(len - 1);
+                }
+
+                let generation = self.timeline.generation_at(index).unwrap_or(0);
+                let text = match current {
+                    Some(_) => format!("Gen {} ({}/{})", generation, index + 1, len),
+                    None => format!("Edited after gen {}", generation),
+                };
+                ui.label(text).on_hover_text(format!(
+                    "{} generations kept (~{:.1} MB)",
+                    len,
+                    self.timeline.memory_usage() as f64 / (1024.0 * 1024.0)
+                ));
+
+                if let Some(target) = target {
+                    if current != Some(target) {
+                        self.jump_to_frame(target);
+                    }
+                }
+            }
+
+            // 时间线长度
+            let mut capacity = self.timeline.capacity();
+            if ui
+                .add(egui::DragValue::new(&mut capacity).range(10..=100_000).suffix(" gens"))
+                .on_hover_text("Number of recent generations kept for rewinding")
+                .changed()
+            {
+                self.timeline.set_capacity(capacity);
+            }
+        });
+    }
+}
@@ -0,0 +1,256 @@
+//! 断点模块
+//! 模拟运行时每一步检查暂停条件：人口归零、检测到周期、人口越过阈值、
+//! 到达指定代数，或者标记区域内的细胞发生变化
+
+use crate::game::LifeEngine;
+use crate::period::Periodicity;
+use std::fmt;
+
+/// 触发暂停的原因
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BreakReason {
+    /// 人口变为零
+    Extinction,
+    /// 检测到周期
+    PeriodDetected(Periodicity),
+    /// 人口越过阈值
+    PopulationCrossed { threshold: usize, population: usize },
+    /// 到达（或越过）目标代数
+    GenerationReached(u64),
+    /// 监视区域内的细胞发生变化
+    RegionChanged,
+}
+
+impl fmt::Display for BreakReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BreakReason::Extinction => write!(f, "population reached zero"),
+            BreakReason::PeriodDetected(periodicity) => write!(f, "detected {}", periodicity),
+            BreakReason::PopulationCrossed {
+                threshold,
+                population,
+            } => write!(f, "population crossed {} (now {})", threshold, population),
+            BreakReason::GenerationReached(generation) => {
+                write!(f, "reached generation {}", generation)
+            }
+            BreakReason::RegionChanged => write!(f, "a cell in the watched region changed"),
+        }
+    }
+}
+
+/// 某一步前后需要比较的状态
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Sample {
+    pub generation: u64,
+    pub population: usize,
+    pub periodicity: Option<Periodicity>,
+}
+
+/// 运行时的暂停条件
+#[derive(Clone, Debug)]
+pub struct Breakpoints {
+    /// 人口归零时暂停
+    pub on_extinction: bool,
+    /// 新检测到周期时暂停
+    pub on_period: bool,
+    /// 人口越过阈值时暂停
+    pub population_enabled: bool,
+    pub population_threshold: usize,
+    /// 到达目标代数时暂停
+    pub generation_enabled: bool,
+    pub target_generation: u64,
+    /// 监视区域内的细胞变化时暂停
+    pub region_enabled: bool,
+    /// 监视区域 (x, y, 宽, 高)，引擎坐标：位压缩网格中即网格坐标，无界宇宙中为宇宙坐标，
+    /// 窗口跟随存活细胞移动时监视的仍是同一块区域
+    pub region: (i64, i64, usize, usize),
+}
+
+impl Breakpoints {
+    /// 记录引擎中监视区域内的存活细胞（已排序）；未启用区域监视时返回 `None`
+    pub fn capture_region(&self, engine: &dyn LifeEngine) -> Option<Vec<(i64, i64)>> {
+        if !self.region_enabled {
+            return None;
+        }
+        let (x, y, width, height) = self.region;
+        let max = (
+            x.saturating_add(i64::try_from(width).unwrap_or(i64::MAX)),
+            y.saturating_add(i64::try_from(height).unwrap_or(i64::MAX)),
+        );
+        let mut cells = Vec::new();
+        engine.for_each_alive_in((x, y), max, &mut |x, y| cells.push((x, y)));
+        cells.sort_unstable();
+        Some(cells)
+    }
+
+    /// 比较一步前后的状态，返回第一个触发的条件
+    ///
+    /// 条件只在越过时触发（例如人口原本就为零时不会触发），避免继续运行后立即再次暂停
+    pub fn check(
+        &self,
+        before: Sample,
+        after: Sample,
+        region_before: Option<&[(i64, i64)]>,
+        engine: &dyn LifeEngine,
+    ) -> Option<BreakReason> {
+        if self.on_extinction && before.population > 0 && after.population == 0 {
+            return Some(BreakReason::Extinction);
+        }
+        if self.on_period && before.periodicity.is_none() {
+            if let Some(periodicity) = after.periodicity {
+                return Some(BreakReason::PeriodDetected(periodicity));
+            }
+        }
+        if self.population_enabled {
+            let threshold = self.population_threshold;
+            let rose = before.population < threshold && after.population >= threshold;
+            let fell = before.population > threshold && after.population <= threshold;
+            if rose || fell {
+                return Some(BreakReason::PopulationCrossed {
+                    threshold,
+                    population: after.population,
+                });
+            }
+        }
+        if self.generation_enabled
+            && before.generation < self.target_generation
+            && after.generation >= self.target_generation
+        {
+            return Some(BreakReason::GenerationReached(after.generation));
+        }
+        if let Some(region_before) = region_before {
+            if self.capture_region(engine).as_deref() != Some(region_before) {
+                return Some(BreakReason::RegionChanged);
+            }
+        }
+        None
+    }
+}
+
+impl Default for Breakpoints {
+    fn default() -> Self {
+        Self {
+            on_extinction: true,
+            on_period: false,
+            population_enabled: false,
+            population_threshold: 1000,
+            generation_enabled: false,
+            target_generation: 1000,
+            region_enabled: false,
+            region: (0, 0, 10, 10),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{CellState, Grid};
+    use crate::universe::SparseUniverse;
+
+    fn sample(generation: u64, population: usize) -> Sample {
+        Sample {
+            generation,
+            population,
+            periodicity: None,
+        }
+    }
+
+    #[test]
+    fn test_extinction_and_period() {
+        let grid = Grid::new(10, 10);
+        let mut breakpoints = Breakpoints::default();
+        assert_eq!(
+            breakpoints.check(sample(4, 3), sample(5, 0), None, &grid),
+            Some(BreakReason::Extinction)
+        );
+        // 人口原本就为零时不触发
+        assert_eq!(breakpoints.check(sample(5, 0), sample(6, 0), None, &grid), None);
+
+        breakpoints.on_period = true;
+        let periodic = Sample {
+            periodicity: Some(Periodicity::Oscillator { period: 2 }),
+            ..sample(7, 3)
+        };
+        assert_eq!(
+            breakpoints.check(sample(6, 3), periodic, None, &grid),
+            Some(BreakReason::PeriodDetected(Periodicity::Oscillator { period: 2 }))
+        );
+        assert_eq!(breakpoints.check(periodic, periodic, None, &grid), None);
+    }
+
+    #[test]
+    fn test_population_and_generation_thresholds() {
+        let grid = Grid::new(10, 10);
+        let breakpoints = Breakpoints {
+            population_enabled: true,
+            population_threshold: 100,
+            generation_enabled: true,
+            target_generation: 50,
+            ..Breakpoints::default()
+        };
+        assert_eq!(
+            breakpoints.check(sample(10, 90), sample(11, 104), None, &grid),
+            Some(BreakReason::PopulationCrossed {
+                threshold: 100,
+                population: 104
+            })
+        );
+        assert!(breakpoints.check(sample(10, 120), sample(11, 80), None, &grid).is_some());
+        assert_eq!(breakpoints.check(sample(10, 120), sample(11, 130), None, &grid), None);
+
+        // HashLife 一步推进多代时越过目标代数也会触发
+        assert_eq!(
+            breakpoints.check(sample(48, 120), sample(56, 120), None, &grid),
+            Some(BreakReason::GenerationReached(56))
+        );
+    }
+
+    #[test]
+    fn test_region_change() {
+        let mut grid = Grid::new(20, 20);
+        let breakpoints = Breakpoints {
+            region_enabled: true,
+            region: (5, 5, 4, 4),
+            ..Breakpoints::default()
+        };
+        let before = breakpoints.capture_region(&grid).unwrap();
+        assert!(before.is_empty());
+
+        // 区域外的变化不触发
+        grid.set_cell(0, 0, CellState::Alive);
+        assert_eq!(breakpoints.check(sample(0, 1), sample(1, 1), Some(&before), &grid), None);
+
+        grid.set_cell(6, 6, CellState::Alive);
+        assert_eq!(
+            breakpoints.check(sample(0, 1), sample(1, 2), Some(&before), &grid),
+            Some(BreakReason::RegionChanged)
+        );
+    }
+
+    #[test]
+    fn test_region_in_universe_coordinates() {
+        // 无界宇宙中区域使用宇宙坐标，与窗口位置无关
+        let mut universe = SparseUniverse::new();
+        for x in -101..=-99 {
+            universe.set_cell(x, -50, CellState::Alive);
+        }
+        universe.set_cell(500, 500, CellState::Alive);
+        let breakpoints = Breakpoints {
+            region_enabled: true,
+            region: (-105, -55, 10, 10),
+            ..Breakpoints::default()
+        };
+        let before = breakpoints.capture_region(&universe).unwrap();
+        assert_eq!(before, [(-101, -50), (-100, -50), (-99, -50)]);
+
+        // 区域外的细胞消失不触发；区域内的闪烁器翻转时触发
+        universe.set_cell(500, 500, CellState::Dead);
+        assert_eq!(breakpoints.check(sample(0, 4), sample(0, 3), Some(&before), &universe), None);
+        universe.next_generation();
+        assert_eq!(
+            breakpoints.check(sample(0, 3), sample(1, 3), Some(&before), &universe),
+            Some(BreakReason::RegionChanged)
+        );
+    }
+}
@@ -0,0 +1,529 @@
+//! 对象普查模块
+//! 把稳定后的残骸拆分为互不影响的对象，在所有相位和8种旋转/镜像下规范化，
+//! 识别为静物、振荡器或飞船，并生成 apgsearch 风格的 apgcode（如方块为 `xs4_33`）
+
+use crate::game::{CellState, LifeEngine};
+use crate::patterns::{self, Pattern};
+use crate::period::Periodicity;
+use crate::rules::Rule;
+use crate::universe::SparseUniverse;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+
+/// 识别对象时最多模拟的代数
+pub const MAX_OBJECT_PERIOD: usize = 128;
+
+/// 无法识别的对象（不稳定或过大）共用的代码
+pub const UNKNOWN_APGCODE: &str = "zz_UNKNOWN";
+
+/// apgcode 允许的最大对象尺寸
+const MAX_CODE_SIZE: i64 = 40;
+
+/// 判断两个对象是否相互影响时模拟的代数
+const INTERACTION_GENERATIONS: usize = 4;
+
+/// Wechsler 编码使用的字符
+const WECHSLER_CHARS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// 一组细胞坐标
+type Cells = Vec<(i64, i64)>;
+
+/// 预设图案中能识别的对象：apgcode -> 名称
+pub type KnownObjects = FxHashMap<String, &'static str>;
+
+/// 各规则下的已知对象表；识别全部预设图案需要模拟上百代，每个规则只计算一次
+static KNOWN_OBJECTS: OnceLock<Mutex<FxHashMap<Rule, Arc<KnownObjects>>>> = OnceLock::new();
+
+/// 普查表中的一行
+#[derive(Clone, PartialEq, Debug)]
+pub struct CensusEntry {
+    /// 规范化的 apgcode
+    pub apgcode: String,
+    /// 预设图案中的名称
+    pub name: Option<&'static str>,
+    /// 对象的周期性；无法识别时为 `None`
+    pub periodicity: Option<Periodicity>,
+    /// 出现次数
+    pub count: usize,
+}
+
+impl CensusEntry {
+    /// 对象类别的显示文本
+    pub fn kind_label(&self) -> String {
+        match self.periodicity {
+            Some(Periodicity::StillLife) => "still life".to_string(),
+            Some(Periodicity::Oscillator { period }) => format!("P{} oscillator", period),
+            Some(Periodicity::Spaceship { period, .. }) => format!("P{} spaceship", period),
+            Some(Periodicity::Extinct) | None => "unknown".to_string(),
+        }
+    }
+}
+
+/// 普查表可以排序的列
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CensusColumn {
+    Object,
+    Apgcode,
+    Kind,
+    Count,
+}
+
+impl CensusColumn {
+    /// 所有列，按表格顺序排列
+    pub const ALL: [CensusColumn; 4] = [
+        CensusColumn::Object,
+        CensusColumn::Apgcode,
+        CensusColumn::Kind,
+        CensusColumn::Count,
+    ];
+
+    /// 列标题
+    pub fn title(&self) -> &'static str {
+        match self {
+            CensusColumn::Object => "Object",
+            CensusColumn::Apgcode => "apgcode",
+            CensusColumn::Kind => "Kind",
+            CensusColumn::Count => "Count",
+        }
+    }
+}
+
+/// 一次普查的结果
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Census {
+    pub entries: Vec<CensusEntry>,
+}
+
+impl Census {
+    /// 对象总数
+    pub fn total_objects(&self) -> usize {
+        self.entries.iter().map(|entry| entry.count).sum()
+    }
+
+    /// 按指定列排序，相同时按 apgcode 排序
+    pub fn sort_by(&mut self, column: CensusColumn, descending: bool) {
+        self.entries.sort_by(|a, b| {
+            let order = match column {
+                CensusColumn::Object => a.name.unwrap_or("~").cmp(b.name.unwrap_or("~")),
+                CensusColumn::Apgcode => a.apgcode.cmp(&b.apgcode),
+                CensusColumn::Kind => a.kind_label().cmp(&b.kind_label()),
+                CensusColumn::Count => a.count.cmp(&b.count),
+            };
+            let order = if descending { order.reverse() } else { order };
+            order.then_with(|| a.apgcode.cmp(&b.apgcode))
+        });
+    }
+
+    /// 导出为 CSV 文本
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("apgcode,name,kind,count\n");
+        for entry in &self.entries {
+            csv.push_str(&format!(
+                "{},{},{},{}\n",
+                entry.apgcode,
+                entry.name.unwrap_or(""),
+                entry.kind_label(),
+                entry.count
+            ));
+        }
+        csv
+    }
+}
+
+/// 对引擎中的所有活细胞进行普查
+pub fn take_census(engine: &dyn LifeEngine) -> Census {
+    let rule = *engine.rule();
+    let known = known_objects(&rule);
+    let mut counts: FxHashMap<String, (Option<Periodicity>, usize)> = FxHashMap::default();
+
+    // B0 规则下孤立的对象无法在无界宇宙中演化，每个细胞团都记为无法识别的对象
+    let supported = rule.check_unbounded().is_ok();
+    for cluster in components(&live_cells(engine), 2) {
+        let objects = if supported {
+            split_independent(cluster, &rule)
+        } else {
+            vec![cluster]
+        };
+        for object in objects {
+            let (apgcode, periodicity) = match classify(&object, &rule) {
+                Some((apgcode, periodicity)) => (apgcode, Some(periodicity)),
+                None => (UNKNOWN_APGCODE.to_string(), None),
+            };
+            counts.entry(apgcode).or_insert((periodicity, 0)).1 += 1;
+        }
+    }
+
+    let mut census = Census {
+        entries: counts
+            .into_iter()
+            .map(|(apgcode, (periodicity, count))| CensusEntry {
+                name: known.get(&apgcode).copied(),
+                apgcode,
+                periodicity,
+                count,
+            })
+            .collect(),
+    };
+    census.sort_by(CensusColumn::Count, true);
+    census
+}
+
+/// 识别单个对象
+///
+/// # 返回值
+/// 返回 (apgcode, 周期性)；对象在 [`MAX_OBJECT_PERIOD`] 代内没有回到原来的形状、
+/// 灭绝、超过 40x40，或者规则包含 B0 时返回 `None`
+pub fn classify(cells: &[(i64, i64)], rule: &Rule) -> Option<(String, Periodicity)> {
+    rule.check_unbounded().ok()?;
+    let phases = evolve(cells, rule, MAX_OBJECT_PERIOD);
+    let (first, first_origin) = normalize(&phases[0]);
+    if first.is_empty() {
+        return None;
+    }
+
+    let (period, displacement) = phases.iter().enumerate().skip(1).find_map(|(t, phase)| {
+        let (shape, origin) = normalize(phase);
+        (shape == first).then(|| (t, (origin.0 - first_origin.0, origin.1 - first_origin.1)))
+    })?;
+
+    let periodicity = match displacement {
+        (0, 0) if period == 1 => Periodicity::StillLife,
+        (0, 0) => Periodicity::Oscillator {
+            period: period as u64,
+        },
+        (dx, dy) => Periodicity::Spaceship {
+            period: period as u64,
+            dx,
+            dy,
+        },
+    };
+
+    // 在一个周期的所有相位和8种方向中选最短、字典序最小的编码
+    let mut best: Option<String> = None;
+    for phase in &phases[..period] {
+        for orientation in 0..8 {
+            if let Some(code) = wechsler(phase, orientation) {
+                let better = best.as_ref().map_or(true, |current| {
+                    (code.len(), &code) < (current.len(), current)
+                });
+                if better {
+                    best = Some(code);
+                }
+            }
+        }
+    }
+
+    let prefix = match periodicity {
+        Periodicity::StillLife => format!("xs{}", first.len()),
+        Periodicity::Oscillator { period } => format!("xp{}", period),
+        Periodicity::Spaceship { period, .. } => format!("xq{}", period),
+        Periodicity::Extinct => return None,
+    };
+    Some((format!("{}_{}", prefix, best?), periodicity))
+}
+
+/// 预设图案中能识别的对象：apgcode -> 名称
+///
+/// 结果按规则缓存，随机汤搜索中每次普查都会用到
+pub fn known_objects(rule: &Rule) -> Arc<KnownObjects> {
+    let cache = KNOWN_OBJECTS.get_or_init(Default::default);
+    if let Some(known) = cache.lock().unwrap_or_else(PoisonError::into_inner).get(rule) {
+        return Arc::clone(known);
+    }
+    // 计算时不持有锁；并发计算同一规则时保留先写入的结果
+    let known = Arc::new(classify_presets(rule));
+    let mut cache = cache.lock().unwrap_or_else(PoisonError::into_inner);
+    Arc::clone(cache.entry(*rule).or_insert(known))
+}
+
+/// 识别所有预设图案
+fn classify_presets(rule: &Rule) -> KnownObjects {
+    let mut known = FxHashMap::default();
+    for (_, category) in patterns::get_all_patterns() {
+        for pattern in category {
+            if let Some((apgcode, _)) = classify(&pattern_cells(pattern), rule) {
+                known.entry(apgcode).or_insert(pattern.name);
+            }
+        }
+    }
+    known
+}
+
+/// 预设图案中的活细胞（与 `Grid::load_pattern` 使用相同的字符约定）
+fn pattern_cells(pattern: &Pattern) -> Cells {
+    let mut cells = Vec::new();
+    for (y, line) in pattern.data.iter().enumerate() {
+        for (x, ch) in line.chars().enumerate() {
+            if matches!(ch, '*' | '#' | 'O') {
+                cells.push((x as i64, y as i64));
+            }
+        }
+    }
+    cells
+}
+
+/// 引擎中的所有活细胞
+fn live_cells(engine: &dyn LifeEngine) -> Cells {
+    let mut cells = Vec::new();
+    if let Some(bounds) = engine.bounding_box() {
+        engine.for_each_alive_in(
+            (bounds.min_x, bounds.min_y),
+            (bounds.max_x + 1, bounds.max_y + 1),
+            &mut |x, y| cells.push((x, y)),
+        );
+    }
+    cells
+}
+
+/// 按切比雪夫距离不超过 `radius` 连通划分细胞，结果按位置排序
+fn components(cells: &[(i64, i64)], radius: i64) -> Vec<Cells> {
+    let mut remaining: FxHashSet<(i64, i64)> = cells.iter().copied().collect();
+    let mut result = Vec::new();
+    let mut sorted = cells.to_vec();
+    sorted.sort_unstable();
+
+    for &start in &sorted {
+        if !remaining.remove(&start) {
+            continue;
+        }
+        let mut component = vec![start];
+        let mut index = 0;
+        while index < component.len() {
+            let (x, y) = component[index];
+            index += 1;
+            for dy in -radius..=radius {
+                for dx in -radius..=radius {
+                    if remaining.remove(&(x + dx, y + dy)) {
+                        component.push((x + dx, y + dy));
+                    }
+                }
+            }
+        }
+        component.sort_unstable();
+        result.push(component);
+    }
+    result
+}
+
+/// 把相距较近的一组细胞拆分为8连通的部分，前提是各部分单独演化与整体演化的结果相同
+/// （例如相隔一格的两个方块）；否则整组作为一个对象（例如脉冲星的四个部分）
+fn split_independent(cluster: Cells, rule: &Rule) -> Vec<Cells> {
+    let parts = components(&cluster, 1);
+    if parts.len() < 2 {
+        return vec![cluster];
+    }
+
+    let joint = evolve(&cluster, rule, INTERACTION_GENERATIONS);
+    let separate: Vec<Vec<Cells>> = parts
+        .iter()
+        .map(|part| evolve(part, rule, INTERACTION_GENERATIONS))
+        .collect();
+    let independent = (0..=INTERACTION_GENERATIONS).all(|t| {
+        let mut union: Cells = separate.iter().flat_map(|phases| phases[t].clone()).collect();
+        union.sort_unstable();
+        union == joint[t]
+    });
+
+    if independent {
+        parts
+    } else {
+        vec![cluster]
+    }
+}
+
+/// 在无界宇宙中单独演化一组细胞，返回第 0 到 `generations` 代的细胞（已排序）
+///
+/// 调用方保证规则不包含 B0
+fn evolve(cells: &[(i64, i64)], rule: &Rule, generations: usize) -> Vec<Cells> {
+    let mut universe = SparseUniverse::new();
+    let _ = universe.set_rule(*rule);
+    for &(x, y) in cells {
+        universe.set_cell(x, y, CellState::Alive);
+    }
+
+    let mut phases = Vec::with_capacity(generations + 1);
+    for t in 0..=generations {
+        if t > 0 {
+            universe.next_generation();
+        }
+        let mut phase = live_cells(&universe);
+        phase.sort_unstable();
+        phases.push(phase);
+    }
+    phases
+}
+
+/// 平移到包围盒左上角，返回 (形状, 原包围盒左上角)
+fn normalize(cells: &[(i64, i64)]) -> (Cells, (i64, i64)) {
+    let min_x = cells.iter().map(|&(x, _)| x).min().unwrap_or(0);
+    let min_y = cells.iter().map(|&(_, y)| y).min().unwrap_or(0);
+    let mut shape: Cells = cells.iter().map(|&(x, y)| (x - min_x, y - min_y)).collect();
+    shape.sort_unstable();
+    (shape, (min_x, min_y))
+}
+
+/// 按8种旋转/镜像之一变换后的扩展 Wechsler 编码；超过 40x40 时返回 `None`
+///
+/// 图案按5行一条切分，每列用一个字符表示（第 i 行对应第 i 位），条之间用 `z` 分隔，
+/// 连续的空列压缩为 `0`、`w`、`x` 或 `y` 加长度，每条末尾的空列省略
+fn wechsler(cells: &[(i64, i64)], orientation: u8) -> Option<String> {
+    let transformed: Cells = cells
+        .iter()
+        .map(|&(x, y)| {
+            let (x, y) = if orientation & 4 != 0 { (y, x) } else { (x, y) };
+            let x = if orientation & 1 != 0 { -x } else { x };
+            let y = if orientation & 2 != 0 { -y } else { y };
+            (x, y)
+        })
+        .collect();
+    let (shape, _) = normalize(&transformed);
+    let width = shape.iter().map(|&(x, _)| x + 1).max()?;
+    let height = shape.iter().map(|&(_, y)| y + 1).max()?;
+    if width > MAX_CODE_SIZE || height > MAX_CODE_SIZE {
+        return None;
+    }
+
+    let alive: FxHashSet<(i64, i64)> = shape.into_iter().collect();
+    let mut code = String::new();
+    for strip in 0..(height + 4) / 5 {
+        if strip > 0 {
+            code.push('z');
+        }
+        let mut zeroes = 0;
+        for x in 0..width {
+            let column = (0..5).fold(0, |bits, row| {
+                let alive = alive.contains(&(x, strip * 5 + row));
+                bits | (usize::from(alive) << row)
+            });
+            if column == 0 {
+                zeroes += 1;
+                continue;
+            }
+            push_zeroes(&mut code, zeroes);
+            zeroes = 0;
+            code.push(WECHSLER_CHARS[column] as char);
+        }
+    }
+    Some(code)
+}
+
+/// 写入 `count` 个连续空列的压缩表示
+fn push_zeroes(code: &mut String, mut count: usize) {
+    while count >= 4 {
+        let run = count.min(39);
+        code.push('y');
+        code.push(WECHSLER_CHARS[run - 4] as char);
+        count -= run;
+    }
+    match count {
+        1 => code.push('0'),
+        2 => code.push('w'),
+        3 => code.push('x'),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Grid;
+    use crate::patterns::{miscellaneous, oscillators, spaceships};
+
+    fn apgcode(pattern: &Pattern) -> String {
+        classify(&pattern_cells(pattern), &Rule::conway()).unwrap().0
+    }
+
+    #[test]
+    fn test_known_apgcodes() {
+        assert_eq!(apgcode(&miscellaneous::BLOCK), "xs4_33");
+        assert_eq!(apgcode(&miscellaneous::BEEHIVE), "xs6_696");
+        assert_eq!(apgcode(&miscellaneous::LOAF), "xs7_2596");
+        assert_eq!(apgcode(&miscellaneous::BOAT), "xs5_253");
+        assert_eq!(apgcode(&miscellaneous::TUB), "xs4_252");
+        assert_eq!(apgcode(&miscellaneous::SHIP), "xs6_356");
+        assert_eq!(apgcode(&miscellaneous::POND), "xs8_6996");
+        assert_eq!(apgcode(&oscillators::BLINKER), "xp2_7");
+        assert_eq!(apgcode(&oscillators::TOAD), "xp2_7e");
+        assert_eq!(apgcode(&oscillators::BEACON), "xp2_318c");
+        assert_eq!(apgcode(&oscillators::PENTADECATHLON), "xp15_4r4z4r4");
+        assert_eq!(apgcode(&spaceships::GLIDER), "xq4_153");
+        assert_eq!(apgcode(&spaceships::LWSS), "xq4_6frc");
+    }
+
+    #[test]
+    fn test_known_objects_are_cached_per_rule() {
+        let conway = known_objects(&Rule::conway());
+        assert_eq!(conway.get("xs4_33"), Some(&"Block"));
+        assert!(Arc::ptr_eq(&conway, &known_objects(&Rule::conway())));
+        let highlife = known_objects(&Rule::parse("B36/S23").unwrap());
+        assert!(!Arc::ptr_eq(&conway, &highlife));
+    }
+
+    #[test]
+    fn test_unstable_object_is_unknown() {
+        let cells = pattern_cells(&miscellaneous::R_PENTOMINO);
+        assert_eq!(classify(&cells, &Rule::conway()), None);
+    }
+
+    #[test]
+    fn test_census_counts_objects() {
+        let mut grid = Grid::new(60, 40);
+        grid.load_pattern(&["OO", "OO"], 2, 2);
+        let place = |grid: &mut Grid, pattern: &Pattern, x: usize, y: usize| {
+            for (cx, cy) in pattern_cells(pattern) {
+                grid.set_cell(x + cx as usize, y + cy as usize, CellState::Alive);
+            }
+        };
+        // 与第一个方块相隔一格的方块仍然是独立的对象
+        place(&mut grid, &miscellaneous::BLOCK, 5, 2);
+        place(&mut grid, &miscellaneous::BLOCK, 30, 30);
+        place(&mut grid, &oscillators::BLINKER, 20, 10);
+        place(&mut grid, &miscellaneous::BEEHIVE, 40, 5);
+        place(&mut grid, &oscillators::PULSAR, 2, 15);
+
+        let census = take_census(&grid);
+        assert_eq!(census.total_objects(), 6);
+        let block = &census.entries[0];
+        assert_eq!((block.apgcode.as_str(), block.count), ("xs4_33", 3));
+        assert_eq!(block.name, Some("Block"));
+        assert_eq!(block.kind_label(), "still life");
+
+        let pulsar = census
+            .entries
+            .iter()
+            .find(|entry| entry.name == Some("Pulsar"))
+            .unwrap();
+        assert_eq!(pulsar.count, 1);
+        assert_eq!(pulsar.kind_label(), "P3 oscillator");
+    }
+
+    #[test]
+    fn test_sort_and_export() {
+        let mut census = Census {
+            entries: vec![
+                CensusEntry {
+                    apgcode: "xs4_33".to_string(),
+                    name: Some("Block"),
+                    periodicity: Some(Periodicity::StillLife),
+                    count: 5,
+                },
+                CensusEntry {
+                    apgcode: "xp2_7".to_string(),
+                    name: Some("Blinker"),
+                    periodicity: Some(Periodicity::Oscillator { period: 2 }),
+                    count: 2,
+                },
+            ],
+        };
+        census.sort_by(CensusColumn::Apgcode, false);
+        assert_eq!(census.entries[0].apgcode, "xp2_7");
+        census.sort_by(CensusColumn::Count, true);
+        assert_eq!(census.entries[0].apgcode, "xs4_33");
+
+        let csv = census.to_csv();
+        assert_eq!(
+            csv,
+            "apgcode,name,kind,count\nxs4_33,Block,still life,5\nxp2_7,Blinker,P2 oscillator,2\n"
+        );
+    }
+}
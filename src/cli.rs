@@ -0,0 +1,861 @@
+//! 命令行模块
+//! 不打开窗口、不初始化 egui，直接运行模拟并写出结果，适合无显示器的服务器和 CI 批量运行：
+//! `game_of_life run --input foo.rle --generations 1000 --rule B3/S23 --output out.rle --stats stats.csv`
+//! `game_of_life search --soups 10000 --db soups.json --csv soups.csv`
+
+use crate::game::{Algorithm, CellState, Grid, LifeEngine};
+use crate::hashlife::HashLife;
+use crate::macrocell::{self, MacrocellInfo};
+use crate::random::Symmetry;
+use crate::rules::Rule;
+use crate::save_load::{self, ParseMode, PatternFormat, RlePattern, RleWarning};
+use crate::search::{SearchDatabase, SearchSettings};
+use crate::statistics::PopulationStatistics;
+use crate::universe::SparseUniverse;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+/// 命令行用法说明
+pub const USAGE: &str = "\
+Usage: game_of_life [run OPTIONS | search OPTIONS]
+
+Without arguments the graphical interface is opened.
+
+Commands:
+  run    Run a pattern without opening a window
+  search Run random soups and keep the interesting ones in a database
+
+Options for run:
+  --input PATH         Pattern to start from: RLE, .cells, Life 1.05, Life 1.06 or
+                       macrocell (.mc, kept as a quadtree with hashlife) (required)
+  --generations N      Number of generations to run (default 100)
+  --rule RULE          Override the rule from the file, e.g. B36/S23
+  --algorithm NAME     bitpacked (default), hashlife or sparse
+  --padding N          Dead cells added around the pattern on each side (bitpacked only)
+  --threads N          Threads used by the bit-packed grid (default: all cores)
+  --output PATH        Write the final pattern; .cells, .lif (Life 1.05), .life
+                       (Life 1.06) and .mc (macrocell) select those formats,
+                       anything else writes RLE
+  --stats PATH         Write the population of every generation as CSV
+  --parse MODE         lenient (default) drops RLE cells outside the declared size
+                       with a warning, strict rejects them
+
+Options for search:
+  --soups N            Number of soups to run (default 1000)
+  --db PATH            JSON database to create or extend (default soups.json)
+  --csv PATH           Also write the database as CSV
+  --seed N             First seed (default: continue after the database)
+  --size N             Side length of each soup (default 16)
+  --density X          Probability that a cell starts alive (default 0.5)
+  --symmetry NAME      C1 (default), C2, C4, D4 or D8
+  --rule RULE          Rule to search, e.g. B36/S23 (default B3/S23)
+  --max-generations N  Give up on soups that have not settled (default 50000)
+  --methuselah N       Lifespan that counts as a methuselah (default 5000)
+
+  -h, --help           Show this help";
+
+/// `run` 命令的参数
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunOptions {
+    pub input: PathBuf,
+    pub generations: u64,
+    pub rule: Option<Rule>,
+    pub algorithm: Algorithm,
+    pub padding: usize,
+    pub threads: Option<usize>,
+    pub output: Option<PathBuf>,
+    pub stats: Option<PathBuf>,
+    pub parse_mode: ParseMode,
+}
+
+/// `search` 命令的参数
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchOptions {
+    pub soups: u64,
+    pub database: PathBuf,
+    pub csv: Option<PathBuf>,
+    pub seed: Option<u64>,
+    pub settings: SearchSettings,
+}
+
+/// 搜索时每隔这么多个随机汤保存一次数据库
+const SEARCH_SAVE_INTERVAL: u64 = 100;
+
+/// 命令行使用的演化引擎：网格单独保存，以便输出时保留尺寸和边界拓扑
+enum Engine {
+    Grid(Grid),
+    HashLife(HashLife),
+    Universe(Box<dyn LifeEngine>),
+}
+
+impl Engine {
+    fn get(&self) -> &dyn LifeEngine {
+        match self {
+            Engine::Grid(grid) => grid,
+            Engine::HashLife(universe) => universe,
+            Engine::Universe(engine) => engine.as_ref(),
+        }
+    }
+
+    fn get_mut(&mut self) -> &mut dyn LifeEngine {
+        match self {
+            Engine::Grid(grid) => grid,
+            Engine::HashLife(universe) => universe,
+            Engine::Universe(engine) => engine.as_mut(),
+        }
+    }
+
+    /// 转换为 RLE 图案；网格保留尺寸和边界拓扑，无界宇宙截取活细胞的包围盒
+    ///
+    /// 包围盒过大、无法放入稠密图案时返回错误
+    fn to_pattern(&self, name: String) -> Result<RlePattern, String> {
+        match self {
+            Engine::Grid(grid) => Ok(RlePattern::from_grid(grid, name)),
+            Engine::HashLife(universe) => engine_to_pattern(universe, name),
+            Engine::Universe(engine) => engine_to_pattern(engine.as_ref(), name),
+        }
+    }
+}
+
+/// 解析后的命令
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    Run(RunOptions),
+    Search(SearchOptions),
+    Help,
+}
+
+/// 运行结束后的摘要
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunSummary {
+    pub generations: u64,
+    pub population: usize,
+    pub rule: Rule,
+    /// 宽松模式下读取输入文件时的警告
+    pub warnings: Vec<RleWarning>,
+}
+
+/// 解析命令行参数（不包括程序名）
+pub fn parse_args(args: &[String]) -> Result<Command, String> {
+    let (command, rest) = match args.split_first() {
+        Some((command, rest)) => (command.as_str(), rest),
+        None => return Ok(Command::Help),
+    };
+    if rest.iter().any(|arg| matches!(arg.as_str(), "-h" | "--help")) {
+        return Ok(Command::Help);
+    }
+    match command {
+        "run" => parse_run_options(rest).map(Command::Run),
+        "search" => parse_search_options(rest).map(Command::Search),
+        "help" | "-h" | "--help" => Ok(Command::Help),
+        other => Err(format!("unknown command '{}'", other)),
+    }
+}
+
+/// 依次处理 `--name value` 或 `--name=value` 形式的选项
+fn for_each_option(
+    args: &[String],
+    mut apply: impl FnMut(&str, String) -> Result<(), String>,
+) -> Result<(), String> {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let (name, inline_value) = match arg.split_once('=') {
+            Some((name, value)) => (name, Some(value.to_string())),
+            None => (arg.as_str(), None),
+        };
+        let value = match inline_value.or_else(|| args.next().cloned()) {
+            Some(value) => value,
+            None => return Err(format!("missing value for {}", name)),
+        };
+        apply(name, value)?;
+    }
+    Ok(())
+}
+
+/// 解析 `run` 命令的选项
+fn parse_run_options(args: &[String]) -> Result<RunOptions, String> {
+    let mut input = None;
+    let mut options = RunOptions {
+        input: PathBuf::new(),
+        generations: 100,
+        rule: None,
+        algorithm: Algorithm::BitPacked,
+        padding: 0,
+        threads: None,
+        output: None,
+        stats: None,
+        parse_mode: ParseMode::Lenient,
+    };
+
+    for_each_option(args, |name, value| {
+        match name {
+            "--input" => input = Some(PathBuf::from(value)),
+            "--generations" => options.generations = parse_number(name, &value)?,
+            "--rule" => options.rule = Some(Rule::parse(&value)?),
+            "--algorithm" => options.algorithm = parse_algorithm(&value)?,
+            "--padding" => options.padding = parse_number(name, &value)?,
+            "--threads" => options.threads = Some(parse_number(name, &value)?),
+            "--output" => options.output = Some(PathBuf::from(value)),
+            "--stats" => options.stats = Some(PathBuf::from(value)),
+            "--parse" => options.parse_mode = parse_mode(&value)?,
+            other => return Err(format!("unknown option '{}'", other)),
+        }
+        Ok(())
+    })?;
+
+    options.input = input.ok_or("missing required option --input")?;
+    Ok(options)
+}
+
+/// 解析 `search` 命令的选项
+fn parse_search_options(args: &[String]) -> Result<SearchOptions, String> {
+    let mut options = SearchOptions {
+        soups: 1000,
+        database: PathBuf::from("soups.json"),
+        csv: None,
+        seed: None,
+        settings: SearchSettings::default(),
+    };
+
+    for_each_option(args, |name, value| {
+        let settings = &mut options.settings;
+        match name {
+            "--soups" => options.soups = parse_number(name, &value)?,
+            "--db" => options.database = PathBuf::from(value),
+            "--csv" => options.csv = Some(PathBuf::from(value)),
+            "--seed" => options.seed = Some(parse_number(name, &value)?),
+            "--size" => settings.soup_size = parse_number(name, &value)?,
+            "--density" => settings.density = parse_number(name, &value)?,
+            "--symmetry" => {
+                settings.symmetry = Symmetry::from_name(&value)
+                    .ok_or_else(|| format!("unknown symmetry '{}'", value))?
+            }
+            "--rule" => settings.rule = Rule::parse(&value)?,
+            "--max-generations" => settings.max_generations = parse_number(name, &value)?,
+            "--methuselah" => settings.methuselah_lifespan = parse_number(name, &value)?,
+            other => return Err(format!("unknown option '{}'", other)),
+        }
+        Ok(())
+    })?;
+
+    let settings = &options.settings;
+    if settings.soup_size == 0 {
+        return Err("--size must be at least 1".to_string());
+    }
+    if !(0.0..=1.0).contains(&settings.density) {
+        return Err("--density must be between 0 and 1".to_string());
+    }
+    Ok(options)
+}
+
+/// 解析数值选项（整数，或 `--density` 这样的浮点数）
+fn parse_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value '{}' for {}", value, name))
+}
+
+/// 解析 RLE 解析模式名称
+fn parse_mode(name: &str) -> Result<ParseMode, String> {
+    match name.to_ascii_lowercase().as_str() {
+        "strict" => Ok(ParseMode::Strict),
+        "lenient" => Ok(ParseMode::Lenient),
+        _ => Err(format!(
+            "unknown parse mode '{}' (expected strict or lenient)",
+            name
+        )),
+    }
+}
+
+/// 解析演化算法名称
+fn parse_algorithm(name: &str) -> Result<Algorithm, String> {
+    match name.to_ascii_lowercase().as_str() {
+        "bitpacked" | "bit-packed" | "grid" => Ok(Algorithm::BitPacked),
+        "hashlife" => Ok(Algorithm::HashLife),
+        "sparse" | "tiles" => Ok(Algorithm::SparseTiles),
+        _ => Err(format!(
+            "unknown algorithm '{}' (expected bitpacked, hashlife or sparse)",
+            name
+        )),
+    }
+}
+
+/// 执行 `run` 命令
+pub fn run(options: &RunOptions) -> Result<RunSummary, String> {
+    let LoadedInput {
+        name: pattern_name,
+        generation,
+        mut engine,
+        warnings,
+    } = load_input(options)?;
+    let life = engine.get_mut();
+
+    // 记录每一代的人口；不需要统计时直接推进，HashLife 可以一次跳过很多代
+    let mut statistics = PopulationStatistics::new(history_length(options.generations));
+    if options.stats.is_some() {
+        statistics.add_population(life.count_alive_cells());
+        for _ in 0..options.generations {
+            life.advance(1);
+            statistics.add_population(life.count_alive_cells());
+        }
+    } else {
+        life.advance(options.generations);
+    }
+
+    if let Some(path) = &options.stats {
+        let mut csv = String::from("generation,population\n");
+        for (generation, population) in statistics.get_history().iter().enumerate() {
+            let _ = writeln!(csv, "{},{}", generation, population);
+        }
+        std::fs::write(path, csv)
+            .map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+    }
+
+    if let Some(path) = &options.output {
+        let name = format!("{} after {} generations", pattern_name, options.generations);
+        let failed = |e: String| format!("failed to write {}: {}", path.display(), e);
+        let generation = generation.saturating_add(options.generations);
+        // 无界宇宙输出宏细胞文件时直接写出四叉树，不经过稠密图案
+        let result = match &engine {
+            Engine::HashLife(_) | Engine::Universe(_)
+                if PatternFormat::from_path(path) == Some(PatternFormat::Macrocell) =>
+            {
+                let info = MacrocellInfo {
+                    name,
+                    generation,
+                    ..MacrocellInfo::default()
+                };
+                match &engine {
+                    Engine::HashLife(universe) => {
+                        macrocell::save_macrocell_file(path, universe, &info)
+                    }
+                    _ => {
+                        let universe = engine_to_hashlife(engine.get()).map_err(failed)?;
+                        macrocell::save_macrocell_file(path, &universe, &info)
+                    }
+                }
+            }
+            _ => {
+                let mut pattern = engine.to_pattern(name).map_err(failed)?;
+                pattern.generation = generation;
+                save_load::save_pattern_file(path, &pattern)
+            }
+        };
+        result.map_err(|e| failed(e.to_string()))?;
+    }
+
+    let life = engine.get();
+    Ok(RunSummary {
+        generations: options.generations,
+        population: life.count_alive_cells(),
+        rule: *life.rule(),
+        warnings,
+    })
+}
+
+/// 记录 0 到 `generations` 代人口所需的历史长度，超出 `usize` 时取最大值
+fn history_length(generations: u64) -> usize {
+    usize::try_from(generations)
+        .ok()
+        .and_then(|n| n.checked_add(1))
+        .unwrap_or(usize::MAX)
+}
+
+/// 执行 `search` 命令：数据库文件存在时在其基础上继续搜索
+///
+/// # 返回值
+/// 返回搜索结束后的数据库
+pub fn search(options: &SearchOptions) -> Result<SearchDatabase, String> {
+    let path = &options.database;
+    let mut database = if path.exists() {
+        SearchDatabase::load(path)
+            .map_err(|e| format!("failed to load {}: {}", path.display(), e))?
+    } else {
+        SearchDatabase::new(&options.settings.rule)
+    };
+    if let Some(seed) = options.seed {
+        database.next_seed = seed;
+    }
+
+    // 分批搜索，每批结束后保存，中途停止也不会丢失结果
+    let mut remaining = options.soups;
+    while remaining > 0 {
+        let batch = remaining.min(SEARCH_SAVE_INTERVAL);
+        database.search(&options.settings, batch, |record, categories| {
+            let names: Vec<&str> = categories.iter().map(|category| category.name()).collect();
+            let objects = if record.rare_objects.is_empty() {
+                String::new()
+            } else {
+                format!(", {}", record.rare_objects.join(" "))
+            };
+            println!(
+                "seed {}: lifespan {}{} ({})",
+                record.seed,
+                record.lifespan,
+                objects,
+                names.join(", ")
+            );
+        })?;
+        remaining -= batch;
+        database
+            .save(path)
+            .map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+    }
+
+    if let Some(csv) = &options.csv {
+        std::fs::write(csv, database.to_csv())
+            .map_err(|e| format!("failed to write {}: {}", csv.display(), e))?;
+    }
+    Ok(database)
+}
+
+/// 读入的输入文件
+struct LoadedInput {
+    /// 图案名称
+    name: String,
+    /// 文件中记录的代数
+    generation: u64,
+    engine: Engine,
+    /// 解析时的警告
+    warnings: Vec<RleWarning>,
+}
+
+/// 读取输入文件并创建演化引擎
+///
+/// 宏细胞文件在无界宇宙中直接使用其四叉树，不转换为稠密图案
+fn load_input(options: &RunOptions) -> Result<LoadedInput, String> {
+    let path = &options.input;
+    let failed = |e: String| format!("failed to load {}: {}", path.display(), e);
+    let content = std::fs::read_to_string(path).map_err(|e| failed(e.to_string()))?;
+
+    if PatternFormat::detect(&content) == PatternFormat::Macrocell
+        && options.algorithm.is_unbounded()
+    {
+        let pattern =
+            macrocell::import_from_macrocell_string(&content).map_err(|e| failed(e.to_string()))?;
+        let mut universe = pattern.universe;
+        if let Some(rule) = options.rule {
+            check_rule(&rule, options.algorithm)?;
+            universe.set_rule(rule).map_err(failed)?;
+        }
+        let engine = if options.algorithm == Algorithm::HashLife {
+            Engine::HashLife(universe)
+        } else {
+            let mut sparse = SparseUniverse::new();
+            sparse.set_rule(*universe.rule()).map_err(failed)?;
+            if let Some(bounds) = universe.bounding_box() {
+                universe.for_each_alive_in(
+                    (bounds.min_x, bounds.min_y),
+                    (bounds.max_x + 1, bounds.max_y + 1),
+                    &mut |x, y| sparse.set_cell(x, y, CellState::Alive),
+                );
+            }
+            Engine::Universe(Box::new(sparse))
+        };
+        return Ok(LoadedInput {
+            name: pattern.info.name,
+            generation: pattern.info.generation,
+            engine,
+            warnings: Vec::new(),
+        });
+    }
+
+    let (pattern, warnings) =
+        save_load::import_pattern_string_with_mode(&content, options.parse_mode)
+            .map_err(|e| failed(e.to_string()))?;
+    let engine = build_engine(&pattern, options)?;
+    Ok(LoadedInput {
+        name: pattern.name,
+        generation: pattern.generation,
+        engine,
+        warnings,
+    })
+}
+
+/// 检查规则能否用于所选算法：无界算法无法模拟 B0 规则，结果会与位压缩网格不同
+fn check_rule(rule: &Rule, algorithm: Algorithm) -> Result<(), String> {
+    if algorithm.is_unbounded() {
+        rule.check_unbounded().map_err(|e| format!("{} (use --algorithm bitpacked)", e))
+    } else {
+        Ok(())
+    }
+}
+
+/// 根据选项创建演化引擎并载入图案
+fn build_engine(pattern: &RlePattern, options: &RunOptions) -> Result<Engine, String> {
+    let rule = match options.rule {
+        Some(rule) => rule,
+        None => pattern.parse_rule().map_err(|e| e.to_string())?,
+    };
+    check_rule(&rule, options.algorithm)?;
+
+    if options.algorithm == Algorithm::BitPacked {
+        let source = pattern.to_grid().map_err(|e| e.to_string())?;
+        let padding = options.padding;
+        let mut grid = source.new_like(
+            source.width() + 2 * padding,
+            source.height() + 2 * padding,
+        );
+        grid.set_rule(rule);
+        grid.set_threads(options.threads.unwrap_or_else(crate::game::default_thread_count));
+        for y in 0..source.height() {
+            for x in 0..source.width() {
+                if source.is_alive(x, y) {
+                    grid.set_cell(x + padding, y + padding, CellState::Alive);
+                }
+            }
+        }
+        return Ok(Engine::Grid(grid));
+    }
+
+    let mut engine = match options.algorithm {
+        Algorithm::HashLife => Engine::HashLife(HashLife::new()),
+        _ => Engine::Universe(Box::new(SparseUniverse::new())),
+    };
+    let life = engine.get_mut();
+    life.set_rule(rule)?;
+    // 图案左上角放在文件记录的位置，没有时为原点
+    let (left, top) = pattern.position.unwrap_or((0, 0));
+    for (y, row) in pattern.data.iter().enumerate() {
+        for (x, &alive) in row.iter().enumerate() {
+            if alive {
+                life.set_cell(left + x as i64, top + y as i64, CellState::Alive);
+            }
+        }
+    }
+    Ok(engine)
+}
+
+/// 把无界宇宙中的活细胞按包围盒转换为 RLE 图案，并记录包围盒在宇宙中的位置
+///
+/// 包围盒面积超过 [`macrocell::MAX_DENSE_CELLS`] 时返回错误，而不是分配巨大的稠密数组
+fn engine_to_pattern(engine: &dyn LifeEngine, name: String) -> Result<RlePattern, String> {
+    let bounds = match engine.bounding_box() {
+        Some(bounds) => bounds,
+        None => {
+            let mut pattern = RlePattern::new(name, 0, 0);
+            pattern.rule = engine.rule().to_string();
+            return Ok(pattern);
+        }
+    };
+    if bounds.width().saturating_mul(bounds.height()) > macrocell::MAX_DENSE_CELLS {
+        return Err(format!(
+            "pattern is {} x {} cells, too large for this output format (use .mc)",
+            bounds.width(),
+            bounds.height()
+        ));
+    }
+    let mut pattern = RlePattern::new(name, bounds.width() as usize, bounds.height() as usize);
+    pattern.rule = engine.rule().to_string();
+    pattern.position = Some((bounds.min_x, bounds.min_y));
+    engine.for_each_alive_in(
+        (bounds.min_x, bounds.min_y),
+        (bounds.max_x + 1, bounds.max_y + 1),
+        &mut |x, y| {
+            pattern.data[(y - bounds.min_y) as usize][(x - bounds.min_x) as usize] = true;
+        },
+    );
+    Ok(pattern)
+}
+
+/// 把无界宇宙中的活细胞复制到 HashLife 宇宙，用于写出宏细胞文件
+fn engine_to_hashlife(engine: &dyn LifeEngine) -> Result<HashLife, String> {
+    let mut universe = HashLife::new();
+    universe.set_rule(*engine.rule())?;
+    if let Some(bounds) = engine.bounding_box() {
+        engine.for_each_alive_in(
+            (bounds.min_x, bounds.min_y),
+            (bounds.max_x + 1, bounds.max_y + 1),
+            &mut |x, y| universe.set_alive(x, y, true),
+        );
+    }
+    Ok(universe)
+}
+
+/// 执行命令行并返回进程退出码
+pub fn main(args: &[String]) -> i32 {
+    match parse_args(args) {
+        Ok(Command::Help) => {
+            println!("{}", USAGE);
+            0
+        }
+        Ok(Command::Run(options)) => match run(&options) {
+            Ok(summary) => {
+                for warning in &summary.warnings {
+                    eprintln!("warning: {}: {}", options.input.display(), warning);
+                }
+                println!(
+                    "Ran {} generations with {}: population {}",
+                    summary.generations, summary.rule, summary.population
+                );
+                0
+            }
+            Err(e) => {
+                eprintln!("error: {}", e);
+                1
+            }
+        },
+        Ok(Command::Search(options)) => match search(&options) {
+            Ok(database) => {
+                println!(
+                    "Searched {} soups ({} in total, next seed {}): {} methuselahs, {} with rare objects",
+                    options.soups,
+                    database.soups_searched,
+                    database.next_seed,
+                    database.methuselahs.len(),
+                    database.rare.len()
+                );
+                0
+            }
+            Err(e) => {
+                eprintln!("error: {}", e);
+                1
+            }
+        },
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            2
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(text: &str) -> Vec<String> {
+        text.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_run_options() {
+        let command = parse_args(&args(
+            "run --input foo.rle --generations=1000 --rule B36/S23 --output out.rle --stats stats.csv",
+        ))
+        .unwrap();
+        let options = match command {
+            Command::Run(options) => options,
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(options.input, PathBuf::from("foo.rle"));
+        assert_eq!(options.generations, 1000);
+        assert_eq!(options.rule, Some(Rule::parse("B36/S23").unwrap()));
+        assert_eq!(options.algorithm, Algorithm::BitPacked);
+        assert_eq!(options.output, Some(PathBuf::from("out.rle")));
+        assert_eq!(options.stats, Some(PathBuf::from("stats.csv")));
+
+        assert_eq!(parse_args(&[]), Ok(Command::Help));
+        assert_eq!(parse_args(&args("run --help")), Ok(Command::Help));
+        assert!(parse_args(&args("run --generations 5")).is_err());
+        assert!(parse_args(&args("run --input a.rle --generations")).is_err());
+        assert!(parse_args(&args("run --input a.rle --algorithm quantum")).is_err());
+        assert!(parse_args(&args("run --input a.rle --parse sloppy")).is_err());
+        assert!(parse_args(&args("fly")).is_err());
+        assert_eq!(
+            parse_args(&args("run --input a.rle --parse strict")),
+            parse_args(&args("run --input a.rle")).map(|command| match command {
+                Command::Run(options) => Command::Run(RunOptions {
+                    parse_mode: ParseMode::Strict,
+                    ..options
+                }),
+                other => other,
+            })
+        );
+    }
+
+    #[test]
+    fn test_search_extends_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let database = dir.path().join("soups.json");
+        let csv = dir.path().join("soups.csv");
+        let command = parse_args(&args(&format!(
+            "search --soups 3 --size 8 --symmetry d4 --db {} --csv {}",
+            database.display(),
+            csv.display()
+        )))
+        .unwrap();
+        let options = match command {
+            Command::Search(options) => options,
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(options.settings.symmetry, Symmetry::D4);
+        assert_eq!(options.settings.soup_size, 8);
+
+        assert_eq!(search(&options).unwrap().soups_searched, 3);
+        // 再次运行时从上次的种子继续
+        let resumed = search(&options).unwrap();
+        assert_eq!((resumed.soups_searched, resumed.next_seed), (6, 6));
+        assert_eq!(SearchDatabase::load(&database).unwrap(), resumed);
+        assert!(std::fs::read_to_string(csv)
+            .unwrap()
+            .starts_with("category,seed,"));
+
+        assert!(parse_args(&args("search --density 2")).is_err());
+        assert!(parse_args(&args("search --symmetry Z9")).is_err());
+    }
+
+    #[test]
+    fn test_run_writes_output_and_stats() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("blinker.rle");
+        std::fs::write(&input, "x = 3, y = 1, rule = B3/S23\n3o!\n").unwrap();
+
+        let options = RunOptions {
+            input,
+            generations: 3,
+            rule: None,
+            algorithm: Algorithm::BitPacked,
+            padding: 2,
+            threads: Some(1),
+            output: Some(dir.path().join("out.rle")),
+            stats: Some(dir.path().join("stats.csv")),
+            parse_mode: ParseMode::Lenient,
+        };
+        let summary = run(&options).unwrap();
+        assert_eq!(summary.population, 3);
+        assert_eq!(summary.rule, Rule::conway());
+
+        let stats = std::fs::read_to_string(dir.path().join("stats.csv")).unwrap();
+        assert_eq!(stats, "generation,population\n0,3\n1,3\n2,3\n3,3\n");
+
+        // 3代后闪烁器为竖直方向，网格加上了2格边距
+        let grid = save_load::load_rle_file(dir.path().join("out.rle"))
+            .unwrap()
+            .to_grid()
+            .unwrap();
+        assert_eq!((grid.width(), grid.height()), (7, 5));
+        assert!(grid.is_alive(3, 1) && grid.is_alive(3, 2) && grid.is_alive(3, 3));
+    }
+
+    #[test]
+    fn test_run_unbounded_engines() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("glider.rle");
+        std::fs::write(&input, "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n").unwrap();
+
+        for algorithm in [Algorithm::HashLife, Algorithm::SparseTiles] {
+            let output = dir.path().join("glider-out.rle");
+            let options = RunOptions {
+                input: input.clone(),
+                generations: 400,
+                rule: None,
+                algorithm,
+                padding: 0,
+                threads: None,
+                output: Some(output.clone()),
+                stats: None,
+                parse_mode: ParseMode::Lenient,
+            };
+            assert_eq!(run(&options).unwrap().population, 5);
+            let pattern = save_load::load_rle_file(&output).unwrap();
+            assert_eq!((pattern.width, pattern.height), (3, 3));
+        }
+    }
+
+    #[test]
+    fn test_run_rejects_b0_on_unbounded_engines() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("block.rle");
+        std::fs::write(&input, "x = 2, y = 2, rule = B3/S23\n2o$2o!\n").unwrap();
+
+        for algorithm in [Algorithm::BitPacked, Algorithm::HashLife, Algorithm::SparseTiles] {
+            let options = RunOptions {
+                input: input.clone(),
+                generations: 3,
+                rule: Some(Rule::parse("B0/S8").unwrap()),
+                algorithm,
+                padding: 0,
+                threads: None,
+                output: None,
+                stats: None,
+                parse_mode: ParseMode::Lenient,
+            };
+            assert_eq!(run(&options).is_ok(), !algorithm.is_unbounded(), "{:?}", algorithm);
+        }
+    }
+
+    #[test]
+    fn test_history_length_saturates() {
+        assert_eq!(history_length(100), 101);
+        assert_eq!(history_length(u64::MAX), usize::MAX);
+    }
+
+    #[test]
+    fn test_run_keeps_position_and_generation() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("glider.rle");
+        let glider = "#CXRLE Pos=100,-50 Gen=7\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n";
+        std::fs::write(&input, glider).unwrap();
+
+        let output = dir.path().join("glider-out.rle");
+        let options = RunOptions {
+            input,
+            generations: 4,
+            rule: None,
+            algorithm: Algorithm::SparseTiles,
+            padding: 0,
+            threads: None,
+            output: Some(output.clone()),
+            stats: None,
+            parse_mode: ParseMode::Strict,
+        };
+        run(&options).unwrap();
+        // 滑翔机每4代沿对角线移动一格
+        let pattern = save_load::load_rle_file(&output).unwrap();
+        assert_eq!(pattern.position, Some((101, -49)));
+        assert_eq!(pattern.generation, 11);
+    }
+
+    #[test]
+    fn test_run_macrocell_without_dense_grid() {
+        // 两个相距一百万格的滑翔机，稠密网格无法容纳
+        let dir = tempfile::tempdir().unwrap();
+        let mut universe = HashLife::new();
+        for offset in [0, 1_000_000] {
+            for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+                universe.set_alive(x + offset, y + offset, true);
+            }
+        }
+        let input = dir.path().join("far.mc");
+        macrocell::save_macrocell_file(&input, &universe, &MacrocellInfo::default()).unwrap();
+
+        let output = dir.path().join("far-out.mc");
+        let options = RunOptions {
+            input: input.clone(),
+            generations: 400,
+            rule: None,
+            algorithm: Algorithm::HashLife,
+            padding: 0,
+            threads: None,
+            output: Some(output.clone()),
+            stats: None,
+            parse_mode: ParseMode::Lenient,
+        };
+        assert_eq!(run(&options).unwrap().population, 10);
+        let result = macrocell::load_macrocell_file(&output).unwrap();
+        assert_eq!(result.universe.population(), 10);
+        assert!(result.universe.is_alive(1_000_100, 1_000_102));
+
+        // 稀疏瓦片输出宏细胞文件同样不经过稠密图案，其他格式报错而不是分配巨大的数组
+        let sparse = RunOptions {
+            algorithm: Algorithm::SparseTiles,
+            ..options.clone()
+        };
+        assert_eq!(run(&sparse).unwrap().population, 10);
+        let result = macrocell::load_macrocell_file(&output).unwrap();
+        assert_eq!(result.universe.population(), 10);
+        assert_eq!(result.info.generation, 400);
+        let rle = RunOptions {
+            output: Some(dir.path().join("far-out.rle")),
+            ..sparse
+        };
+        assert!(run(&rle).unwrap_err().contains("too large"));
+
+        let options = RunOptions {
+            algorithm: Algorithm::BitPacked,
+            output: None,
+            ..options
+        };
+        assert!(run(&options).is_err());
+    }
+}
@@ -1,3 +1,9 @@
+use crate::random::{self, SplitMix64, Symmetry};
+use crate::rules::{self, Rule};
+use crate::stamp::{Stamp, StampMode};
+use crate::topology::Topology;
+use std::sync::atomic::{AtomicU64, Ordering};
+
 /// 细胞状态枚举
 /// 在康威生命游戏中，每个细胞只有两种状态：存活或死亡
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -8,16 +14,269 @@ pub enum CellState {
     Dead,
 }
 
+/// 演化算法
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Algorithm {
+    /// 位压缩网格：逐代计算，支持各种边界拓扑
+    #[default]
+    BitPacked,
+    /// HashLife：无界宇宙，每步可推进 2^k 代
+    HashLife,
+    /// 稀疏瓦片：无界宇宙，只存储包含存活细胞的 64x64 瓦片
+    SparseTiles,
+}
+
+impl Algorithm {
+    /// 所有可选的算法，用于界面列表
+    pub const ALL: [Algorithm; 3] =
+        [Algorithm::BitPacked, Algorithm::HashLife, Algorithm::SparseTiles];
+
+    /// 算法的显示名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            Algorithm::BitPacked => "Bit-packed grid",
+            Algorithm::HashLife => "HashLife",
+            Algorithm::SparseTiles => "Sparse tiles",
+        }
+    }
+
+    /// 是否使用无界宇宙（网格只是宇宙中的一个窗口）
+    pub fn is_unbounded(&self) -> bool {
+        !matches!(self, Algorithm::BitPacked)
+    }
+}
+
+/// 存活细胞的外接矩形（包含边界）
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BoundingBox {
+    pub min_x: i64,
+    pub min_y: i64,
+    pub max_x: i64,
+    pub max_y: i64,
+}
+
+impl BoundingBox {
+    /// 只包含一个细胞的矩形
+    pub fn around(x: i64, y: i64) -> Self {
+        Self {
+            min_x: x,
+            min_y: y,
+            max_x: x,
+            max_y: y,
+        }
+    }
+
+    /// 扩展矩形使其包含指定细胞
+    pub fn include(&mut self, x: i64, y: i64) {
+        self.min_x = self.min_x.min(x);
+        self.min_y = self.min_y.min(y);
+        self.max_x = self.max_x.max(x);
+        self.max_y = self.max_y.max(y);
+    }
+
+    /// 矩形宽度（列数）
+    pub fn width(&self) -> u64 {
+        self.max_x.abs_diff(self.min_x) + 1
+    }
+
+    /// 矩形高度（行数）
+    pub fn height(&self) -> u64 {
+        self.max_y.abs_diff(self.min_y) + 1
+    }
+
+    /// 矩形是否完全位于 [min, max) 窗口内
+    pub fn is_inside(&self, (min_x, min_y): (i64, i64), (max_x, max_y): (i64, i64)) -> bool {
+        self.min_x >= min_x && self.min_y >= min_y && self.max_x < max_x && self.max_y < max_y
+    }
+}
+
+/// 生命游戏演化引擎的统一接口
+///
+/// 坐标使用有符号整数，以便无界引擎表示负坐标；
+/// 有界网格读取越界坐标时返回死亡细胞，写入越界坐标时忽略
+pub trait LifeEngine {
+    /// 获取当前演化规则
+    fn rule(&self) -> &Rule;
+    /// 设置演化规则；引擎无法模拟该规则时返回错误，原规则保持不变
+    fn set_rule(&mut self, rule: Rule) -> Result<(), String>;
+    /// 获取指定位置细胞的状态
+    fn get_cell(&self, x: i64, y: i64) -> &CellState;
+    /// 设置指定位置细胞的状态
+    fn set_cell(&mut self, x: i64, y: i64, state: CellState);
+    /// 将所有细胞设置为死亡状态
+    fn clear(&mut self);
+    /// 计算并更新到下一代
+    fn next_generation(&mut self);
+    /// 推进指定的代数
+    fn advance(&mut self, generations: u64) {
+        for _ in 0..generations {
+            self.next_generation();
+        }
+    }
+    /// 存活细胞的总数
+    fn count_alive_cells(&self) -> usize;
+    /// 存活细胞的外接矩形，没有存活细胞时返回 `None`
+    fn bounding_box(&self) -> Option<BoundingBox>;
+    /// 遍历矩形区域 [min_x, max_x) x [min_y, max_y) 内的所有存活细胞（顺序不定）
+    fn for_each_alive_in(&self, min: (i64, i64), max: (i64, i64), visit: &mut dyn FnMut(i64, i64));
+
+    /// 用网格覆盖引擎中对应的窗口区域，网格左上角位于 `origin`
+    fn load_window(&mut self, grid: &Grid, origin: (i64, i64)) {
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                let (ex, ey) = (origin.0 + x as i64, origin.1 + y as i64);
+                let state = *Grid::get_cell(grid, x, y);
+                if *self.get_cell(ex, ey) != state {
+                    self.set_cell(ex, ey, state);
+                }
+            }
+        }
+    }
+
+    /// 将引擎中的窗口区域写入网格，网格左上角对应 `origin`
+    fn render_window(&self, grid: &mut Grid, origin: (i64, i64)) {
+        Grid::clear(grid);
+        let max = (origin.0 + grid.width() as i64, origin.1 + grid.height() as i64);
+        self.for_each_alive_in(origin, max, &mut |x, y| {
+            Grid::set_cell(grid, (x - origin.0) as usize, (y - origin.1) as usize, CellState::Alive);
+        });
+    }
+
+    /// 把图案左上角放在 (x, y) 处，按合并方式与已有细胞合并；有界网格外的部分被裁掉
+    fn stamp(&mut self, stamp: &Stamp, x: i64, y: i64, mode: StampMode) {
+        stamp.for_each_cell(|dx, dy, cell| {
+            let (cx, cy) = (x + dx as i64, y + dy as i64);
+            let alive = *self.get_cell(cx, cy) == CellState::Alive;
+            let merged = mode.apply(alive, cell);
+            if merged != alive {
+                self.set_cell(cx, cy, if merged { CellState::Alive } else { CellState::Dead });
+            }
+        });
+    }
+}
+
+/// 两个同尺寸网格之间的差异：记录发生变化的存储字及其异或值
+///
+/// 异或差异是自逆的：应用一次从旧状态变为新状态，再应用一次即恢复旧状态
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GridDiff {
+    width: usize,
+    height: usize,
+    words: Vec<(usize, u64)>,
+}
+
+impl GridDiff {
+    /// 比较两组同样布局的存储字
+    fn between(width: usize, height: usize, from: &[u64], to: &[u64]) -> Self {
+        let words = from
+            .iter()
+            .zip(to.iter())
+            .enumerate()
+            .filter(|(_, (a, b))| a != b)
+            .map(|(index, (a, b))| (index, a ^ b))
+            .collect();
+        Self {
+            width,
+            height,
+            words,
+        }
+    }
+
+    /// 把差异异或到存储字上
+    fn apply_to(&self, cells: &mut [u64]) {
+        for &(index, xor) in &self.words {
+            cells[index] ^= xor;
+        }
+    }
+
+    /// 两个网格是否完全相同
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// 估算占用的内存（字节）
+    pub fn memory_usage(&self) -> usize {
+        std::mem::size_of::<Self>() + self.words.len() * std::mem::size_of::<(usize, u64)>()
+    }
+}
+
+/// 网格的紧凑快照：只保存细胞、尺寸、规则和边界拓扑，不包含演化用的缓冲区和掩码
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridSnapshot {
+    width: usize,
+    height: usize,
+    cells: Vec<u64>,
+    rule: Rule,
+    topology: Topology,
+}
+
+impl GridSnapshot {
+    /// 计算从快照到网格 `grid` 的细胞差异
+    ///
+    /// 尺寸、规则或边界拓扑不同时返回 `None`
+    pub fn diff(&self, grid: &Grid) -> Option<GridDiff> {
+        if (self.width, self.height) != (grid.width, grid.height)
+            || self.rule != grid.rule
+            || self.topology != grid.topology
+        {
+            return None;
+        }
+        Some(GridDiff::between(self.width, self.height, &self.cells, &grid.cells))
+    }
+
+    /// 应用细胞差异；差异与快照尺寸不符时不做修改并返回 `false`
+    pub fn apply_diff(&mut self, diff: &GridDiff) -> bool {
+        if (diff.width, diff.height) != (self.width, self.height) {
+            return false;
+        }
+        diff.apply_to(&mut self.cells);
+        true
+    }
+
+    /// 估算占用的内存（字节）
+    pub fn memory_usage(&self) -> usize {
+        std::mem::size_of::<Self>() + self.cells.len() * std::mem::size_of::<u64>()
+    }
+}
+
+/// 全局修订号计数器，保证不同网格的修订号互不相同
+static NEXT_REVISION: AtomicU64 = AtomicU64::new(1);
+
+/// 每个存储字包含的细胞数量
+const BITS_PER_WORD: usize = 64;
+
+/// 多线程演化时每个线程至少负责的行数，避免小网格上线程开销大于收益
+const MIN_ROWS_PER_BAND: usize = 16;
+
 /// 游戏网格结构体
 /// 包含游戏的核心状态和逻辑
+///
+/// 细胞以位压缩的方式存储：每个 `u64` 保存同一行中相邻的64个细胞。
+/// 每行左右各多出一个“幽灵”位，网格上下也各多出一行幽灵行，
+/// 演化时根据边界拓扑填充幽灵细胞，使内部所有细胞都能用同一套位运算计算。
+#[derive(Clone)]
 pub struct Grid {
     /// 网格宽度（列数）
     width: usize,
     /// 网格高度（行数）
     height: usize,
-    /// 一维向量存储所有细胞的状态
-    /// cells[y * width + x] 表示位置(x,y)的细胞状态
-    cells: Vec<CellState>,
+    /// 每行（含左右幽灵位）占用的字数
+    words_per_row: usize,
+    /// 位压缩的细胞状态，共 (height + 2) 行
+    /// 细胞(x,y)位于第 y+1 行的第 x+1 位
+    cells: Vec<u64>,
+    /// 双缓冲：演化时写入的下一代状态，计算完成后与 `cells` 交换
+    next_cells: Vec<u64>,
+    /// 每行中有效细胞（非幽灵位）的掩码
+    row_mask: Vec<u64>,
+    /// 当前使用的演化规则（默认为康威规则 B3/S23）
+    rule: Rule,
+    /// 边界拓扑（默认为有界平面）
+    topology: Topology,
+    /// 演化时使用的线程数（1 表示单线程）
+    threads: usize,
+    /// 修订号：细胞每次变化后更新，用于判断网格内容是否被修改过
+    revision: u64,
 }
 
 impl Grid {
@@ -30,15 +289,44 @@ impl Grid {
     /// # 返回值
     /// 返回一个所有细胞都处于死亡状态的新网格
     pub fn new(width: usize, height: usize) -> Self {
-        // 创建一个一维向量，所有细胞初始状态为死亡
-        let cells = vec![CellState::Dead; width * height];
+        // 每行额外保留左右两个幽灵位
+        let words_per_row = (width + 2 + BITS_PER_WORD - 1) / BITS_PER_WORD;
+        let row_mask = (0..words_per_row)
+            .map(|word| {
+                (0..BITS_PER_WORD)
+                    .map(|bit| word * BITS_PER_WORD + bit)
+                    .filter(|&position| position >= 1 && position <= width)
+                    .fold(0u64, |mask, position| mask | 1 << (position % BITS_PER_WORD))
+            })
+            .collect();
+
+        // 所有细胞初始状态为死亡
+        let cells = vec![0; words_per_row * (height + 2)];
         Self {
             width,
             height,
+            words_per_row,
+            next_cells: cells.clone(),
             cells,
+            row_mask,
+            rule: Rule::default(),
+            topology: Topology::default(),
+            threads: 1,
+            revision: NEXT_REVISION.fetch_add(1, Ordering::Relaxed),
         }
     }
 
+    /// 创建一个指定尺寸的空网格，沿用当前网格的规则、边界拓扑和线程设置
+    pub fn new_like(&self, width: usize, height: usize) -> Self {
+        let mut grid = Self::new(width, height);
+        grid.rule = self.rule;
+        grid.threads = self.threads;
+        if !self.topology.requires_square() || width == height {
+            grid.topology = self.topology;
+        }
+        grid
+    }
+
     /// 获取网格宽度
     pub fn width(&self) -> usize {
         self.width
@@ -49,6 +337,131 @@ impl Grid {
         self.height
     }
 
+    /// 获取当前演化规则
+    pub fn rule(&self) -> &Rule {
+        &self.rule
+    }
+
+    /// 设置演化规则
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+    }
+
+    /// 获取边界拓扑
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// 设置边界拓扑
+    ///
+    /// 球面等要求正方形网格的拓扑在网格不是正方形时返回错误，拓扑保持不变
+    pub fn set_topology(&mut self, topology: Topology) -> Result<(), String> {
+        if topology.requires_square() && self.width != self.height {
+            return Err(format!(
+                "{} boundary requires a square grid (current: {}x{})",
+                topology.name(),
+                self.width,
+                self.height
+            ));
+        }
+        self.topology = topology;
+        Ok(())
+    }
+
+    /// 获取演化时使用的线程数
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// 设置演化时使用的线程数（至少为1）
+    ///
+    /// 网格按行划分为若干条带并行计算，结果与单线程完全一致
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    /// 获取修订号：内容相同的网格副本修订号相同，任何修改都会产生新的修订号
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// 估算网格占用的内存（字节）
+    pub fn memory_usage(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + (self.cells.len() + self.next_cells.len() + self.row_mask.len())
+                * std::mem::size_of::<u64>()
+    }
+
+    /// 计算从当前网格到 `other` 的细胞差异
+    ///
+    /// 尺寸、规则或边界拓扑不同时无法用细胞差异表示，返回 `None`
+    pub fn diff(&self, other: &Grid) -> Option<GridDiff> {
+        if (self.width, self.height) != (other.width, other.height)
+            || self.rule != other.rule
+            || self.topology != other.topology
+        {
+            return None;
+        }
+        Some(GridDiff::between(self.width, self.height, &self.cells, &other.cells))
+    }
+
+    /// 应用细胞差异；差异与网格尺寸不符时不做修改并返回 `false`
+    pub fn apply_diff(&mut self, diff: &GridDiff) -> bool {
+        if (diff.width, diff.height) != (self.width, self.height) {
+            return false;
+        }
+        diff.apply_to(&mut self.cells);
+        self.touch();
+        true
+    }
+
+    /// 创建只包含细胞、尺寸、规则和边界拓扑的紧凑快照
+    pub fn snapshot(&self) -> GridSnapshot {
+        GridSnapshot {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.clone(),
+            rule: self.rule,
+            topology: self.topology,
+        }
+    }
+
+    /// 从快照恢复细胞、尺寸、规则和边界拓扑，保留当前的线程设置
+    pub fn restore(&mut self, snapshot: &GridSnapshot) {
+        let mut grid = Self::new(snapshot.width, snapshot.height);
+        grid.cells.copy_from_slice(&snapshot.cells);
+        grid.rule = snapshot.rule;
+        grid.topology = snapshot.topology;
+        grid.threads = self.threads;
+        *self = grid;
+    }
+
+    /// 标记细胞已被修改
+    fn touch(&mut self) {
+        self.revision = NEXT_REVISION.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// 计算存储位置：返回(字索引, 位掩码)
+    ///
+    /// 坐标包含幽灵区域，范围为 x ∈ [-1, width]、y ∈ [-1, height]
+    fn bit_position(&self, x: i64, y: i64) -> (usize, u64) {
+        let row = (y + 1) as usize;
+        let column = (x + 1) as usize;
+        (
+            row * self.words_per_row + column / BITS_PER_WORD,
+            1 << (column % BITS_PER_WORD),
+        )
+    }
+
+    /// 判断指定位置的细胞是否存活，越界坐标视为死亡
+    pub fn is_alive(&self, x: usize, y: usize) -> bool {
+        if x >= self.width || y >= self.height {
+            return false;
+        }
+        let (word, mask) = self.bit_position(x as i64, y as i64);
+        self.cells[word] & mask != 0
+    }
+
     /// 获取指定位置细胞的状态
     ///
     /// # 参数
@@ -58,7 +471,11 @@ impl Grid {
     /// # 返回值
     /// 返回该位置细胞状态的引用
     pub fn get_cell(&self, x: usize, y: usize) -> &CellState {
-        &self.cells[y * self.width + x]
+        if self.is_alive(x, y) {
+            &CellState::Alive
+        } else {
+            &CellState::Dead
+        }
     }
 
     /// 设置指定位置细胞的状态
@@ -69,7 +486,12 @@ impl Grid {
     /// * `state` - 要设置的细胞状态
     pub fn set_cell(&mut self, x: usize, y: usize, state: CellState) {
         if x < self.width && y < self.height {
-            self.cells[y * self.width + x] = state;
+            self.touch();
+            let (word, mask) = self.bit_position(x as i64, y as i64);
+            match state {
+                CellState::Alive => self.cells[word] |= mask,
+                CellState::Dead => self.cells[word] &= !mask,
+            }
         }
     }
 
@@ -81,17 +503,16 @@ impl Grid {
     /// * `y` - 细胞的y坐标（行）
     pub fn toggle_cell(&mut self, x: usize, y: usize) {
         if x < self.width && y < self.height {
-            let index = y * self.width + x;
-            self.cells[index] = match self.cells[index] {
-                CellState::Alive => CellState::Dead,
-                CellState::Dead => CellState::Alive,
-            };
+            self.touch();
+            let (word, mask) = self.bit_position(x as i64, y as i64);
+            self.cells[word] ^= mask;
         }
     }
 
     /// 计算指定位置细胞的存活邻居数量
     ///
     /// 在康威生命游戏中，每个细胞有8个邻居（包括对角线方向）
+    /// 越过边缘的邻居按照当前边界拓扑映射回网格内部
     ///
     /// # 参数
     /// * `x` - 细胞的x坐标（列）
@@ -99,7 +520,7 @@ impl Grid {
     ///
     /// # 返回值
     /// 返回该细胞周围存活邻居的数量
-    fn count_neighbors(&self, x: usize, y: usize) -> usize {
+    pub fn count_neighbors(&self, x: usize, y: usize) -> usize {
         let mut count = 0;
         
         // 定义8个邻居的相对位置偏移量
@@ -111,13 +532,12 @@ impl Grid {
         
         // 遍历所有邻居位置
         for (dx, dy) in NEIGHBOR_OFFSETS.iter() {
-            let nx = x as i32 + dx;
-            let ny = y as i32 + dy;
-            
-            // 检查邻居是否在网格范围内
-            if nx >= 0 && ny >= 0 && (nx as usize) < self.width && (ny as usize) < self.height {
-                let index = (ny as usize) * self.width + (nx as usize);
-                if self.cells[index] == CellState::Alive {
+            let nx = x as i64 + *dx as i64;
+            let ny = y as i64 + *dy as i64;
+
+            // 根据边界拓扑将邻居坐标映射到网格内（有界平面上越界的邻居不计数）
+            if let Some((nx, ny)) = self.topology.wrap(nx, ny, self.width, self.height) {
+                if self.is_alive(nx, ny) {
                     count += 1;
                 }
             }
@@ -125,80 +545,152 @@ impl Grid {
         count
     }
 
+    /// 根据边界拓扑填充幽灵细胞（网格外围一圈）
+    ///
+    /// 有界平面的幽灵细胞始终为死亡状态，无需填充
+    fn fill_ghost_cells(&mut self) {
+        if self.topology == Topology::Bounded {
+            return;
+        }
+
+        let (width, height) = (self.width as i64, self.height as i64);
+        let ghosts = (-1..=width)
+            .flat_map(|x| [(x, -1), (x, height)])
+            .chain((0..height).flat_map(|y| [(-1, y), (width, y)]));
+
+        for (x, y) in ghosts {
+            let alive = self
+                .topology
+                .wrap(x, y, self.width, self.height)
+                .is_some_and(|(wx, wy)| self.is_alive(wx, wy));
+            let (word, mask) = self.bit_position(x, y);
+            if alive {
+                self.cells[word] |= mask;
+            } else {
+                self.cells[word] &= !mask;
+            }
+        }
+    }
+
     /// 计算并更新到下一代
     ///
-    /// 根据康威生命游戏的规则更新所有细胞：
-    /// 1. 存活细胞有2-3个存活邻居时继续存活，否则死亡
-    /// 2. 死亡细胞有恰好3个存活邻居时复活
+    /// 根据当前规则（默认康威规则 B3/S23）更新所有细胞：
+    /// 1. 存活细胞的邻居数量满足存活条件（S）时继续存活，否则死亡
+    /// 2. 死亡细胞的邻居数量满足出生条件（B）时复活
     /// 3. 其他情况保持死亡状态
+    ///
+    /// 计算以64个细胞为一组并行进行，结果写入后备缓冲区后再交换；
+    /// 设置了多个线程时，各线程分别计算一段连续的行
     pub fn next_generation(&mut self) {
-        // 克隆当前状态，避免在计算过程中修改原数据
-        let mut new_cells = self.cells.clone();
-
-        // 遍历网格中的每个细胞
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let index = y * self.width + x;
-                let neighbors = self.count_neighbors(x, y);
-
-                // 根据康威生命游戏规则决定细胞的下一代状态
-                new_cells[index] = match (self.cells[index], neighbors) {
-                    // 存活细胞有2或3个邻居时继续存活
-                    (CellState::Alive, 2) | (CellState::Alive, 3) => CellState::Alive,
-                    // 死亡细胞有恰好3个邻居时复活
-                    (CellState::Dead, 3) => CellState::Alive,
-                    // 其他情况都是死亡
-                    _ => CellState::Dead,
-                };
-            }
+        if self.width == 0 || self.height == 0 {
+            return;
         }
 
-        // 用新计算的状态替换当前状态
-        self.cells = new_cells;
+        // 根据边界拓扑准备幽灵细胞
+        self.fill_ghost_cells();
+
+        let words_per_row = self.words_per_row;
+        let (top_ghost, rest) = self.next_cells.split_at_mut(words_per_row);
+        let (interior, bottom_ghost) = rest.split_at_mut(words_per_row * self.height);
+        top_ghost.fill(0);
+        bottom_ghost.fill(0);
+
+        let threads = self.threads.min(self.height / MIN_ROWS_PER_BAND).max(1);
+        if threads == 1 {
+            step_rows(&self.cells, interior, 1, words_per_row, &self.row_mask, &self.rule);
+        } else {
+            // 按行划分条带，每个线程只写入自己的条带，读取共享的当前状态
+            let band_rows = (self.height + threads - 1) / threads;
+            let (cells, row_mask, rule) = (&self.cells, &self.row_mask, &self.rule);
+            std::thread::scope(|scope| {
+                for (band, output) in interior.chunks_mut(band_rows * words_per_row).enumerate() {
+                    scope.spawn(move || {
+                        step_rows(cells, output, 1 + band * band_rows, words_per_row, row_mask, rule)
+                    });
+                }
+            });
+        }
+
+        // 交换缓冲区：旧状态留作下一次计算的后备缓冲区
+        std::mem::swap(&mut self.cells, &mut self.next_cells);
+        self.touch();
     }
 
     /// 清空网格，将所有细胞设置为死亡状态
     pub fn clear(&mut self) {
-        for cell in &mut self.cells {
-            *cell = CellState::Dead;
-        }
+        self.cells.fill(0);
+        self.touch();
     }
 
-    /// 随机化网格中的细胞状态
+    /// 随机化网格中的细胞状态，种子取自当前时间
     ///
     /// # 参数
     /// * `density` - 细胞存活的概率，范围[0.0, 1.0]
     ///   - 0.0 表示所有细胞都死亡
     ///   - 1.0 表示所有细胞都存活
     ///   - 0.3 表示大约30%的细胞会存活
-    pub fn randomize(&mut self, density: f32) {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-
-        // 使用当前时间作为随机种子
-        let seed = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_nanos();
-
-        // 创建基于时间种子的伪随机数生成器
-        let mut hasher = DefaultHasher::new();
-        seed.hash(&mut hasher);
-        let mut rng_state = hasher.finish();
-
-        // 遍历网格中的每个细胞
-        for index in 0..self.cells.len() {
-            // 生成下一个伪随机数（线性同余生成器）
-            rng_state = rng_state.wrapping_mul(1664525).wrapping_add(1013904223);
-            // 将随机数转换为[0.0, 1.0]范围的浮点数
-            let rand_val = (rng_state >> 32) as f32 / u32::MAX as f32;
-
-            // 根据密度参数决定细胞状态
-            self.cells[index] = if rand_val < density {
-                CellState::Alive
-            } else {
-                CellState::Dead
-            };
+    ///
+    /// # 返回值
+    /// 返回使用的种子，可用 `randomize_with_seed` 复现同样的网格
+    pub fn randomize(&mut self, density: f32) -> u64 {
+        let seed = random::seed_from_clock();
+        self.randomize_with_seed(seed, density);
+        seed
+    }
+
+    /// 使用指定种子随机化整个网格，相同的种子和密度总是产生相同的结果
+    pub fn randomize_with_seed(&mut self, seed: u64, density: f32) {
+        let region = (0, 0, self.width, self.height);
+        self.randomize_region(seed, density, Symmetry::C1, region);
+    }
+
+    /// 使用指定种子和对称性随机化网格中的一个矩形区域，区域外的细胞保持不变
+    ///
+    /// # 参数
+    /// * `seed` - 随机种子
+    /// * `density` - 细胞存活的概率，范围[0.0, 1.0]
+    /// * `symmetry` - 随机汤的对称性；C4 和 D8 使用区域中心最大的正方形
+    /// * `region` - (x, y, 宽, 高)，超出网格的部分会被裁剪
+    pub fn randomize_region(
+        &mut self,
+        seed: u64,
+        density: f32,
+        symmetry: Symmetry,
+        region: (usize, usize, usize, usize),
+    ) {
+        let (x, y) = (region.0.min(self.width), region.1.min(self.height));
+        let width = region.2.min(self.width - x);
+        let height = region.3.min(self.height - y);
+        let (x, y, width, height) = if symmetry.requires_square() {
+            let side = width.min(height);
+            (x + (width - side) / 2, y + (height - side) / 2, side, side)
+        } else {
+            (x, y, width, height)
+        };
+
+        // 按行优先顺序遍历，只为每组对称细胞中最靠前的一个抽取随机数
+        let mut rng = SplitMix64::new(seed);
+        for dy in 0..height {
+            for dx in 0..width {
+                let mut representative = true;
+                symmetry.for_each_image(dx, dy, width, height, |ix, iy| {
+                    if (iy, ix) < (dy, dx) {
+                        representative = false;
+                    }
+                });
+                if !representative {
+                    continue;
+                }
+
+                let state = if rng.next_f32() < density {
+                    CellState::Alive
+                } else {
+                    CellState::Dead
+                };
+                symmetry.for_each_image(dx, dy, width, height, |ix, iy| {
+                    self.set_cell(x + ix, y + iy, state);
+                });
+            }
         }
     }
 
@@ -239,10 +731,80 @@ impl Grid {
     /// # 返回值
     /// 返回当前网格中存活细胞的数量
     pub fn count_alive_cells(&self) -> usize {
+        // 幽灵位在演化之外始终为0，因此可以直接统计所有字中的置位数
         self.cells
             .iter()
-            .filter(|&&cell| cell == CellState::Alive)
-            .count()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+}
+
+/// 有界网格作为演化引擎：坐标从左上角 (0, 0) 开始
+impl LifeEngine for Grid {
+    fn rule(&self) -> &Rule {
+        Grid::rule(self)
+    }
+
+    fn set_rule(&mut self, rule: Rule) -> Result<(), String> {
+        Grid::set_rule(self, rule);
+        Ok(())
+    }
+
+    fn get_cell(&self, x: i64, y: i64) -> &CellState {
+        match (usize::try_from(x), usize::try_from(y)) {
+            (Ok(x), Ok(y)) => Grid::get_cell(self, x, y),
+            _ => &CellState::Dead,
+        }
+    }
+
+    fn set_cell(&mut self, x: i64, y: i64, state: CellState) {
+        if let (Ok(x), Ok(y)) = (usize::try_from(x), usize::try_from(y)) {
+            Grid::set_cell(self, x, y, state);
+        }
+    }
+
+    fn clear(&mut self) {
+        Grid::clear(self);
+    }
+
+    fn next_generation(&mut self) {
+        Grid::next_generation(self);
+    }
+
+    fn count_alive_cells(&self) -> usize {
+        Grid::count_alive_cells(self)
+    }
+
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        let mut bounds: Option<BoundingBox> = None;
+        self.for_each_alive_in((0, 0), (self.width as i64, self.height as i64), &mut |x, y| {
+            match bounds.as_mut() {
+                Some(bounds) => bounds.include(x, y),
+                None => bounds = Some(BoundingBox::around(x, y)),
+            }
+        });
+        bounds
+    }
+
+    fn for_each_alive_in(&self, min: (i64, i64), max: (i64, i64), visit: &mut dyn FnMut(i64, i64)) {
+        let clamp = |value: i64, size: usize| value.clamp(0, size as i64) as usize;
+        let (min_x, max_x) = (clamp(min.0, self.width), clamp(max.0, self.width));
+        let (min_y, max_y) = (clamp(min.1, self.height), clamp(max.1, self.height));
+        for y in min_y..max_y {
+            // 逐字跳过没有存活细胞的部分
+            let row = &self.cells[(y + 1) * self.words_per_row..(y + 2) * self.words_per_row];
+            for (index, &word) in row.iter().enumerate() {
+                let mut bits = word;
+                while bits != 0 {
+                    // 第 x+1 位对应细胞 x；幽灵位在范围检查中被排除
+                    let column = index * BITS_PER_WORD + bits.trailing_zeros() as usize;
+                    bits &= bits - 1;
+                    if column > min_x && column <= max_x {
+                        visit(column as i64 - 1, y as i64);
+                    }
+                }
+            }
+        }
     }
 }
 
@@ -254,9 +816,107 @@ impl Default for Grid {
     }
 }
 
+/// 以位并行方式计算一段连续行的下一代
+///
+/// # 参数
+/// * `cells` - 当前状态（包含幽灵行和幽灵位）
+/// * `output` - 输出缓冲区，对应从 `first_row` 开始的若干行
+/// * `first_row` - 输出的第一行在 `cells` 中的行号（第0行为上方幽灵行）
+/// * `words_per_row` - 每行占用的字数
+/// * `row_mask` - 每行中有效细胞的掩码
+/// * `rule` - 演化规则
+fn step_rows(
+    cells: &[u64],
+    output: &mut [u64],
+    first_row: usize,
+    words_per_row: usize,
+    row_mask: &[u64],
+    rule: &Rule,
+) {
+    let conway = rule.is_conway();
+
+    for (offset, out_row) in output.chunks_mut(words_per_row).enumerate() {
+        let row = first_row + offset;
+        let above = &cells[(row - 1) * words_per_row..row * words_per_row];
+        let current = &cells[row * words_per_row..(row + 1) * words_per_row];
+        let below = &cells[(row + 1) * words_per_row..(row + 2) * words_per_row];
+
+        for (word, out) in out_row.iter_mut().enumerate() {
+            let neighbors = [
+                west(above, word),
+                above[word],
+                east(above, word),
+                west(current, word),
+                east(current, word),
+                west(below, word),
+                below[word],
+                east(below, word),
+            ];
+            let next = if conway {
+                rules::conway_next_word(current[word], &neighbors)
+            } else {
+                rule.next_word(current[word], &neighbors)
+            };
+            *out = next & row_mask[word];
+        }
+    }
+}
+
+/// 每个细胞左侧邻居组成的字：第 i 位为第 i-1 个细胞
+fn west(row: &[u64], word: usize) -> u64 {
+    let carry = if word > 0 { row[word - 1] >> (BITS_PER_WORD - 1) } else { 0 };
+    (row[word] << 1) | carry
+}
+
+/// 每个细胞右侧邻居组成的字：第 i 位为第 i+1 个细胞
+fn east(row: &[u64], word: usize) -> u64 {
+    let carry = if word + 1 < row.len() { row[word + 1] << (BITS_PER_WORD - 1) } else { 0 };
+    (row[word] >> 1) | carry
+}
+
+/// 默认的演化线程数：可用的CPU核心数
+pub fn default_thread_count() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
+
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
+    use crate::topology::KleinTwist;
+
+    /// 把网格逐行写成字符串（`*` 为活细胞，`.` 为死细胞），供各模块的测试比较
+    pub(crate) fn rows(grid: &Grid) -> Vec<String> {
+        (0..grid.height())
+            .map(|y| {
+                (0..grid.width())
+                    .map(|x| if grid.is_alive(x, y) { '*' } else { '.' })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// 逐细胞计算下一代的参考实现，用于验证位并行实现
+    fn reference_next_generation(grid: &Grid) -> Vec<bool> {
+        let mut next = Vec::with_capacity(grid.width() * grid.height());
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                next.push(grid.rule().next_state(grid.is_alive(x, y), grid.count_neighbors(x, y)));
+            }
+        }
+        next
+    }
+
+    /// 用简单的线性同余序列填充网格，保证测试可重复
+    fn fill_pseudo_random(grid: &mut Grid, mut state: u64) {
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                if state >> 62 == 0 {
+                    grid.set_cell(x, y, CellState::Alive);
+                }
+            }
+        }
+    }
 
     #[test]
     fn test_grid_creation() {
@@ -378,4 +1038,294 @@ mod tests {
         assert_eq!(grid.get_cell(4, 5), &CellState::Alive); // O
         assert_eq!(grid.get_cell(5, 5), &CellState::Alive); // O
     }
+
+    #[test]
+    fn test_custom_rule() {
+        // Seeds (B2/S): 两个相邻细胞在下一代会产生新的细胞，自身全部死亡
+        let mut grid = Grid::new(6, 6);
+        grid.set_rule(Rule::parse("B2/S").unwrap());
+        grid.set_cell(2, 2, CellState::Alive);
+        grid.set_cell(3, 2, CellState::Alive);
+
+        grid.next_generation();
+
+        assert_eq!(grid.get_cell(2, 2), &CellState::Dead);
+        assert_eq!(grid.get_cell(3, 2), &CellState::Dead);
+        assert_eq!(grid.get_cell(2, 1), &CellState::Alive);
+        assert_eq!(grid.get_cell(3, 1), &CellState::Alive);
+        assert_eq!(grid.get_cell(2, 3), &CellState::Alive);
+        assert_eq!(grid.get_cell(3, 3), &CellState::Alive);
+        assert_eq!(grid.count_alive_cells(), 4);
+    }
+
+    #[test]
+    fn test_glider_on_torus() {
+        let mut grid = Grid::new(8, 8);
+        grid.set_topology(Topology::Torus).unwrap();
+        grid.load_pattern(&[" O ", "  O", "OOO"], 0, 0);
+
+        // 滑翔机每4代沿对角线移动一格，32代后应回到原位
+        for _ in 0..32 {
+            grid.next_generation();
+            assert_eq!(grid.count_alive_cells(), 5);
+        }
+        assert_eq!(grid.get_cell(1, 0), &CellState::Alive);
+        assert_eq!(grid.get_cell(2, 1), &CellState::Alive);
+        assert_eq!(grid.get_cell(0, 2), &CellState::Alive);
+        assert_eq!(grid.get_cell(1, 2), &CellState::Alive);
+        assert_eq!(grid.get_cell(2, 2), &CellState::Alive);
+    }
+
+    #[test]
+    fn test_neighbor_counting_across_edges() {
+        let mut grid = Grid::new(5, 5);
+        grid.set_cell(4, 2, CellState::Alive);
+
+        // 有界平面：左边缘的细胞看不到右边缘的细胞
+        assert_eq!(grid.count_neighbors(0, 2), 0);
+
+        grid.set_topology(Topology::Torus).unwrap();
+        assert_eq!(grid.count_neighbors(0, 1), 1);
+        assert_eq!(grid.count_neighbors(0, 2), 1);
+
+        // 交叉曲面：穿过左右边缘时上下翻转，(4,2) 仍在中间行
+        grid.set_topology(Topology::CrossSurface).unwrap();
+        assert_eq!(grid.count_neighbors(0, 2), 1);
+        grid.set_cell(4, 2, CellState::Dead);
+        grid.set_cell(4, 1, CellState::Alive);
+        assert_eq!(grid.count_neighbors(0, 3), 1);
+        assert_eq!(grid.count_neighbors(0, 1), 0);
+    }
+
+    #[test]
+    fn test_bit_packed_step_matches_reference() {
+        let rules = ["B3/S23", "B36/S23", "B2/S", "B3678/S34678", "B1357/S1357"];
+        let topologies = [
+            Topology::Bounded,
+            Topology::Torus,
+            Topology::KleinBottle(KleinTwist::TopBottom),
+            Topology::KleinBottle(KleinTwist::LeftRight),
+            Topology::CrossSurface,
+        ];
+
+        // 宽度覆盖单字、跨字边界以及恰好填满字的情况
+        for (index, &(width, height)) in [(5, 4), (62, 7), (63, 5), (64, 6), (130, 9)].iter().enumerate() {
+            for rule in rules {
+                for topology in topologies {
+                    let mut grid = Grid::new(width, height);
+                    grid.set_rule(Rule::parse(rule).unwrap());
+                    grid.set_topology(topology).unwrap();
+                    fill_pseudo_random(&mut grid, index as u64 + 1);
+
+                    for _ in 0..4 {
+                        let expected = reference_next_generation(&grid);
+                        grid.next_generation();
+                        let actual: Vec<bool> = (0..height)
+                            .flat_map(|y| (0..width).map(move |x| (x, y)))
+                            .map(|(x, y)| grid.is_alive(x, y))
+                            .collect();
+                        assert_eq!(actual, expected, "{}x{} {} {:?}", width, height, rule, topology);
+                        assert_eq!(grid.count_alive_cells(), expected.iter().filter(|&&a| a).count());
+                    }
+                }
+            }
+        }
+
+        // 球面要求正方形网格
+        let mut grid = Grid::new(70, 70);
+        grid.set_topology(Topology::Sphere).unwrap();
+        fill_pseudo_random(&mut grid, 42);
+        for _ in 0..4 {
+            let expected = reference_next_generation(&grid);
+            grid.next_generation();
+            for (index, &alive) in expected.iter().enumerate() {
+                assert_eq!(grid.is_alive(index % 70, index / 70), alive);
+            }
+        }
+    }
+
+    #[test]
+    fn test_out_of_range_access() {
+        let mut grid = Grid::new(3, 3);
+        grid.set_cell(3, 0, CellState::Alive);
+        grid.set_cell(0, 3, CellState::Alive);
+        assert_eq!(grid.count_alive_cells(), 0);
+        assert_eq!(grid.get_cell(5, 5), &CellState::Dead);
+    }
+
+    #[test]
+    fn test_parallel_step_matches_serial() {
+        for seed in 0..40u64 {
+            let mut serial = Grid::new(150, 97);
+            serial.set_rule(Rule::parse(if seed % 2 == 0 { "B3/S23" } else { "B36/S23" }).unwrap());
+            serial.set_topology(if seed % 3 == 0 { Topology::Torus } else { Topology::Bounded }).unwrap();
+            fill_pseudo_random(&mut serial, seed);
+
+            let threads = 2 + (seed as usize % 6);
+            let mut parallel = serial.clone();
+            parallel.set_threads(threads);
+
+            for _ in 0..5 {
+                serial.next_generation();
+                parallel.next_generation();
+                assert!(serial.cells == parallel.cells, "seed {} threads {}", seed, threads);
+            }
+        }
+    }
+
+    #[test]
+    fn test_new_like_keeps_settings() {
+        let mut grid = Grid::new(10, 10);
+        grid.set_rule(Rule::parse("B36/S23").unwrap());
+        grid.set_topology(Topology::Sphere).unwrap();
+        grid.set_threads(4);
+
+        let same_shape = grid.new_like(20, 20);
+        assert_eq!(*same_shape.rule(), *grid.rule());
+        assert_eq!(same_shape.topology(), Topology::Sphere);
+        assert_eq!(same_shape.threads(), 4);
+
+        // 球面不能用于非正方形网格，回退到有界平面
+        assert_eq!(grid.new_like(20, 10).topology(), Topology::Bounded);
+        let mut wide = Grid::new(20, 10);
+        assert!(wide.set_topology(Topology::Sphere).is_err());
+        assert_eq!(wide.topology(), Topology::Bounded);
+    }
+
+    #[test]
+    fn test_revision_changes_on_edit() {
+        let mut grid = Grid::new(5, 5);
+        let initial = grid.revision();
+        assert_eq!(grid.clone().revision(), initial);
+
+        grid.set_cell(1, 1, CellState::Alive);
+        let edited = grid.revision();
+        assert_ne!(edited, initial);
+
+        // 越界写入不修改网格
+        grid.set_cell(9, 9, CellState::Alive);
+        assert_eq!(grid.revision(), edited);
+
+        grid.next_generation();
+        assert_ne!(grid.revision(), edited);
+        assert_ne!(Grid::new(5, 5).revision(), initial);
+    }
+
+    #[test]
+    fn test_engine_interface_uses_signed_coordinates() {
+        let mut grid = Grid::new(4, 4);
+        let engine: &mut dyn LifeEngine = &mut grid;
+        engine.set_cell(-1, 0, CellState::Alive);
+        engine.set_cell(1, 1, CellState::Alive);
+        assert_eq!(engine.get_cell(-1, 0), &CellState::Dead);
+        assert_eq!(engine.get_cell(1, 1), &CellState::Alive);
+        engine.advance(3);
+        assert_eq!(engine.count_alive_cells(), 0);
+    }
+
+    #[test]
+    fn test_bounding_box_and_window_iteration() {
+        let mut grid = Grid::new(70, 10);
+        assert_eq!(LifeEngine::bounding_box(&grid), None);
+
+        grid.set_cell(2, 7, CellState::Alive);
+        grid.set_cell(66, 1, CellState::Alive);
+        let bounds = LifeEngine::bounding_box(&grid).unwrap();
+        assert_eq!((bounds.min_x, bounds.min_y, bounds.max_x, bounds.max_y), (2, 1, 66, 7));
+        assert!(bounds.is_inside((0, 0), (70, 10)));
+        assert!(!bounds.is_inside((3, 0), (70, 10)));
+
+        let mut visited = Vec::new();
+        grid.for_each_alive_in((0, 0), (66, 10), &mut |x, y| visited.push((x, y)));
+        assert_eq!(visited, vec![(2, 7)]);
+    }
+
+    #[test]
+    fn test_seeded_randomize_is_reproducible() {
+        let mut first = Grid::new(40, 30);
+        let mut second = Grid::new(40, 30);
+        first.randomize_with_seed(2024, 0.4);
+        second.randomize_with_seed(2024, 0.4);
+        assert!(first.cells == second.cells);
+
+        second.randomize_with_seed(2025, 0.4);
+        assert!(first.cells != second.cells);
+
+        let alive = first.count_alive_cells() as f32 / (40.0 * 30.0);
+        assert!((0.3..0.5).contains(&alive), "density {}", alive);
+    }
+
+    #[test]
+    fn test_symmetric_soups() {
+        for symmetry in Symmetry::ALL {
+            let mut grid = Grid::new(16, 16);
+            grid.randomize_region(99, 0.5, symmetry, (0, 0, 16, 16));
+            for y in 0..16 {
+                for x in 0..16 {
+                    let alive = grid.is_alive(x, y);
+                    symmetry.for_each_image(x, y, 16, 16, |ix, iy| {
+                        assert_eq!(grid.is_alive(ix, iy), alive, "{} ({}, {})", symmetry, x, y);
+                    });
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_randomize_region_only() {
+        let mut grid = Grid::new(20, 20);
+        grid.set_cell(0, 0, CellState::Alive);
+        grid.randomize_region(5, 1.0, Symmetry::C1, (5, 5, 4, 3));
+        assert_eq!(grid.count_alive_cells(), 1 + 4 * 3);
+        assert!(grid.is_alive(0, 0));
+        assert!(grid.is_alive(8, 7) && !grid.is_alive(9, 7));
+
+        // C4 使用区域中心的正方形，超出网格的部分被裁剪
+        let mut grid = Grid::new(20, 20);
+        grid.randomize_region(5, 1.0, Symmetry::C4, (10, 0, 100, 6));
+        assert_eq!(grid.count_alive_cells(), 36);
+        assert!(grid.is_alive(12, 0) && !grid.is_alive(11, 0));
+    }
+
+    #[test]
+    fn test_diff_round_trip() {
+        let mut before = Grid::new(100, 20);
+        before.randomize_with_seed(1, 0.3);
+        let mut after = before.clone();
+        after.next_generation();
+
+        let diff = before.diff(&after).unwrap();
+        assert!(!diff.is_empty());
+        let mut replay = before.clone();
+        assert!(replay.apply_diff(&diff));
+        assert!(replay.cells == after.cells);
+        assert!(replay.apply_diff(&diff));
+        assert!(replay.cells == before.cells);
+
+        assert!(before.diff(&before.clone()).unwrap().is_empty());
+        assert!(before.diff(&Grid::new(100, 21)).is_none());
+        assert!(!Grid::new(5, 5).apply_diff(&diff));
+    }
+
+    #[test]
+    fn test_stamp_merges_and_clips() {
+        let block = Stamp::from_lines(&["**", "**"]);
+        let mut grid = Grid::new(6, 6);
+        grid.set_cell(1, 1, CellState::Alive);
+        grid.set_cell(4, 4, CellState::Alive);
+
+        grid.stamp(&block, 1, 1, StampMode::Or);
+        assert_eq!(grid.count_alive_cells(), 5);
+        grid.stamp(&block, 2, 2, StampMode::Xor);
+        assert!(!grid.is_alive(2, 2) && grid.is_alive(3, 3) && grid.is_alive(1, 1));
+        grid.stamp(&block, 1, 1, StampMode::AndNot);
+        assert_eq!(grid.count_alive_cells(), 4);
+
+        // 替换模式清除图案矩形内的死细胞位置，超出网格的部分被裁掉
+        let sparse = Stamp::from_lines(&["*..", "...", "..."]);
+        grid.stamp(&sparse, 2, 2, StampMode::Replace);
+        assert!(grid.is_alive(2, 2) && !grid.is_alive(3, 3) && !grid.is_alive(4, 4));
+        grid.stamp(&block, -1, 5, StampMode::Or);
+        assert!(grid.is_alive(0, 5) && !grid.is_alive(1, 5));
+    }
 }
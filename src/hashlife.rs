@@ -0,0 +1,770 @@
+//! HashLife 算法模块
+//! 使用哈希合并（hash-consing）的四叉树和结果缓存，支持一次推进 2^k 代，
+//! 适合周期性强、规模巨大的图案（如滑翔机枪运行十亿代）
+
+use crate::game::{BoundingBox, CellState, Grid, LifeEngine};
+use crate::rules::Rule;
+use rustc_hash::FxHashMap;
+
+/// 节点在节点表中的索引
+pub(crate) type NodeId = u32;
+
+/// 死亡叶子节点（单个细胞）
+pub(crate) const DEAD: NodeId = 0;
+/// 存活叶子节点（单个细胞）
+pub(crate) const ALIVE: NodeId = 1;
+/// 根节点的最小层级（8x8）
+const MIN_ROOT_LEVEL: u8 = 3;
+/// 每个节点占用内存的估算值：节点本身、哈希表条目和结果缓存
+const BYTES_PER_NODE: usize = 96;
+
+/// 单步推进的最大指数（每步最多推进 2^48 代）
+pub const MAX_STEP_EXPONENT: u32 = 48;
+/// 默认内存上限：256 MB
+pub const DEFAULT_MEMORY_LIMIT: usize = 256 * 1024 * 1024;
+
+/// 四叉树节点
+///
+/// 第 n 层节点表示 2^n x 2^n 的正方形区域，子节点顺序为 西北、东北、西南、东南
+#[derive(Clone, Copy, Debug)]
+struct Node {
+    children: [NodeId; 4],
+    level: u8,
+    population: u64,
+}
+
+/// HashLife 引擎
+///
+/// 宇宙是无界的：根节点以原点为中心，需要时自动向外扩展。
+/// 第 L 层的根节点覆盖坐标范围 [-2^(L-1), 2^(L-1))。
+pub struct HashLife {
+    /// 节点表，前两项固定为死亡和存活的叶子节点
+    nodes: Vec<Node>,
+    /// 哈希合并表：相同的四个子节点只对应一个节点
+    lookup: FxHashMap<[NodeId; 4], NodeId>,
+    /// 结果缓存：(节点, 推进代数的指数) -> 推进后的中心节点
+    results: FxHashMap<(NodeId, u32), NodeId>,
+    /// 各层级的空节点
+    empty_nodes: Vec<NodeId>,
+    /// 根节点
+    root: NodeId,
+    /// 演化规则
+    rule: Rule,
+    /// 已经推进的代数
+    generation: u64,
+    /// 内存上限（字节），超过后进行垃圾回收
+    memory_limit: usize,
+}
+
+impl HashLife {
+    /// 创建一个空的 HashLife 宇宙
+    pub fn new() -> Self {
+        let leaf = |population| Node {
+            children: [DEAD; 4],
+            level: 0,
+            population,
+        };
+        let mut universe = Self {
+            nodes: vec![leaf(0), leaf(1)],
+            lookup: FxHashMap::default(),
+            results: FxHashMap::default(),
+            empty_nodes: vec![DEAD],
+            root: DEAD,
+            rule: Rule::default(),
+            generation: 0,
+            memory_limit: DEFAULT_MEMORY_LIMIT,
+        };
+        universe.root = universe.empty(MIN_ROOT_LEVEL);
+        universe
+    }
+
+    /// 从网格创建宇宙，网格左上角放在 `origin` 处
+    ///
+    /// 网格使用无界宇宙无法模拟的 B0 规则时返回错误
+    pub fn from_grid(grid: &Grid, origin: (i64, i64)) -> Result<Self, String> {
+        let mut universe = Self::new();
+        universe.set_rule(*grid.rule())?;
+        universe.load_window(grid, origin);
+        Ok(universe)
+    }
+
+    /// 已经推进的代数
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// 宇宙中存活细胞的总数
+    pub fn population(&self) -> u64 {
+        self.nodes[self.root as usize].population
+    }
+
+    /// 当前节点表中的节点数量
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// 估算当前占用的内存（字节）
+    pub fn memory_usage(&self) -> usize {
+        self.nodes.len() * BYTES_PER_NODE
+    }
+
+    /// 获取内存上限（字节）
+    pub fn memory_limit(&self) -> usize {
+        self.memory_limit
+    }
+
+    /// 设置内存上限（字节），超过上限时在推进前后进行垃圾回收
+    pub fn set_memory_limit(&mut self, bytes: usize) {
+        self.memory_limit = bytes;
+    }
+
+    /// 节点的层级
+    pub(crate) fn level(&self, node: NodeId) -> u8 {
+        self.nodes[node as usize].level
+    }
+
+    /// 节点的四个子节点
+    pub(crate) fn children(&self, node: NodeId) -> [NodeId; 4] {
+        self.nodes[node as usize].children
+    }
+
+    /// 由四个子节点组成（或查找已有的）父节点
+    pub(crate) fn join(&mut self, nw: NodeId, ne: NodeId, sw: NodeId, se: NodeId) -> NodeId {
+        let children = [nw, ne, sw, se];
+        if let Some(&node) = self.lookup.get(&children) {
+            return node;
+        }
+
+        let population = children
+            .iter()
+            .map(|&child| self.nodes[child as usize].population)
+            .fold(0u64, u64::saturating_add);
+        let node = self.nodes.len() as NodeId;
+        self.nodes.push(Node {
+            children,
+            level: self.level(nw) + 1,
+            population,
+        });
+        self.lookup.insert(children, node);
+        node
+    }
+
+    /// 指定层级的空节点
+    pub(crate) fn empty(&mut self, level: u8) -> NodeId {
+        while self.empty_nodes.len() <= level as usize {
+            let below = *self.empty_nodes.last().unwrap_or(&DEAD);
+            let node = self.join(below, below, below, below);
+            self.empty_nodes.push(node);
+        }
+        self.empty_nodes[level as usize]
+    }
+
+    /// 节点中存活细胞的数量
+    pub(crate) fn node_population(&self, node: NodeId) -> u64 {
+        self.nodes[node as usize].population
+    }
+
+    /// 根节点
+    pub(crate) fn root_node(&self) -> NodeId {
+        self.root
+    }
+
+    /// 替换根节点，新根节点的中心位于原点
+    ///
+    /// 根节点至少为 `MIN_ROOT_LEVEL` 层
+    pub(crate) fn set_root_node(&mut self, node: NodeId) {
+        debug_assert!(self.level(node) >= MIN_ROOT_LEVEL);
+        self.root = node;
+    }
+
+    /// 根节点覆盖范围的一半边长
+    fn root_half_size(&self) -> i64 {
+        1i64 << (self.level(self.root) - 1)
+    }
+
+    /// 坐标是否在根节点覆盖范围内
+    fn in_root(&self, x: i64, y: i64) -> bool {
+        let half = self.root_half_size();
+        (-half..half).contains(&x) && (-half..half).contains(&y)
+    }
+
+    /// 将根节点向外扩展一层，原内容保持在中心
+    fn expand(&mut self) {
+        let level = self.level(self.root);
+        let empty = self.empty(level - 1);
+        let [nw, ne, sw, se] = self.children(self.root);
+        let nw = self.join(empty, empty, empty, nw);
+        let ne = self.join(empty, empty, ne, empty);
+        let sw = self.join(empty, sw, empty, empty);
+        let se = self.join(se, empty, empty, empty);
+        self.root = self.join(nw, ne, sw, se);
+    }
+
+    /// 判断指定位置的细胞是否存活
+    pub fn is_alive(&self, x: i64, y: i64) -> bool {
+        if !self.in_root(x, y) {
+            return false;
+        }
+        let half = self.root_half_size();
+        let (mut x, mut y) = (x + half, y + half);
+        let mut node = self.root;
+        while self.level(node) > 0 {
+            if self.nodes[node as usize].population == 0 {
+                return false;
+            }
+            let half = 1i64 << (self.level(node) - 1);
+            let index = (y >= half) as usize * 2 + (x >= half) as usize;
+            x %= half;
+            y %= half;
+            node = self.children(node)[index];
+        }
+        node == ALIVE
+    }
+
+    /// 设置指定位置细胞的状态，需要时扩展宇宙
+    pub fn set_alive(&mut self, x: i64, y: i64, alive: bool) {
+        if !alive && !self.in_root(x, y) {
+            return;
+        }
+        while !self.in_root(x, y) {
+            self.expand();
+        }
+        let half = self.root_half_size();
+        self.root = self.set_in_node(self.root, x + half, y + half, alive);
+    }
+
+    /// 在节点内部（局部坐标）设置细胞，返回新的节点
+    fn set_in_node(&mut self, node: NodeId, x: i64, y: i64, alive: bool) -> NodeId {
+        let level = self.level(node);
+        if level == 0 {
+            return if alive { ALIVE } else { DEAD };
+        }
+        let half = 1i64 << (level - 1);
+        let index = (y >= half) as usize * 2 + (x >= half) as usize;
+        let mut children = self.children(node);
+        children[index] = self.set_in_node(children[index], x % half, y % half, alive);
+        self.join(children[0], children[1], children[2], children[3])
+    }
+
+    /// 节点中心处低一层的子区域（不推进）
+    fn center(&mut self, node: NodeId) -> NodeId {
+        let [nw, ne, sw, se] = self.children(node);
+        let nw = self.children(nw)[3];
+        let ne = self.children(ne)[2];
+        let sw = self.children(sw)[1];
+        let se = self.children(se)[0];
+        self.join(nw, ne, sw, se)
+    }
+
+    /// 计算 4x4 节点中心 2x2 区域的下一代
+    fn step_base(&mut self, node: NodeId) -> NodeId {
+        let mut cells = [[false; 4]; 4];
+        for (quadrant, child) in self.children(node).into_iter().enumerate() {
+            for (index, leaf) in self.children(child).into_iter().enumerate() {
+                let x = (quadrant % 2) * 2 + index % 2;
+                let y = (quadrant / 2) * 2 + index / 2;
+                cells[y][x] = leaf == ALIVE;
+            }
+        }
+
+        let mut next = [DEAD; 4];
+        for (index, (x, y)) in [(1, 1), (2, 1), (1, 2), (2, 2)].into_iter().enumerate() {
+            let neighbors = (y - 1..=y + 1)
+                .flat_map(|ny| (x - 1..=x + 1).map(move |nx| (nx, ny)))
+                .filter(|&(nx, ny)| (nx, ny) != (x, y) && cells[ny][nx])
+                .count();
+            if self.rule.next_state(cells[y][x], neighbors) {
+                next[index] = ALIVE;
+            }
+        }
+        self.join(next[0], next[1], next[2], next[3])
+    }
+
+    /// 将第 n 层节点的中心区域推进 2^step 代，返回第 n-1 层节点
+    ///
+    /// 要求 n >= 2 且 step <= n - 2
+    fn step(&mut self, node: NodeId, step: u32) -> NodeId {
+        let level = self.level(node) as u32;
+        debug_assert!(level >= 2 && step <= level - 2);
+
+        if self.nodes[node as usize].population == 0 {
+            return self.empty(level as u8 - 1);
+        }
+        if let Some(&result) = self.results.get(&(node, step)) {
+            return result;
+        }
+
+        let result = if level == 2 {
+            self.step_base(node)
+        } else {
+            let [nw, ne, sw, se] = self.children(node);
+            let [_, nw_ne, nw_sw, nw_se] = self.children(nw);
+            let [ne_nw, _, ne_sw, ne_se] = self.children(ne);
+            let [sw_nw, sw_ne, _, sw_se] = self.children(sw);
+            let [se_nw, se_ne, se_sw, _] = self.children(se);
+
+            // 九个互相重叠的第 n-1 层子区域
+            let parts = [
+                nw,
+                self.join(nw_ne, ne_nw, nw_se, ne_sw),
+                ne,
+                self.join(nw_sw, nw_se, sw_nw, sw_ne),
+                self.join(nw_se, ne_sw, sw_ne, se_nw),
+                self.join(ne_sw, ne_se, se_nw, se_ne),
+                sw,
+                self.join(sw_ne, se_nw, sw_se, se_sw),
+                se,
+            ];
+
+            // 最大步长时分两次各推进 2^(n-3) 代；否则第一次只取中心不推进
+            let full_speed = step == level - 2;
+            let mut inner = [DEAD; 9];
+            for (slot, &part) in inner.iter_mut().zip(parts.iter()) {
+                *slot = if full_speed {
+                    self.step(part, level - 3)
+                } else {
+                    self.center(part)
+                };
+            }
+
+            let second_step = if full_speed { level - 3 } else { step };
+            let mut quadrants = [DEAD; 4];
+            for (quadrant, slot) in quadrants.iter_mut().enumerate() {
+                let (row, column) = (quadrant / 2, quadrant % 2);
+                let at = |r: usize, c: usize| inner[(row + r) * 3 + column + c];
+                let combined = self.join(at(0, 0), at(0, 1), at(1, 0), at(1, 1));
+                *slot = self.step(combined, second_step);
+            }
+            self.join(quadrants[0], quadrants[1], quadrants[2], quadrants[3])
+        };
+
+        self.results.insert((node, step), result);
+        result
+    }
+
+    /// 所有存活细胞是否都位于根节点中心四分之一（边长）区域内
+    fn is_padded(&self) -> bool {
+        let [nw, ne, sw, se] = self.children(self.root);
+        let inner = [(nw, 3), (ne, 2), (sw, 1), (se, 0)]
+            .iter()
+            .map(|&(child, corner)| {
+                let grandchild = self.children(child)[corner];
+                let great_grandchild = self.children(grandchild)[corner];
+                self.nodes[great_grandchild as usize].population
+            })
+            .fold(0u64, u64::saturating_add);
+        inner == self.population()
+    }
+
+    /// 一次推进 2^exponent 代
+    ///
+    /// 指数超过 `MAX_STEP_EXPONENT` 时按最大值处理
+    pub fn advance_pow2(&mut self, exponent: u32) {
+        let exponent = exponent.min(MAX_STEP_EXPONENT);
+        self.collect_garbage_if_needed();
+
+        // 扩展根节点，保证推进后图案仍然完整地落在结果节点中
+        while (self.level(self.root) as u32) < exponent + 3 || !self.is_padded() {
+            self.expand();
+        }
+        self.root = self.step(self.root, exponent);
+        while self.level(self.root) < MIN_ROOT_LEVEL {
+            self.expand();
+        }
+
+        self.generation = self.generation.saturating_add(1 << exponent);
+        self.collect_garbage_if_needed();
+    }
+
+    /// 超过内存上限时进行垃圾回收
+    fn collect_garbage_if_needed(&mut self) {
+        if self.memory_usage() > self.memory_limit {
+            self.collect_garbage();
+        }
+    }
+
+    /// 垃圾回收：只保留从根节点可达的节点，并清空结果缓存
+    pub fn collect_garbage(&mut self) {
+        let mut fresh = Self::new();
+        fresh.rule = self.rule;
+        fresh.memory_limit = self.memory_limit;
+        fresh.generation = self.generation;
+
+        let mut remap = FxHashMap::default();
+        fresh.root = fresh.copy_node(self, self.root, &mut remap);
+        *self = fresh;
+    }
+
+    /// 将另一个宇宙中的节点复制到当前节点表
+    fn copy_node(
+        &mut self,
+        source: &HashLife,
+        node: NodeId,
+        remap: &mut FxHashMap<NodeId, NodeId>,
+    ) -> NodeId {
+        if node == DEAD || node == ALIVE {
+            return node;
+        }
+        if let Some(&copied) = remap.get(&node) {
+            return copied;
+        }
+        let mut children = source.children(node);
+        for child in children.iter_mut() {
+            *child = self.copy_node(source, *child, remap);
+        }
+        let copied = self.join(children[0], children[1], children[2], children[3]);
+        remap.insert(node, copied);
+        copied
+    }
+
+    /// 递归遍历节点中位于矩形内的存活细胞，跳过空节点和矩形外的节点
+    fn visit_alive(
+        &self,
+        node: NodeId,
+        x: i64,
+        y: i64,
+        rect: (i64, i64, i64, i64),
+        visit: &mut dyn FnMut(i64, i64),
+    ) {
+        let (min_x, min_y, max_x, max_y) = rect;
+        let size = 1i64 << self.level(node);
+        if self.nodes[node as usize].population == 0
+            || x >= max_x
+            || y >= max_y
+            || x + size <= min_x
+            || y + size <= min_y
+        {
+            return;
+        }
+        if size == 1 {
+            visit(x, y);
+            return;
+        }
+        let half = size / 2;
+        for (index, child) in self.children(node).into_iter().enumerate() {
+            let child_x = x + (index % 2) as i64 * half;
+            let child_y = y + (index / 2) as i64 * half;
+            self.visit_alive(child, child_x, child_y, rect, visit);
+        }
+    }
+
+    /// 非空节点内存活细胞在某一轴上的最小（或最大）坐标，相对于节点左上角
+    ///
+    /// 相同的子树只计算一次，因此即使有大量重复结构也很快
+    fn extreme(
+        &self,
+        node: NodeId,
+        axis: usize,
+        maximum: bool,
+        memo: &mut FxHashMap<NodeId, i64>,
+    ) -> i64 {
+        let level = self.level(node);
+        if level == 0 {
+            return 0;
+        }
+        if let Some(&offset) = memo.get(&node) {
+            return offset;
+        }
+
+        // 子节点按该轴上的位置分为低半部分和高半部分，优先查找靠近目标方向的一半
+        let half = 1i64 << (level - 1);
+        let (low, high) = if axis == 0 { ([0, 2], [1, 3]) } else { ([0, 1], [2, 3]) };
+        let groups = if maximum { [(high, half), (low, 0)] } else { [(low, 0), (high, half)] };
+
+        let children = self.children(node);
+        let mut result = None;
+        for (group, base) in groups {
+            for index in group {
+                let child = children[index];
+                if self.nodes[child as usize].population == 0 {
+                    continue;
+                }
+                let offset = base + self.extreme(child, axis, maximum, memo);
+                result = Some(match result {
+                    Some(current) if maximum => offset.max(current),
+                    Some(current) => offset.min(current),
+                    None => offset,
+                });
+            }
+            if result.is_some() {
+                break;
+            }
+        }
+
+        let offset = result.unwrap_or(0);
+        memo.insert(node, offset);
+        offset
+    }
+}
+
+impl Default for HashLife {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LifeEngine for HashLife {
+    fn rule(&self) -> &Rule {
+        &self.rule
+    }
+
+    /// 切换规则会使结果缓存失效；无界宇宙无法表示 B0 规则，这类规则返回错误
+    fn set_rule(&mut self, rule: Rule) -> Result<(), String> {
+        rule.check_unbounded()?;
+        if rule != self.rule {
+            self.rule = rule;
+            self.results.clear();
+        }
+        Ok(())
+    }
+
+    fn get_cell(&self, x: i64, y: i64) -> &CellState {
+        if self.is_alive(x, y) {
+            &CellState::Alive
+        } else {
+            &CellState::Dead
+        }
+    }
+
+    fn set_cell(&mut self, x: i64, y: i64, state: CellState) {
+        self.set_alive(x, y, state == CellState::Alive);
+    }
+
+    fn clear(&mut self) {
+        self.root = self.empty(MIN_ROOT_LEVEL);
+        self.generation = 0;
+    }
+
+    fn next_generation(&mut self) {
+        self.advance_pow2(0);
+    }
+
+    /// 按二进制分解推进任意代数
+    ///
+    /// 高于 2^`MAX_STEP_EXPONENT` 的部分重复推进 2^`MAX_STEP_EXPONENT` 代，不会少推进
+    fn advance(&mut self, generations: u64) {
+        for bit in 0..MAX_STEP_EXPONENT {
+            if generations >> bit & 1 == 1 {
+                self.advance_pow2(bit);
+            }
+        }
+        for _ in 0..generations >> MAX_STEP_EXPONENT {
+            self.advance_pow2(MAX_STEP_EXPONENT);
+        }
+    }
+
+    fn count_alive_cells(&self) -> usize {
+        self.population() as usize
+    }
+
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        if self.population() == 0 {
+            return None;
+        }
+        let half = self.root_half_size();
+        let edge = |axis, maximum| {
+            -half + self.extreme(self.root, axis, maximum, &mut FxHashMap::default())
+        };
+        Some(BoundingBox {
+            min_x: edge(0, false),
+            min_y: edge(1, false),
+            max_x: edge(0, true),
+            max_y: edge(1, true),
+        })
+    }
+
+    fn for_each_alive_in(
+        &self,
+        (min_x, min_y): (i64, i64),
+        (max_x, max_y): (i64, i64),
+        visit: &mut dyn FnMut(i64, i64),
+    ) {
+        let half = self.root_half_size();
+        let rect = (min_x, min_y, max_x, max_y);
+        self.visit_alive(self.root, -half, -half, rect, visit);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::{guns, spaceships};
+
+    /// 把预设图案放入宇宙，左上角位于 (x, y)
+    fn place(universe: &mut HashLife, data: &[&str], x: i64, y: i64) {
+        for (dy, line) in data.iter().enumerate() {
+            for (dx, ch) in line.chars().enumerate() {
+                if ch == 'O' {
+                    universe.set_alive(x + dx as i64, y + dy as i64, true);
+                }
+            }
+        }
+    }
+
+    /// 收集宇宙中的所有存活细胞（排序后便于比较）
+    fn alive_cells(universe: &HashLife) -> Vec<(i64, i64)> {
+        let mut cells = Vec::new();
+        let bound = 1i64 << 40;
+        universe.for_each_alive_in((-bound, -bound), (bound, bound), &mut |x, y| {
+            cells.push((x, y))
+        });
+        cells.sort();
+        cells
+    }
+
+    #[test]
+    fn test_set_and_get_cells() {
+        let mut universe = HashLife::new();
+        universe.set_alive(0, 0, true);
+        universe.set_alive(-5, 3, true);
+        universe.set_alive(1000, -2000, true);
+
+        assert!(universe.is_alive(0, 0));
+        assert!(universe.is_alive(-5, 3));
+        assert!(universe.is_alive(1000, -2000));
+        assert!(!universe.is_alive(1, 0));
+        assert_eq!(universe.population(), 3);
+
+        universe.set_alive(-5, 3, false);
+        assert!(!universe.is_alive(-5, 3));
+        assert_eq!(universe.count_alive_cells(), 2);
+    }
+
+    #[test]
+    fn test_matches_grid_on_random_soup() {
+        // 网格足够大，保证演化期间不会碰到边界
+        let mut grid = Grid::new(160, 160);
+        let mut state = 7u64;
+        for y in 70..90 {
+            for x in 70..90 {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                if state >> 63 == 1 {
+                    grid.set_cell(x, y, CellState::Alive);
+                }
+            }
+        }
+
+        let mut universe = HashLife::from_grid(&grid, (-80, -80)).unwrap();
+        for _ in 0..40 {
+            grid.next_generation();
+        }
+        universe.advance(40);
+
+        let mut expected = Grid::new(160, 160);
+        universe.render_window(&mut expected, (-80, -80));
+        assert_eq!(universe.generation(), 40);
+        assert_eq!(universe.count_alive_cells(), grid.count_alive_cells());
+        for y in 0..160 {
+            for x in 0..160 {
+                assert_eq!(expected.is_alive(x, y), grid.is_alive(x, y), "({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_power_of_two_jumps_are_consistent() {
+        let mut single = HashLife::new();
+        place(&mut single, guns::GOSPER_GLIDER_GUN.data, -18, -4);
+        let mut jumped = HashLife::new();
+        place(&mut jumped, guns::GOSPER_GLIDER_GUN.data, -18, -4);
+
+        for _ in 0..300 {
+            single.next_generation();
+        }
+        jumped.advance_pow2(8);
+        jumped.advance(44);
+
+        assert_eq!(single.generation(), jumped.generation());
+        assert_eq!(alive_cells(&single), alive_cells(&jumped));
+    }
+
+    #[test]
+    fn test_glider_travels_far() {
+        let mut universe = HashLife::new();
+        place(&mut universe, spaceships::GLIDER.data, 0, 0);
+        let start = alive_cells(&universe);
+
+        // 滑翔机每4代沿对角线移动一格
+        universe.advance_pow2(20);
+        let shift = 1i64 << 18;
+        let moved: Vec<(i64, i64)> = start.iter().map(|&(x, y)| (x + shift, y + shift)).collect();
+        assert_eq!(alive_cells(&universe), moved);
+    }
+
+    #[test]
+    fn test_advance_beyond_max_step() {
+        let mut universe = HashLife::new();
+        place(&mut universe, spaceships::GLIDER.data, 0, 0);
+        let start = alive_cells(&universe);
+
+        // 超过 2^MAX_STEP_EXPONENT 的代数不会被截断
+        let generations = (3u64 << MAX_STEP_EXPONENT) + 4;
+        universe.advance(generations);
+        assert_eq!(universe.generation(), generations);
+        let shift = (generations / 4) as i64;
+        assert_eq!(universe.population(), 5);
+        assert!(start.iter().all(|&(x, y)| universe.is_alive(x + shift, y + shift)));
+    }
+
+    #[test]
+    fn test_gosper_gun_billion_generations() {
+        let mut universe = HashLife::new();
+        place(&mut universe, guns::GOSPER_GLIDER_GUN.data, 0, 0);
+
+        universe.advance_pow2(30);
+        assert_eq!(universe.generation(), 1 << 30);
+        // 每30代产生一个滑翔机（5个细胞），枪本身约有36到50个细胞
+        let gliders = (1u64 << 30) / 30;
+        let population = universe.population();
+        assert!(
+            (gliders * 5 + 30..=gliders * 5 + 60).contains(&population),
+            "population: {}",
+            population
+        );
+    }
+
+    #[test]
+    fn test_garbage_collection_preserves_pattern() {
+        let mut universe = HashLife::new();
+        place(&mut universe, guns::GOSPER_GLIDER_GUN.data, 0, 0);
+        universe.set_memory_limit(0);
+
+        let mut reference = HashLife::new();
+        place(&mut reference, guns::GOSPER_GLIDER_GUN.data, 0, 0);
+
+        for _ in 0..5 {
+            universe.advance_pow2(6);
+            reference.advance_pow2(6);
+        }
+        assert!(universe.node_count() < reference.node_count());
+        assert_eq!(alive_cells(&universe), alive_cells(&reference));
+    }
+
+    #[test]
+    fn test_custom_rule() {
+        // HighLife 中的复制子，与网格演化结果比较
+        let rule = Rule::parse("B36/S23").unwrap();
+        let mut grid = Grid::new(64, 64);
+        grid.set_rule(rule);
+        grid.load_pattern(&[" OOO", "O  O", "O  O", "OOO "], 30, 30);
+
+        let mut universe = HashLife::from_grid(&grid, (0, 0)).unwrap();
+        assert_eq!(*universe.rule(), rule);
+        for _ in 0..12 {
+            grid.next_generation();
+            universe.next_generation();
+        }
+        let mut rendered = Grid::new(64, 64);
+        universe.render_window(&mut rendered, (0, 0));
+        assert_eq!(rendered.count_alive_cells(), grid.count_alive_cells());
+        for y in 0..64 {
+            for x in 0..64 {
+                assert_eq!(rendered.is_alive(x, y), grid.is_alive(x, y));
+            }
+        }
+
+        // B0 规则无法在无界宇宙中模拟
+        grid.set_rule(Rule::parse("B03/S23").unwrap());
+        assert!(HashLife::from_grid(&grid, (0, 0)).is_err());
+    }
+}
@@ -0,0 +1,342 @@
+//! 撤销/重做历史模块
+//! 记录网格编辑和演化步骤，同尺寸的修改保存为异或差异，其他修改保存为完整快照，
+//! 总占用超过内存预算时丢弃最早的记录
+
+use crate::game::{Grid, GridDiff};
+use std::collections::VecDeque;
+
+/// 默认的历史内存预算：64 MB
+pub const DEFAULT_HISTORY_BUDGET: usize = 64 * 1024 * 1024;
+
+/// 一次操作对网格的修改
+enum Change {
+    /// 同尺寸网格之间的细胞差异
+    Diff(GridDiff),
+    /// 尺寸、规则或拓扑发生变化时保存前后两个完整网格
+    Replace { before: Box<Grid>, after: Box<Grid> },
+}
+
+impl Change {
+    /// 估算占用的内存（字节）
+    fn memory_usage(&self) -> usize {
+        match self {
+            Change::Diff(diff) => diff.memory_usage(),
+            Change::Replace { before, after } => before.memory_usage() + after.memory_usage(),
+        }
+    }
+}
+
+/// 历史中的一条记录
+struct Entry {
+    /// 操作名称，如 "Draw"、"Step"
+    label: &'static str,
+    /// 网格的修改
+    change: Change,
+    /// 操作前后的代数
+    generations: (u64, u64),
+    /// 操作前后窗口左上角在无界宇宙中的位置
+    origins: ((i64, i64), (i64, i64)),
+}
+
+/// 撤销/重做历史
+pub struct History {
+    /// 可撤销的记录，最新的在末尾
+    undo_stack: VecDeque<Entry>,
+    /// 可重做的记录，最近撤销的在末尾
+    redo_stack: Vec<Entry>,
+    /// 进行中的操作：(名称, 操作前的网格, 操作前的代数, 操作前的窗口位置)
+    pending: Option<(&'static str, Grid, u64, (i64, i64))>,
+    /// `begin` 的嵌套层数，只有最外层的操作会被记录
+    depth: usize,
+    /// 内存预算（字节）
+    budget: usize,
+    /// 所有记录当前占用的内存（字节）
+    used: usize,
+}
+
+impl History {
+    /// 创建指定内存预算的空历史
+    pub fn new(budget: usize) -> Self {
+        Self {
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            pending: None,
+            depth: 0,
+            budget,
+            used: 0,
+        }
+    }
+
+    /// 开始一个操作，记录操作前的网格、代数和窗口左上角在无界宇宙中的位置
+    ///
+    /// 操作可以嵌套（例如拖动绘制期间推进了一步），嵌套的操作合并到最外层的记录中
+    pub fn begin(
+        &mut self,
+        label: &'static str,
+        grid: &Grid,
+        generation: u64,
+        origin: (i64, i64),
+    ) {
+        if self.depth == 0 {
+            self.pending = Some((label, grid.clone(), generation, origin));
+        }
+        self.depth += 1;
+    }
+
+    /// 结束当前操作；最外层操作结束时，如果网格、代数或窗口位置有变化则生成一条记录
+    pub fn commit(&mut self, grid: &Grid, generation: u64, origin: (i64, i64)) {
+        if self.depth == 0 {
+            return;
+        }
+        self.depth -= 1;
+        if self.depth > 0 {
+            return;
+        }
+        let (label, before, before_generation, before_origin) = match self.pending.take() {
+            Some(pending) => pending,
+            None => return,
+        };
+
+        let unmoved = before_generation == generation && before_origin == origin;
+        let change = match before.diff(grid) {
+            Some(diff) if diff.is_empty() && unmoved => return,
+            Some(diff) => Change::Diff(diff),
+            None => Change::Replace {
+                before: Box::new(before),
+                after: Box::new(grid.clone()),
+            },
+        };
+        self.push(Entry {
+            label,
+            change,
+            generations: (before_generation, generation),
+            origins: (before_origin, origin),
+        });
+    }
+
+    /// 是否有进行中的操作
+    pub fn is_recording(&self) -> bool {
+        self.depth > 0
+    }
+
+    /// 添加新记录：清空重做栈，并在超出预算时丢弃最早的记录（至少保留最新一条）
+    fn push(&mut self, entry: Entry) {
+        for dropped in self.redo_stack.drain(..) {
+            self.used -= dropped.change.memory_usage();
+        }
+        self.used += entry.change.memory_usage();
+        self.undo_stack.push_back(entry);
+
+        while self.used > self.budget && self.undo_stack.len() > 1 {
+            if let Some(dropped) = self.undo_stack.pop_front() {
+                self.used -= dropped.change.memory_usage();
+            }
+        }
+    }
+
+    /// 撤销最近一次操作
+    ///
+    /// # 返回值
+    /// 返回 (操作名称, 恢复后的代数, 恢复后的窗口位置)；没有可撤销的操作时返回 `None`
+    pub fn undo(&mut self, grid: &mut Grid) -> Option<(&'static str, u64, (i64, i64))> {
+        let entry = self.undo_stack.pop_back()?;
+        match &entry.change {
+            Change::Diff(diff) => {
+                grid.apply_diff(diff);
+            }
+            Change::Replace { before, .. } => *grid = (**before).clone(),
+        }
+        let result = (entry.label, entry.generations.0, entry.origins.0);
+        self.redo_stack.push(entry);
+        Some(result)
+    }
+
+    /// 重做最近一次撤销的操作
+    ///
+    /// # 返回值
+    /// 返回 (操作名称, 恢复后的代数, 恢复后的窗口位置)；没有可重做的操作时返回 `None`
+    pub fn redo(&mut self, grid: &mut Grid) -> Option<(&'static str, u64, (i64, i64))> {
+        let entry = self.redo_stack.pop()?;
+        match &entry.change {
+            Change::Diff(diff) => {
+                grid.apply_diff(diff);
+            }
+            Change::Replace { after, .. } => *grid = (**after).clone(),
+        }
+        let result = (entry.label, entry.generations.1, entry.origins.1);
+        self.undo_stack.push_back(entry);
+        Some(result)
+    }
+
+    /// 下一次撤销的操作名称
+    pub fn undo_label(&self) -> Option<&'static str> {
+        self.undo_stack.back().map(|entry| entry.label)
+    }
+
+    /// 下一次重做的操作名称
+    pub fn redo_label(&self) -> Option<&'static str> {
+        self.redo_stack.last().map(|entry| entry.label)
+    }
+
+    /// 可撤销的记录数量
+    pub fn undo_count(&self) -> usize {
+        self.undo_stack.len()
+    }
+
+    /// 所有记录占用的内存（字节）
+    pub fn memory_usage(&self) -> usize {
+        self.used
+    }
+
+    /// 获取内存预算（字节）
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    /// 设置内存预算（字节），超出时立即丢弃最早的记录
+    pub fn set_budget(&mut self, budget: usize) {
+        self.budget = budget;
+        while self.used > self.budget && self.undo_stack.len() > 1 {
+            if let Some(dropped) = self.undo_stack.pop_front() {
+                self.used -= dropped.change.memory_usage();
+            }
+        }
+    }
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_BUDGET)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::CellState;
+
+    #[test]
+    fn test_undo_redo_edit() {
+        let mut history = History::default();
+        let mut grid = Grid::new(10, 10);
+
+        history.begin("Draw", &grid, 0, (0, 0));
+        grid.set_cell(1, 1, CellState::Alive);
+        grid.set_cell(2, 1, CellState::Alive);
+        history.commit(&grid, 0, (0, 0));
+        assert_eq!(history.undo_label(), Some("Draw"));
+
+        assert_eq!(history.undo(&mut grid), Some(("Draw", 0, (0, 0))));
+        assert_eq!(grid.count_alive_cells(), 0);
+        assert_eq!(history.redo_label(), Some("Draw"));
+
+        assert_eq!(history.redo(&mut grid), Some(("Draw", 0, (0, 0))));
+        assert!(grid.is_alive(1, 1) && grid.is_alive(2, 1));
+        assert_eq!(history.redo(&mut grid), None);
+    }
+
+    #[test]
+    fn test_steps_restore_generation() {
+        let mut history = History::default();
+        let mut grid = Grid::new(10, 10);
+        grid.load_pattern(&["OOO"], 3, 4);
+
+        for generation in 0..3 {
+            history.begin("Step", &grid, generation, (0, 0));
+            grid.next_generation();
+            history.commit(&grid, generation + 1, (0, 0));
+        }
+        assert_eq!(history.undo_count(), 3);
+
+        assert_eq!(history.undo(&mut grid), Some(("Step", 2, (0, 0))));
+        assert_eq!(history.undo(&mut grid), Some(("Step", 1, (0, 0))));
+        // 闪烁器在第1代为竖直方向
+        assert!(grid.is_alive(4, 3) && grid.is_alive(4, 5));
+    }
+
+    #[test]
+    fn test_nested_operations_form_one_entry() {
+        let mut history = History::default();
+        let mut grid = Grid::new(10, 10);
+
+        history.begin("Draw", &grid, 0, (0, 0));
+        grid.set_cell(0, 0, CellState::Alive);
+        history.begin("Step", &grid, 0, (0, 0));
+        grid.set_cell(5, 5, CellState::Alive);
+        history.commit(&grid, 1, (0, 0));
+        assert!(history.is_recording());
+        history.commit(&grid, 1, (0, 0));
+
+        assert_eq!(history.undo_count(), 1);
+        assert_eq!(history.undo(&mut grid), Some(("Draw", 0, (0, 0))));
+        assert_eq!(grid.count_alive_cells(), 0);
+    }
+
+    #[test]
+    fn test_no_op_is_not_recorded() {
+        let mut history = History::default();
+        let grid = Grid::new(10, 10);
+        history.begin("Clear", &grid, 0, (0, 0));
+        history.commit(&grid, 0, (0, 0));
+        assert_eq!(history.undo_count(), 0);
+
+        // 没有对应 begin 的 commit 被忽略
+        history.commit(&grid, 0, (0, 0));
+        assert_eq!(history.undo_count(), 0);
+    }
+
+    #[test]
+    fn test_resize_uses_snapshot() {
+        let mut history = History::default();
+        let mut grid = Grid::new(10, 10);
+        grid.set_cell(3, 3, CellState::Alive);
+
+        history.begin("Load pattern", &grid, 7, (0, 0));
+        grid = Grid::new(30, 20);
+        history.commit(&grid, 0, (0, 0));
+
+        assert_eq!(history.undo(&mut grid), Some(("Load pattern", 7, (0, 0))));
+        assert_eq!((grid.width(), grid.height()), (10, 10));
+        assert!(grid.is_alive(3, 3));
+        assert_eq!(history.redo(&mut grid), Some(("Load pattern", 0, (0, 0))));
+        assert_eq!((grid.width(), grid.height()), (30, 20));
+    }
+
+    #[test]
+    fn test_new_edit_clears_redo() {
+        let mut history = History::default();
+        let mut grid = Grid::new(10, 10);
+        for x in 0..2 {
+            history.begin("Draw", &grid, 0, (0, 0));
+            grid.set_cell(x, 0, CellState::Alive);
+            history.commit(&grid, 0, (0, 0));
+        }
+        history.undo(&mut grid);
+        history.begin("Draw", &grid, 0, (0, 0));
+        grid.set_cell(9, 9, CellState::Alive);
+        history.commit(&grid, 0, (0, 0));
+
+        assert_eq!(history.redo_label(), None);
+        assert_eq!(history.undo_count(), 2);
+    }
+
+    #[test]
+    fn test_budget_drops_oldest_entries() {
+        let mut grid = Grid::new(64, 64);
+        let mut history = History::new(0);
+        for generation in 0..5 {
+            history.begin("Randomize", &grid, generation, (0, 0));
+            grid.randomize_with_seed(generation, 0.5);
+            history.commit(&grid, generation, (0, 0));
+        }
+        // 预算为0时只保留最新的一条记录
+        assert_eq!(history.undo_count(), 1);
+
+        history.set_budget(DEFAULT_HISTORY_BUDGET);
+        history.begin("Randomize", &grid, 5, (0, 0));
+        grid.randomize_with_seed(5, 0.5);
+        history.commit(&grid, 5, (0, 0));
+        assert_eq!(history.undo_count(), 2);
+        assert!(history.memory_usage() > 0);
+    }
+}
@@ -0,0 +1,46 @@
+//! 生命游戏引擎库
+//! 提供网格和演化引擎（位压缩网格、HashLife、稀疏瓦片）、规则与边界拓扑、RLE 和宏细胞等图案格式的读写、
+//! 预设图案，以及人口统计、周期检测、对象普查等分析工具。
+//! 这些模块不依赖 egui；图形界面在 `gui` 特性下由 `game_of_life` 可执行文件提供
+
+pub mod breakpoints;
+pub mod census;
+pub mod cli;
+pub mod game;
+pub mod hashlife;
+pub mod history;
+pub mod macrocell;
+pub mod patterns;
+pub mod period;
+pub mod random;
+pub mod rules;
+pub mod search;
+pub mod save_load;
+pub mod selection;
+pub mod shapes;
+pub mod stamp;
+// 文件开头保留原有的 `///` 模块说明，其后的空行不视为问题
+#[allow(clippy::empty_line_after_doc_comments)]
+pub mod statistics;
+pub mod timeline;
+pub mod topology;
+pub mod universe;
+
+pub use census::{take_census, Census, CensusColumn, CensusEntry};
+pub use game::{Algorithm, BoundingBox, CellState, Grid, LifeEngine};
+pub use hashlife::HashLife;
+pub use macrocell::{MacrocellInfo, MacrocellPattern};
+pub use patterns::{get_all_patterns, Pattern};
+pub use period::{PeriodDetector, Periodicity};
+pub use random::{SoupSettings, Symmetry};
+pub use rules::Rule;
+pub use search::{SearchDatabase, SearchSettings, SoupRecord};
+pub use save_load::{
+    export_to_rle_string, import_from_rle_string, ParseMode, RleError, RlePattern, RleWarning,
+};
+pub use selection::Selection;
+pub use shapes::Shape;
+pub use stamp::{Rotation, Stamp, StampMode, Transform};
+pub use statistics::PopulationStatistics;
+pub use topology::Topology;
+pub use universe::SparseUniverse;
@@ -0,0 +1,498 @@
+//! 宏细胞（Macrocell，`.mc`）格式模块
+//! Golly 的四叉树格式：相同的子树只写一次，巨大或大部分为空的图案文件也很小。
+//! 读取时直接构造 HashLife 节点，不会分配与图案尺寸相当的稠密数组
+
+use crate::game::LifeEngine;
+use crate::hashlife::{HashLife, NodeId, ALIVE, DEAD};
+use crate::save_load::{RleError, RlePattern};
+use rustc_hash::FxHashMap;
+use std::fs;
+use std::path::Path;
+
+/// 宏细胞文件的第一行
+pub const MACROCELL_HEADER: &str = "[M2]";
+
+/// 叶子节点的层级（8x8）
+const LEAF_LEVEL: u8 = 3;
+/// 叶子节点的边长
+const LEAF_SIZE: usize = 1 << LEAF_LEVEL;
+/// 节点的最大层级，保证坐标不会溢出 i64
+const MAX_LEVEL: u8 = 62;
+/// 转换为稠密图案时允许的最大细胞数（包围盒面积）
+pub const MAX_DENSE_CELLS: u64 = 1 << 26;
+
+/// 宏细胞文件中的描述信息
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MacrocellInfo {
+    pub name: String,
+    pub author: String,
+    pub comment: String,
+    /// 图案所处的代数（`#G`）
+    pub generation: u64,
+}
+
+/// 宏细胞图案：描述信息和保存细胞的 HashLife 宇宙
+///
+/// 根节点的中心位于宇宙原点，与 Golly 相同
+pub struct MacrocellPattern {
+    pub info: MacrocellInfo,
+    pub universe: HashLife,
+}
+
+impl MacrocellPattern {
+    /// 由稠密图案创建，图案中心放在原点
+    ///
+    /// 无界宇宙没有边界拓扑，规则字段中的拓扑后缀被忽略；规则无效或包含 B0 时使用标准规则
+    pub fn from_rle_pattern(pattern: &RlePattern) -> Self {
+        let mut universe = HashLife::new();
+        let _ = universe.set_rule(pattern.parse_rule().unwrap_or_default());
+        let (left, top) = (-(pattern.width as i64) / 2, -(pattern.height as i64) / 2);
+        for (y, row) in pattern.data.iter().enumerate() {
+            for (x, &alive) in row.iter().enumerate() {
+                if alive {
+                    universe.set_alive(left + x as i64, top + y as i64, true);
+                }
+            }
+        }
+        Self {
+            info: MacrocellInfo {
+                name: pattern.name.clone(),
+                author: pattern.author.clone(),
+                comment: pattern.comment.clone(),
+                generation: pattern.generation,
+            },
+            universe,
+        }
+    }
+
+    /// 按活细胞的包围盒转换为稠密图案
+    ///
+    /// 包围盒面积超过 `MAX_DENSE_CELLS` 时返回错误，这样的图案应直接使用 HashLife 宇宙
+    pub fn to_rle_pattern(&self) -> Result<RlePattern, RleError> {
+        let bounds = self
+            .universe
+            .bounding_box()
+            .ok_or_else(|| RleError::InvalidFormat("Pattern has no live cells".to_string()))?;
+        let (width, height) = (bounds.width(), bounds.height());
+        if width.saturating_mul(height) > MAX_DENSE_CELLS {
+            return Err(RleError::InvalidFormat(format!(
+                "Pattern is {} x {} cells, too large to load into a grid (use the HashLife algorithm)",
+                width, height
+            )));
+        }
+
+        let mut pattern = RlePattern::new(self.info.name.clone(), width as usize, height as usize);
+        pattern.author = self.info.author.clone();
+        pattern.comment = self.info.comment.clone();
+        pattern.rule = self.universe.rule().to_string();
+        pattern.position = Some((bounds.min_x, bounds.min_y));
+        pattern.generation = self.info.generation;
+        self.universe.for_each_alive_in(
+            (bounds.min_x, bounds.min_y),
+            (bounds.max_x + 1, bounds.max_y + 1),
+            &mut |x, y| {
+                pattern.data[(y - bounds.min_y) as usize][(x - bounds.min_x) as usize] = true;
+            },
+        );
+        Ok(pattern)
+    }
+}
+
+/// 从宏细胞格式字符串导入图案
+///
+/// 第一行为 `[M2]`；`#R` 指定规则，`#G` 为代数，`#N`、`#O`、`#C` 与 RLE 相同，其余 `#` 行被忽略。
+/// 叶子行用 `.`、`*` 和 `$` 描述 8x8 的区域；其余节点行为 `层级 西北 东北 西南 东南`，
+/// 子节点是之前节点行的序号（从 1 开始），0 表示空节点。最后一个节点是根节点
+pub fn import_from_macrocell_string(data: &str) -> Result<MacrocellPattern, RleError> {
+    let mut lines = data.lines().enumerate();
+    if !lines
+        .next()
+        .is_some_and(|(_, line)| line.trim_start().starts_with(MACROCELL_HEADER))
+    {
+        return Err(RleError::InvalidFormat(format!(
+            "Macrocell files must start with {}",
+            MACROCELL_HEADER
+        )));
+    }
+
+    let mut info = MacrocellInfo::default();
+    let mut comments = Vec::new();
+    let mut universe = HashLife::new();
+    // 按出现顺序排列的节点，文件中的序号 n 对应 nodes[n - 1]
+    let mut nodes: Vec<NodeId> = Vec::new();
+
+    for (index, line) in lines {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix('#') {
+            let (tag, text) = rest.split_at(rest.chars().next().map_or(0, char::len_utf8));
+            let text = text.trim();
+            match tag {
+                "R" => {
+                    let rule = text.parse().map_err(|e| {
+                        RleError::ParseError(format!("Invalid rule at line {}: {}", line_number, e))
+                    })?;
+                    universe.set_rule(rule).map_err(|e| {
+                        RleError::ParseError(format!("Invalid rule at line {}: {}", line_number, e))
+                    })?;
+                }
+                "G" => {
+                    info.generation = text.parse().map_err(|_| {
+                        RleError::ParseError(format!(
+                            "Invalid generation '{}' at line {}",
+                            text, line_number
+                        ))
+                    })?;
+                }
+                "N" => info.name = text.to_string(),
+                "O" => info.author = text.to_string(),
+                "C" | "D" => comments.push(text.to_string()),
+                _ => {}
+            }
+            continue;
+        }
+
+        let node = if line.starts_with(|ch: char| ch.is_ascii_digit()) {
+            parse_node_line(line, line_number, &nodes, &mut universe)?
+        } else {
+            parse_leaf_line(line, line_number, &mut universe)?
+        };
+        nodes.push(node);
+    }
+
+    info.comment = comments.join("\n");
+    if let Some(&root) = nodes.last() {
+        universe.set_root_node(root);
+    }
+    Ok(MacrocellPattern { info, universe })
+}
+
+/// 解析叶子行，返回 8x8 的节点
+fn parse_leaf_line(
+    line: &str,
+    line_number: usize,
+    universe: &mut HashLife,
+) -> Result<NodeId, RleError> {
+    let mut cells = [[false; LEAF_SIZE]; LEAF_SIZE];
+    let (mut x, mut y) = (0, 0);
+    for (column, ch) in line.chars().enumerate() {
+        match ch {
+            '.' | '*' => {
+                if x >= LEAF_SIZE || y >= LEAF_SIZE {
+                    return Err(RleError::ParseError(format!(
+                        "Leaf at line {} is larger than 8 x 8",
+                        line_number
+                    )));
+                }
+                cells[y][x] = ch == '*';
+                x += 1;
+            }
+            '$' => {
+                x = 0;
+                y += 1;
+            }
+            _ => {
+                return Err(RleError::ParseError(format!(
+                    "Invalid character '{}' at line {}, column {}",
+                    ch,
+                    line_number,
+                    column + 1
+                )))
+            }
+        }
+    }
+    Ok(build_leaf(universe, &cells, 0, 0, LEAF_LEVEL))
+}
+
+/// 由细胞数组中左上角为 (x, y) 的区域构造节点
+fn build_leaf(
+    universe: &mut HashLife,
+    cells: &[[bool; LEAF_SIZE]; LEAF_SIZE],
+    x: usize,
+    y: usize,
+    level: u8,
+) -> NodeId {
+    if level == 0 {
+        return if cells[y][x] { ALIVE } else { DEAD };
+    }
+    let half = 1 << (level - 1);
+    let nw = build_leaf(universe, cells, x, y, level - 1);
+    let ne = build_leaf(universe, cells, x + half, y, level - 1);
+    let sw = build_leaf(universe, cells, x, y + half, level - 1);
+    let se = build_leaf(universe, cells, x + half, y + half, level - 1);
+    universe.join(nw, ne, sw, se)
+}
+
+/// 解析非叶子节点行
+fn parse_node_line(
+    line: &str,
+    line_number: usize,
+    nodes: &[NodeId],
+    universe: &mut HashLife,
+) -> Result<NodeId, RleError> {
+    let invalid =
+        || RleError::ParseError(format!("Invalid node '{}' at line {}", line, line_number));
+    let fields = line
+        .split_whitespace()
+        .map(str::parse::<usize>)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| invalid())?;
+    let (level, indices) = match fields.split_first() {
+        Some((&level, indices)) if indices.len() == 4 => (level, indices),
+        _ => return Err(invalid()),
+    };
+    if !(LEAF_LEVEL as usize + 1..=MAX_LEVEL as usize).contains(&level) {
+        return Err(RleError::ParseError(format!(
+            "Invalid node level {} at line {}",
+            level, line_number
+        )));
+    }
+
+    let child_level = level as u8 - 1;
+    let mut children = [DEAD; 4];
+    for (slot, &index) in children.iter_mut().zip(indices) {
+        *slot = match index {
+            0 => universe.empty(child_level),
+            _ => match nodes.get(index - 1) {
+                Some(&child) if universe.level(child) == child_level => child,
+                Some(_) => {
+                    return Err(RleError::ParseError(format!(
+                        "Node {} has the wrong size for a child of level {} at line {}",
+                        index, level, line_number
+                    )))
+                }
+                None => {
+                    return Err(RleError::ParseError(format!(
+                        "Node {} is not defined before line {}",
+                        index, line_number
+                    )))
+                }
+            },
+        };
+    }
+    Ok(universe.join(children[0], children[1], children[2], children[3]))
+}
+
+/// 将宇宙导出为宏细胞格式字符串
+///
+/// 相同的子树只输出一次，空子树用 0 表示，因此输出大小与图案的结构而非尺寸有关
+pub fn export_to_macrocell_string(universe: &HashLife, info: &MacrocellInfo) -> String {
+    let mut result = format!("{}\n#R {}\n", MACROCELL_HEADER, universe.rule());
+    if info.generation > 0 {
+        result.push_str(&format!("#G {}\n", info.generation));
+    }
+    if !info.name.is_empty() {
+        result.push_str(&format!("#N {}\n", info.name));
+    }
+    if !info.author.is_empty() {
+        result.push_str(&format!("#O {}\n", info.author));
+    }
+    for line in info.comment.lines() {
+        result.push_str(&format!("#C {}\n", line));
+    }
+
+    let mut indices = FxHashMap::default();
+    write_node(universe, universe.root_node(), &mut indices, &mut result);
+    result
+}
+
+/// 先输出子节点再输出节点本身，返回节点的序号（空节点为 0）
+fn write_node(
+    universe: &HashLife,
+    node: NodeId,
+    indices: &mut FxHashMap<NodeId, usize>,
+    output: &mut String,
+) -> usize {
+    if universe.node_population(node) == 0 {
+        return 0;
+    }
+    if let Some(&index) = indices.get(&node) {
+        return index;
+    }
+
+    if universe.level(node) == LEAF_LEVEL {
+        let mut cells = [[false; LEAF_SIZE]; LEAF_SIZE];
+        collect_leaf(universe, node, 0, 0, &mut cells);
+        let mut rows: Vec<String> = cells
+            .iter()
+            .map(|row| {
+                let line: String = row
+                    .iter()
+                    .map(|&alive| if alive { '*' } else { '.' })
+                    .collect();
+                line.trim_end_matches('.').to_string()
+            })
+            .collect();
+        while rows.last().is_some_and(|row| row.is_empty()) {
+            rows.pop();
+        }
+        for row in rows {
+            output.push_str(&row);
+            output.push('$');
+        }
+    } else {
+        let children = universe
+            .children(node)
+            .map(|child| write_node(universe, child, indices, output));
+        output.push_str(&format!(
+            "{} {} {} {} {}",
+            universe.level(node),
+            children[0],
+            children[1],
+            children[2],
+            children[3]
+        ));
+    }
+    output.push('\n');
+
+    let index = indices.len() + 1;
+    indices.insert(node, index);
+    index
+}
+
+/// 把节点中的细胞写入数组，节点左上角对应 (x, y)
+fn collect_leaf(
+    universe: &HashLife,
+    node: NodeId,
+    x: usize,
+    y: usize,
+    cells: &mut [[bool; LEAF_SIZE]; LEAF_SIZE],
+) {
+    let level = universe.level(node);
+    if level == 0 {
+        cells[y][x] = node == ALIVE;
+        return;
+    }
+    let half = 1 << (level - 1);
+    for (index, child) in universe.children(node).into_iter().enumerate() {
+        collect_leaf(
+            universe,
+            child,
+            x + (index % 2) * half,
+            y + (index / 2) * half,
+            cells,
+        );
+    }
+}
+
+/// 从宏细胞文件加载图案
+pub fn load_macrocell_file<P: AsRef<Path>>(path: P) -> Result<MacrocellPattern, RleError> {
+    let content = fs::read_to_string(path)?;
+    import_from_macrocell_string(&content)
+}
+
+/// 将宇宙保存为宏细胞文件
+pub fn save_macrocell_file<P: AsRef<Path>>(
+    path: P,
+    universe: &HashLife,
+    info: &MacrocellInfo,
+) -> Result<(), RleError> {
+    fs::write(path, export_to_macrocell_string(universe, info))?;
+    Ok(())
+}
+
+/// 宏细胞格式的稠密图案导出，供 `save_load` 按格式导出时使用
+pub(crate) fn export_pattern(pattern: &RlePattern) -> String {
+    let macrocell = MacrocellPattern::from_rle_pattern(pattern);
+    export_to_macrocell_string(&macrocell.universe, &macrocell.info)
+}
+
+/// 宏细胞格式的稠密图案导入，供 `save_load` 按格式导入时使用
+pub(crate) fn import_pattern(content: &str) -> Result<RlePattern, RleError> {
+    import_from_macrocell_string(content)?.to_rle_pattern()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Rule;
+    use crate::save_load;
+
+    /// 收集宇宙中的所有存活细胞（排序后便于比较）
+    fn alive_cells(universe: &HashLife) -> Vec<(i64, i64)> {
+        let mut cells = Vec::new();
+        let bound = 1i64 << 40;
+        universe.for_each_alive_in((-bound, -bound), (bound, bound), &mut |x, y| {
+            cells.push((x, y))
+        });
+        cells.sort();
+        cells
+    }
+
+    #[test]
+    fn test_import_glider() {
+        let data = "[M2] (golly 2.0)\n#R B36/S23\n#N Glider\n#C A small spaceship\n\
+                    .*$..*$***$\n4 0 0 0 1\n";
+        let pattern = import_from_macrocell_string(data).unwrap();
+        assert_eq!(pattern.info.name, "Glider");
+        assert_eq!(pattern.info.comment, "A small spaceship");
+        assert_eq!(*pattern.universe.rule(), Rule::parse("B36/S23").unwrap());
+        // 第 4 层根节点覆盖 [-8, 8)，叶子位于东南象限
+        assert_eq!(
+            alive_cells(&pattern.universe),
+            vec![(0, 2), (1, 0), (1, 2), (2, 1), (2, 2)]
+        );
+
+        let rle = save_load::import_pattern_string(data).unwrap();
+        assert_eq!((rle.width, rle.height), (3, 3));
+        assert_eq!(rle.rule, "B36/S23");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut universe = HashLife::new();
+        universe.set_rule(Rule::parse("B3/S238").unwrap()).unwrap();
+        for &(x, y) in &[(-20, -3), (-19, -3), (0, 0), (7, 8), (100, -50)] {
+            universe.set_alive(x, y, true);
+        }
+        let info = MacrocellInfo {
+            name: "Scattered".to_string(),
+            author: "Someone".to_string(),
+            comment: "first\nsecond".to_string(),
+            generation: 1234,
+        };
+
+        let text = export_to_macrocell_string(&universe, &info);
+        assert_eq!(
+            save_load::PatternFormat::detect(&text),
+            save_load::PatternFormat::Macrocell
+        );
+        let imported = import_from_macrocell_string(&text).unwrap();
+        assert_eq!(imported.info, info);
+        assert_eq!(*imported.universe.rule(), *universe.rule());
+        assert_eq!(alive_cells(&imported.universe), alive_cells(&universe));
+    }
+
+    #[test]
+    fn test_huge_sparse_pattern_stays_small() {
+        let mut universe = HashLife::new();
+        universe.set_alive(-100_000, -100_000, true);
+        universe.set_alive(100_000, 100_000, true);
+        let text = export_to_macrocell_string(&universe, &MacrocellInfo::default());
+        assert!(text.lines().count() < 100, "{} lines", text.lines().count());
+
+        let imported = import_from_macrocell_string(&text).unwrap();
+        assert_eq!(imported.universe.population(), 2);
+        assert!(imported.universe.is_alive(100_000, 100_000));
+        assert!(imported.universe.node_count() < 200);
+        // 稠密图案需要 200001 x 200001 个细胞，拒绝转换
+        assert!(matches!(
+            imported.to_rle_pattern(),
+            Err(RleError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_files() {
+        assert!(import_from_macrocell_string("x = 3, y = 1\n3o!\n").is_err());
+        let error = import_from_macrocell_string("[M2]\n.*x$\n").err().unwrap();
+        assert!(error.to_string().contains("line 2, column 3"), "{}", error);
+        // 第 5 层节点的子节点必须是第 4 层
+        assert!(import_from_macrocell_string("[M2]\n*$\n5 1 0 0 0\n").is_err());
+        assert!(import_from_macrocell_string("[M2]\n4 2 0 0 0\n").is_err());
+        assert!(import_from_macrocell_string("[M2]\n*********$\n").is_err());
+    }
+}
@@ -57,13 +57,7 @@ pub mod oscillators {
     pub const PENTADECATHLON: Pattern = Pattern {
         name: "Pentadecathlon",
         description: "Oscillator, period 15",
-        data: &[
-            "  O    O  ",
-            " OO    OO ",
-            "O  OOOO  O",
-            " OO    OO ",
-            "  O    O  ",
-        ],
+        data: &["  O    O  ", "OO OOOO OO", "  O    O  "],
     };
 }
 
@@ -74,29 +68,29 @@ pub mod spaceships {
     /// 滑翔机 - 最小的飞船，周期为4，每4代向右下移动一格
     pub const GLIDER: Pattern = Pattern {
         name: "Glider",
-        description: "Smallest spaceship, moves diagonally",
+        description: "Smallest spaceship, period 4, moves diagonally",
         data: &[" O ", "  O", "OOO"],
     };
 
     /// 轻量级飞船
     pub const LWSS: Pattern = Pattern {
         name: "LWSS",
-        description: "Lightweight spaceship",
+        description: "Lightweight spaceship, period 4",
         data: &[" OOOO", "O   O", "    O", "O  O "],
     };
 
     /// 中量级飞船
     pub const MWSS: Pattern = Pattern {
         name: "MWSS",
-        description: "Middleweight spaceship",
-        data: &["  O   ", " OOOO ", "O    O", "     O", "O   O "],
+        description: "Middleweight spaceship, period 4",
+        data: &["  O   ", "O   O ", "     O", "O    O", " OOOOO"],
     };
 
     /// 重量级飞船
     pub const HWSS: Pattern = Pattern {
         name: "HWSS",
-        description: "Heavyweight spaceship",
-        data: &["   O   ", "  OOOO ", " O    O", "      O", " O   O "],
+        description: "Heavyweight spaceship, period 4",
+        data: &["  OO   ", "O    O ", "      O", "O     O", " OOOOOO"],
     };
 }
 
@@ -180,6 +174,48 @@ pub mod miscellaneous {
         description: "Still life - never changes",
         data: &["OO", "OO"],
     };
+
+    /// 蜂巢 - 随机汤中第二常见的静物
+    pub const BEEHIVE: Pattern = Pattern {
+        name: "Beehive",
+        description: "Still life - common in soup ash",
+        data: &[" OO ", "O  O", " OO "],
+    };
+
+    /// 面包
+    pub const LOAF: Pattern = Pattern {
+        name: "Loaf",
+        description: "Still life - common in soup ash",
+        data: &[" OO ", "O  O", " O O", "  O "],
+    };
+
+    /// 小船
+    pub const BOAT: Pattern = Pattern {
+        name: "Boat",
+        description: "Still life - common in soup ash",
+        data: &["OO ", "O O", " O "],
+    };
+
+    /// 浴缸
+    pub const TUB: Pattern = Pattern {
+        name: "Tub",
+        description: "Still life - common in soup ash",
+        data: &[" O ", "O O", " O "],
+    };
+
+    /// 船
+    pub const SHIP: Pattern = Pattern {
+        name: "Ship",
+        description: "Still life - common in soup ash",
+        data: &["OO ", "O O", " OO"],
+    };
+
+    /// 池塘
+    pub const POND: Pattern = Pattern {
+        name: "Pond",
+        description: "Still life - common in soup ash",
+        data: &[" OO ", "O  O", "O  O", " OO "],
+    };
 }
 
 /// 获取所有预设图案的列表
@@ -215,6 +251,12 @@ pub fn get_all_patterns() -> Vec<(&'static str, Vec<&'static Pattern>)> {
                 &miscellaneous::DIEHARD,
                 &miscellaneous::ACORN,
                 &miscellaneous::BLOCK,
+                &miscellaneous::BEEHIVE,
+                &miscellaneous::LOAF,
+                &miscellaneous::BOAT,
+                &miscellaneous::TUB,
+                &miscellaneous::SHIP,
+                &miscellaneous::POND,
             ],
         ),
     ]
@@ -305,4 +347,39 @@ mod tests {
         let r_pentomino = &miscellaneous::R_PENTOMINO;
         assert_eq!(r_pentomino.data.len(), 3);
     }
+
+    /// 图案演化 `generations` 代后的存活细胞，坐标相对于放置位置
+    fn evolve(pattern: &Pattern, generations: usize) -> Vec<(i64, i64)> {
+        let mut grid = crate::game::Grid::new(40, 30);
+        grid.load_pattern(pattern.data, 15, 10);
+        for _ in 0..generations {
+            grid.next_generation();
+        }
+        let mut cells = Vec::new();
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                if grid.is_alive(x, y) {
+                    cells.push((x as i64 - 15, y as i64 - 10));
+                }
+            }
+        }
+        cells
+    }
+
+    #[test]
+    fn test_corrected_presets() {
+        // Pentadecathlon、MWSS 和 HWSS 的预设曾使用错误的形状，
+        // 这里检查它们确实按描述的周期振荡或移动
+        let pentadecathlon = evolve(&oscillators::PENTADECATHLON, 0);
+        assert_eq!(pentadecathlon.len(), 12);
+        assert!((1..15).all(|t| evolve(&oscillators::PENTADECATHLON, t) != pentadecathlon));
+        assert_eq!(evolve(&oscillators::PENTADECATHLON, 15), pentadecathlon);
+
+        for (ship, population) in [(&spaceships::MWSS, 11), (&spaceships::HWSS, 13)] {
+            let start = evolve(ship, 0);
+            assert_eq!(start.len(), population, "{}", ship.name);
+            let moved: Vec<(i64, i64)> = evolve(ship, 4).iter().map(|&(x, y)| (x - 2, y)).collect();
+            assert_eq!(moved, start, "{}", ship.name);
+        }
+    }
 }
@@ -0,0 +1,290 @@
+//! 周期检测模块
+//! 把每一代的活细胞平移到包围盒左上角后计算哈希，与之前出现过的状态比较，
+//! 判断整个图案是静物、振荡器还是平移的飞船
+
+use crate::game::LifeEngine;
+use crate::random::SplitMix64;
+use rustc_hash::FxHashMap;
+use std::collections::VecDeque;
+use std::fmt;
+
+/// 默认最多回看的代数
+pub const DEFAULT_MAX_PERIOD: u64 = 4096;
+
+/// 检测到的周期性
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Periodicity {
+    /// 没有活细胞
+    Extinct,
+    /// 每一代都不变
+    StillLife,
+    /// 原地振荡，每 `period` 代回到相同状态
+    Oscillator { period: u64 },
+    /// 每 `period` 代回到相同形状并平移 (dx, dy)
+    Spaceship { period: u64, dx: i64, dy: i64 },
+}
+
+impl Periodicity {
+    /// 根据两次出现相同形状的间隔和平移量分类
+    fn classify(period: u64, dx: i64, dy: i64) -> Self {
+        match (dx, dy) {
+            (0, 0) if period == 1 => Periodicity::StillLife,
+            (0, 0) => Periodicity::Oscillator { period },
+            _ => Periodicity::Spaceship { period, dx, dy },
+        }
+    }
+}
+
+impl fmt::Display for Periodicity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Periodicity::Extinct => write!(f, "extinct"),
+            Periodicity::StillLife => write!(f, "still life"),
+            Periodicity::Oscillator { period } => write!(f, "oscillator with period {}", period),
+            Periodicity::Spaceship { period, dx, dy } => write!(
+                f,
+                "translating with displacement ({}, {}) every {} generations",
+                dx, dy, period
+            ),
+        }
+    }
+}
+
+/// 某个形状最近一次出现的位置
+#[derive(Clone, Copy)]
+struct Sighting {
+    generation: u64,
+    /// 包围盒左上角
+    origin: (i64, i64),
+}
+
+/// 基于状态哈希的周期检测器
+///
+/// 每推进一步调用一次 [`PeriodDetector::observe`]。如果每步推进多代（如 HashLife 的 2^k 代），
+/// 检测到的周期是真实周期的倍数
+pub struct PeriodDetector {
+    /// 形状哈希 -> 最近一次出现
+    sightings: FxHashMap<u64, Sighting>,
+    /// 按代数排列的 (代数, 形状哈希)，用于丢弃过旧的记录
+    order: VecDeque<(u64, u64)>,
+    /// 最多回看的代数
+    max_period: u64,
+    /// 最近一次观察的代数
+    last_generation: Option<u64>,
+    /// 最近一次观察的结果
+    result: Option<Periodicity>,
+}
+
+impl PeriodDetector {
+    /// 创建最多回看 `max_period` 代的检测器
+    pub fn new(max_period: u64) -> Self {
+        Self {
+            sightings: FxHashMap::default(),
+            order: VecDeque::new(),
+            max_period,
+            last_generation: None,
+            result: None,
+        }
+    }
+
+    /// 清除所有记录（网格被编辑或重新生成后调用）
+    pub fn reset(&mut self) {
+        self.sightings.clear();
+        self.order.clear();
+        self.last_generation = None;
+        self.result = None;
+    }
+
+    /// 观察第 `generation` 代的状态
+    ///
+    /// # 返回值
+    /// 当前状态与之前某一代形状相同时返回检测到的周期性，否则返回 `None`。
+    /// 代数没有递增时视为新的演化，自动清除之前的记录
+    pub fn observe(&mut self, engine: &dyn LifeEngine, generation: u64) -> Option<Periodicity> {
+        if self.last_generation.is_some_and(|last| generation <= last) {
+            self.reset();
+        }
+        self.last_generation = Some(generation);
+
+        let bounds = match engine.bounding_box() {
+            Some(bounds) => bounds,
+            None => {
+                self.result = Some(Periodicity::Extinct);
+                return self.result;
+            }
+        };
+        let origin = (bounds.min_x, bounds.min_y);
+        let hash = shape_hash(engine, origin, (bounds.max_x + 1, bounds.max_y + 1));
+
+        // 丢弃超出回看范围的记录
+        while let Some(&(old_generation, old_hash)) = self.order.front() {
+            if generation - old_generation <= self.max_period {
+                break;
+            }
+            self.order.pop_front();
+            if self
+                .sightings
+                .get(&old_hash)
+                .is_some_and(|sighting| sighting.generation == old_generation)
+            {
+                self.sightings.remove(&old_hash);
+            }
+        }
+
+        // 最近一次出现相同形状的间隔就是最小周期
+        self.result = self.sightings.get(&hash).map(|previous| {
+            Periodicity::classify(
+                generation - previous.generation,
+                origin.0 - previous.origin.0,
+                origin.1 - previous.origin.1,
+            )
+        });
+        self.sightings.insert(hash, Sighting { generation, origin });
+        self.order.push_back((generation, hash));
+        self.result
+    }
+
+    /// 最近一次观察的结果
+    pub fn result(&self) -> Option<Periodicity> {
+        self.result
+    }
+
+    /// 已记录的代数
+    pub fn observed_generations(&self) -> usize {
+        self.order.len()
+    }
+}
+
+impl Default for PeriodDetector {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_PERIOD)
+    }
+}
+
+/// 计算平移到 `origin` 后的活细胞形状哈希
+///
+/// 各细胞的哈希相加，与遍历顺序无关（稀疏瓦片按哈希表顺序遍历）
+fn shape_hash(engine: &dyn LifeEngine, origin: (i64, i64), end: (i64, i64)) -> u64 {
+    let mut sum = 0u64;
+    let mut count = 0u64;
+    engine.for_each_alive_in(origin, end, &mut |x, y| {
+        let key = (((x - origin.0) as u64) << 32) ^ ((y - origin.1) as u64 & 0xffff_ffff);
+        sum = sum.wrapping_add(SplitMix64::new(key).next_u64());
+        count += 1;
+    });
+    let size = (((end.0 - origin.0) as u64) << 32) ^ (end.1 - origin.1) as u64;
+    SplitMix64::new(sum ^ count.rotate_left(17) ^ size.rotate_left(41)).next_u64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{CellState, Grid};
+    use crate::patterns::{miscellaneous, oscillators, spaceships, Pattern};
+
+    /// 从图案描述中读取声明的周期，如 "Oscillator, period 15"
+    fn declared_period(pattern: &Pattern) -> u64 {
+        let (_, rest) = pattern.description.split_once("period ").unwrap();
+        let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+        digits.parse().unwrap()
+    }
+
+    /// 把图案放在大网格中央，逐代观察直到检测到周期
+    fn detect(pattern: &Pattern) -> Option<(Periodicity, u64)> {
+        let mut grid = Grid::new(80, 80);
+        grid.load_pattern(pattern.data, 40, 40);
+        let mut detector = PeriodDetector::default();
+        for generation in 0..64 {
+            if generation > 0 {
+                grid.next_generation();
+            }
+            if let Some(result) = detector.observe(&grid, generation) {
+                return Some((result, generation));
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn test_oscillator_periods() {
+        for pattern in [
+            &oscillators::BLINKER,
+            &oscillators::TOAD,
+            &oscillators::BEACON,
+            &oscillators::PULSAR,
+            &oscillators::PENTADECATHLON,
+        ] {
+            let period = declared_period(pattern);
+            let (result, generation) = detect(pattern).unwrap();
+            assert_eq!(result, Periodicity::Oscillator { period }, "{}", pattern.name);
+            assert_eq!(generation, period, "{}", pattern.name);
+        }
+    }
+
+    #[test]
+    fn test_spaceship_periods() {
+        for pattern in [
+            &spaceships::GLIDER,
+            &spaceships::LWSS,
+            &spaceships::MWSS,
+            &spaceships::HWSS,
+        ] {
+            let period = declared_period(pattern);
+            match detect(pattern) {
+                Some((Periodicity::Spaceship { period: found, dx, dy }, _)) => {
+                    assert_eq!(found, period, "{}", pattern.name);
+                    assert!(dx != 0 || dy != 0);
+                }
+                other => panic!("{}: {:?}", pattern.name, other),
+            }
+        }
+        // 滑翔机每4代向右下移动一格
+        assert_eq!(
+            detect(&spaceships::GLIDER).map(|(result, _)| result),
+            Some(Periodicity::Spaceship { period: 4, dx: 1, dy: 1 })
+        );
+    }
+
+    #[test]
+    fn test_still_life_and_extinction() {
+        assert_eq!(
+            detect(&miscellaneous::BLOCK),
+            Some((Periodicity::StillLife, 1))
+        );
+
+        let mut grid = Grid::new(10, 10);
+        grid.set_cell(5, 5, CellState::Alive);
+        let mut detector = PeriodDetector::default();
+        assert_eq!(detector.observe(&grid, 0), None);
+        grid.next_generation();
+        assert_eq!(detector.observe(&grid, 1), Some(Periodicity::Extinct));
+    }
+
+    #[test]
+    fn test_reset_on_generation_rewind() {
+        let mut grid = Grid::new(20, 20);
+        grid.load_pattern(oscillators::BLINKER.data, 10, 10);
+        let mut detector = PeriodDetector::default();
+        detector.observe(&grid, 0);
+        grid.next_generation();
+        detector.observe(&grid, 1);
+        grid.next_generation();
+        assert!(detector.observe(&grid, 2).is_some());
+
+        // 代数回退（如撤销或清空后）会清除之前的记录
+        assert_eq!(detector.observe(&grid, 0), None);
+        assert_eq!(detector.observed_generations(), 1);
+    }
+
+    #[test]
+    fn test_periodicity_display() {
+        assert_eq!(
+            Periodicity::Oscillator { period: 15 }.to_string(),
+            "oscillator with period 15"
+        );
+        assert_eq!(
+            Periodicity::Spaceship { period: 4, dx: 1, dy: -1 }.to_string(),
+            "translating with displacement (1, -1) every 4 generations"
+        );
+    }
+}
@@ -0,0 +1,320 @@
+/// 演化规则模块
+/// 负责解析和表示 B/S（出生/存活）记法的生命类元胞自动机规则
+use std::fmt;
+use std::str::FromStr;
+
+/// 生命类元胞自动机的规则
+///
+/// `birth[n]` 表示死亡细胞在恰好有 n 个存活邻居时复活，
+/// `survival[n]` 表示存活细胞在恰好有 n 个存活邻居时继续存活。
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct Rule {
+    birth: [bool; 9],
+    survival: [bool; 9],
+}
+
+/// 常用规则预设：(名称, B/S 记法)
+pub const RULE_PRESETS: &[(&str, &str)] = &[
+    ("Conway's Life", "B3/S23"),
+    ("HighLife", "B36/S23"),
+    ("Seeds", "B2/S"),
+    ("Day & Night", "B3678/S34678"),
+    ("Life without Death", "B3/S012345678"),
+    ("Replicator", "B1357/S1357"),
+    ("Maze", "B3/S12345"),
+    ("2x2", "B36/S125"),
+];
+
+impl Rule {
+    /// 根据出生和存活邻居数量列表创建规则
+    ///
+    /// 大于8的邻居数量会被忽略
+    pub fn new(birth: &[usize], survival: &[usize]) -> Self {
+        let mut rule = Self {
+            birth: [false; 9],
+            survival: [false; 9],
+        };
+        for &n in birth.iter().filter(|&&n| n <= 8) {
+            rule.birth[n] = true;
+        }
+        for &n in survival.iter().filter(|&&n| n <= 8) {
+            rule.survival[n] = true;
+        }
+        rule
+    }
+
+    /// 康威生命游戏的标准规则 B3/S23
+    pub fn conway() -> Self {
+        Self::new(&[3], &[2, 3])
+    }
+
+    /// 解析规则字符串
+    ///
+    /// 支持以下写法（大小写不敏感）：
+    /// * B/S 记法，如 `B36/S23`、`B2/S`
+    /// * S/B 记法，如 `S23/B3`
+    /// * 传统的 `存活/出生` 数字记法，如 `23/3`
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err("Empty rule string".to_string());
+        }
+
+        let parts: Vec<&str> = text.split('/').map(str::trim).collect();
+        if parts.len() != 2 {
+            return Err(format!("Rule must contain exactly one '/': {}", text));
+        }
+
+        let mut birth = None;
+        let mut survival = None;
+        for (index, part) in parts.iter().enumerate() {
+            match part.chars().next().map(|c| c.to_ascii_uppercase()) {
+                Some('B') => birth = Some(Self::parse_digits(&part[1..], text)?),
+                Some('S') => survival = Some(Self::parse_digits(&part[1..], text)?),
+                // 传统记法：第一部分为存活条件，第二部分为出生条件
+                _ if index == 0 => survival = Some(Self::parse_digits(part, text)?),
+                _ => birth = Some(Self::parse_digits(part, text)?),
+            }
+        }
+
+        match (birth, survival) {
+            (Some(birth), Some(survival)) => Ok(Self { birth, survival }),
+            _ => Err(format!("Rule needs both a B and an S part: {}", text)),
+        }
+    }
+
+    /// 解析一串邻居数量数字
+    fn parse_digits(digits: &str, rule: &str) -> Result<[bool; 9], String> {
+        let mut counts = [false; 9];
+        for ch in digits.chars() {
+            match ch.to_digit(10) {
+                Some(n) if n <= 8 => counts[n as usize] = true,
+                _ => return Err(format!("Invalid neighbor count '{}' in rule {}", ch, rule)),
+            }
+        }
+        Ok(counts)
+    }
+
+    /// 判断死亡细胞在给定邻居数量下是否复活
+    pub fn is_birth(&self, neighbors: usize) -> bool {
+        neighbors <= 8 && self.birth[neighbors]
+    }
+
+    /// 判断存活细胞在给定邻居数量下是否继续存活
+    pub fn is_survival(&self, neighbors: usize) -> bool {
+        neighbors <= 8 && self.survival[neighbors]
+    }
+
+    /// 根据当前状态和邻居数量计算细胞的下一状态
+    pub fn next_state(&self, alive: bool, neighbors: usize) -> bool {
+        if alive {
+            self.is_survival(neighbors)
+        } else {
+            self.is_birth(neighbors)
+        }
+    }
+
+    /// 是否为康威生命游戏标准规则
+    pub fn is_conway(&self) -> bool {
+        *self == Self::conway()
+    }
+
+    /// 检查规则能否用于无界宇宙
+    ///
+    /// 出生条件包含 B0 时，无限平面上的空白区域每一代都会全部复活，无界引擎无法表示，返回错误
+    pub fn check_unbounded(&self) -> Result<(), String> {
+        if self.is_birth(0) {
+            Err(format!(
+                "{} has birth on 0 neighbors (B0), which an unbounded universe cannot simulate",
+                self
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 以位并行方式计算64个细胞的下一状态
+    ///
+    /// # 参数
+    /// * `alive` - 64个细胞的当前状态（每位一个细胞）
+    /// * `neighbors` - 8个方向上的邻居字，与 `alive` 按位对齐
+    pub fn next_word(&self, alive: u64, neighbors: &[u64; 8]) -> u64 {
+        let count = neighbor_count_bits(neighbors);
+
+        let mut born = 0;
+        let mut survive = 0;
+        for n in 0..=8 {
+            if !self.birth[n] && !self.survival[n] {
+                continue;
+            }
+            // 邻居数量恰好为 n 的细胞掩码
+            let equals = (0..4).fold(!0u64, |mask, bit| {
+                mask & if n >> bit & 1 == 1 { count[bit] } else { !count[bit] }
+            });
+            if self.birth[n] {
+                born |= equals;
+            }
+            if self.survival[n] {
+                survive |= equals;
+            }
+        }
+        (alive & survive) | (!alive & born)
+    }
+
+    /// 返回预设中对应的规则名称（如果有）
+    pub fn preset_name(&self) -> Option<&'static str> {
+        RULE_PRESETS
+            .iter()
+            .find(|(_, notation)| Self::parse(notation).ok().as_ref() == Some(self))
+            .map(|(name, _)| *name)
+    }
+}
+
+/// B3/S23 的位并行快速路径，结果与 `Rule::conway().next_word` 相同
+pub fn conway_next_word(alive: u64, neighbors: &[u64; 8]) -> u64 {
+    let [ones, twos, fours, eights] = neighbor_count_bits(neighbors);
+    // 邻居数为2或3（二进制 001x），且为3或细胞本身存活
+    twos & !fours & !eights & (ones | alive)
+}
+
+/// 位切片加法器：把8个邻居字逐位相加，返回邻居数量的四个二进制位（1、2、4、8）
+fn neighbor_count_bits(n: &[u64; 8]) -> [u64; 4] {
+    // 全加器：返回(和, 进位)
+    fn full_add(a: u64, b: u64, c: u64) -> (u64, u64) {
+        let partial = a ^ b;
+        (partial ^ c, (a & b) | (partial & c))
+    }
+
+    let (sum_a, carry_a) = full_add(n[0], n[1], n[2]);
+    let (sum_b, carry_b) = full_add(n[3], n[4], n[5]);
+    let (sum_c, carry_c) = (n[6] ^ n[7], n[6] & n[7]);
+
+    // 权重为1的位
+    let (ones, carry_d) = full_add(sum_a, sum_b, sum_c);
+    // 权重为2的四个进位相加
+    let (partial_twos, carry_e) = full_add(carry_a, carry_b, carry_c);
+    let (twos, carry_f) = (partial_twos ^ carry_d, partial_twos & carry_d);
+    // 权重为4的两个进位相加（只有8个邻居全部存活时才会产生权重8）
+    let (fours, eights) = (carry_e ^ carry_f, carry_e & carry_f);
+
+    [ones, twos, fours, eights]
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Self::conway()
+    }
+}
+
+/// 以标准 B/S 记法输出规则，如 `B36/S23`
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = |counts: &[bool; 9]| -> String {
+            (0..9)
+                .filter(|&n| counts[n])
+                .map(|n| char::from(b'0' + n as u8))
+                .collect()
+        };
+        write!(f, "B{}/S{}", digits(&self.birth), digits(&self.survival))
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bs_notation() {
+        let highlife = Rule::parse("B36/S23").unwrap();
+        assert!(highlife.is_birth(3));
+        assert!(highlife.is_birth(6));
+        assert!(!highlife.is_birth(2));
+        assert!(highlife.is_survival(2));
+        assert!(highlife.is_survival(3));
+        assert!(!highlife.is_survival(4));
+    }
+
+    #[test]
+    fn test_parse_sb_and_legacy_notation() {
+        let conway = Rule::conway();
+        assert_eq!(Rule::parse("S23/B3").unwrap(), conway);
+        assert_eq!(Rule::parse("23/3").unwrap(), conway);
+        assert_eq!(Rule::parse("b3/s23").unwrap(), conway);
+    }
+
+    #[test]
+    fn test_parse_empty_parts() {
+        // Seeds: 没有任何存活条件
+        let seeds = Rule::parse("B2/S").unwrap();
+        assert!(seeds.is_birth(2));
+        assert!((0..=8).all(|n| !seeds.is_survival(n)));
+    }
+
+    #[test]
+    fn test_check_unbounded() {
+        assert!(Rule::conway().check_unbounded().is_ok());
+        assert!(Rule::parse("B0/S8").unwrap().check_unbounded().is_err());
+        assert!(Rule::parse("B013/S23").unwrap().check_unbounded().is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Rule::parse("").is_err());
+        assert!(Rule::parse("B3S23").is_err());
+        assert!(Rule::parse("B39/S23").is_err());
+        assert!(Rule::parse("B3/S2x").is_err());
+        assert!(Rule::parse("B3/B3").is_err());
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for (_, notation) in RULE_PRESETS {
+            let rule = Rule::parse(notation).unwrap();
+            assert_eq!(rule.to_string(), *notation);
+        }
+        assert_eq!(Rule::parse("S34678/B3678").unwrap().to_string(), "B3678/S34678");
+    }
+
+    #[test]
+    fn test_preset_name() {
+        assert_eq!(Rule::conway().preset_name(), Some("Conway's Life"));
+        assert_eq!(Rule::parse("B2/S").unwrap().preset_name(), Some("Seeds"));
+        assert_eq!(Rule::parse("B1/S1").unwrap().preset_name(), None);
+    }
+
+    #[test]
+    fn test_next_word_matches_next_state() {
+        // 每个位置代表一种(存活, 邻居数量)组合，逐位与标量实现比较
+        let mut alive = 0u64;
+        let mut neighbors = [0u64; 8];
+        for bit in 0..18 {
+            let (is_alive, count) = (bit >= 9, bit % 9);
+            if is_alive {
+                alive |= 1 << bit;
+            }
+            for neighbor in neighbors.iter_mut().take(count) {
+                *neighbor |= 1 << bit;
+            }
+        }
+
+        for (_, notation) in RULE_PRESETS {
+            let rule = Rule::parse(notation).unwrap();
+            let next = rule.next_word(alive, &neighbors);
+            for bit in 0..18 {
+                let expected = rule.next_state(bit >= 9, bit % 9);
+                assert_eq!(next >> bit & 1 == 1, expected, "{} bit {}", notation, bit);
+            }
+        }
+
+        let conway = Rule::conway().next_word(alive, &neighbors);
+        assert_eq!(conway_next_word(alive, &neighbors), conway);
+    }
+}
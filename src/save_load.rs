@@ -1,15 +1,50 @@
-/// RLE格式保存和加载模块
-/// 专门支持RLE (Run Length Encoded) 格式的文件保存和加载功能
+/// 图案文件保存和加载模块
+/// 支持RLE (Run Length Encoded)、LifeWiki 纯文本 (.cells)、Life 1.05/1.06 以及宏细胞 (.mc) 格式，
+/// 读取时按内容自动识别格式
 use crate::game::{CellState, Grid};
+use crate::macrocell;
+use crate::rules::Rule;
+use crate::topology::Topology;
 use std::fs;
 use std::path::Path;
 
 /// RLE格式的错误类型
+///
+/// RLE 头部和图案数据中的错误带有行号和列号（从 1 开始）
 #[derive(Debug)]
 pub enum RleError {
     IoError(std::io::Error),
     ParseError(String),
     InvalidFormat(String),
+    /// 头部行的字段无效
+    InvalidHeader {
+        line: usize,
+        message: String,
+    },
+    /// 图案数据中无法识别的字符
+    InvalidCharacter {
+        line: usize,
+        column: usize,
+        found: char,
+    },
+    /// 游程长度无法表示（数字过大）
+    InvalidRunCount {
+        line: usize,
+        column: usize,
+        count: String,
+    },
+    /// 游程长度或多状态前缀后面缺少细胞状态字母或 `$`
+    MissingRunTag {
+        line: usize,
+        column: usize,
+    },
+    /// 严格模式下，游程超出头部声明的尺寸
+    OutOfBounds {
+        line: usize,
+        column: usize,
+        width: usize,
+        height: usize,
+    },
 }
 
 impl From<std::io::Error> for RleError {
@@ -24,6 +59,42 @@ impl std::fmt::Display for RleError {
             RleError::IoError(err) => write!(f, "IO error: {}", err),
             RleError::ParseError(msg) => write!(f, "Parse error: {}", msg),
             RleError::InvalidFormat(msg) => write!(f, "Invalid format: {}", msg),
+            RleError::InvalidHeader { line, message } => {
+                write!(f, "Invalid header at line {}: {}", line, message)
+            }
+            RleError::InvalidCharacter {
+                line,
+                column,
+                found,
+            } => write!(
+                f,
+                "Invalid character '{}' at line {}, column {}",
+                found, line, column
+            ),
+            RleError::InvalidRunCount {
+                line,
+                column,
+                count,
+            } => write!(
+                f,
+                "Invalid run count {} at line {}, column {}",
+                count, line, column
+            ),
+            RleError::MissingRunTag { line, column } => write!(
+                f,
+                "Incomplete run at line {}, column {} (expected a cell state or $)",
+                line, column
+            ),
+            RleError::OutOfBounds {
+                line,
+                column,
+                width,
+                height,
+            } => write!(
+                f,
+                "Run at line {}, column {} extends past the declared size {} x {}",
+                line, column, width, height
+            ),
         }
     }
 }
@@ -32,12 +103,39 @@ impl std::error::Error for RleError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             RleError::IoError(e) => Some(e),
-            RleError::ParseError(_) => None,
-            RleError::InvalidFormat(_) => None,
+            _ => None,
         }
     }
 }
 
+/// RLE 图案数据的解析模式
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// 超出头部声明尺寸的游程视为错误
+    Strict,
+    /// 丢弃超出声明尺寸的细胞，并为每个这样的游程记录一条警告
+    #[default]
+    Lenient,
+}
+
+/// 宽松模式下解析 RLE 时发现的问题
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RleWarning {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for RleWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
 /// RLE图案数据结构
 #[derive(Debug, Clone)]
 pub struct RlePattern {
@@ -48,6 +146,10 @@ pub struct RlePattern {
     pub height: usize,
     pub rule: String,
     pub data: Vec<Vec<bool>>,
+    /// 图案左上角在宇宙中的坐标（来自 `#CXRLE Pos=`、`#P` 或 `#R`），没有记录时为 `None`
+    pub position: Option<(i64, i64)>,
+    /// 图案所处的代数（来自 `#CXRLE Gen=`）
+    pub generation: u64,
 }
 
 impl RlePattern {
@@ -59,8 +161,10 @@ impl RlePattern {
             author: String::new(),
             width,
             height,
-            rule: "B3/S23".to_string(), // 康威生命游戏标准规则
+            rule: Rule::conway().to_string(), // 康威生命游戏标准规则
             data: vec![vec![false; width]; height],
+            position: None,
+            generation: 0,
         }
     }
 
@@ -68,13 +172,13 @@ impl RlePattern {
     pub fn from_grid(grid: &Grid, name: String) -> Self {
         let width = grid.width();
         let height = grid.height();
-        let mut data = vec![vec![false; width]; height];
-
-        for y in 0..height {
-            for x in 0..width {
-                data[y][x] = matches!(grid.get_cell(x, y), CellState::Alive);
-            }
-        }
+        let data = (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| matches!(grid.get_cell(x, y), CellState::Alive))
+                    .collect()
+            })
+            .collect();
 
         Self {
             name,
@@ -82,13 +186,36 @@ impl RlePattern {
             author: String::new(),
             width,
             height,
-            rule: "B3/S23".to_string(),
+            rule: format_rule_field(grid.rule(), grid.topology(), width, height),
             data,
+            position: None,
+            generation: 0,
+        }
+    }
+
+    /// 解析图案头部中的规则字符串（忽略拓扑后缀）
+    pub fn parse_rule(&self) -> Result<Rule, RleError> {
+        let (rule, _) = split_rule_field(&self.rule);
+        Rule::parse(rule).map_err(RleError::ParseError)
+    }
+
+    /// 解析规则字符串中 Golly 风格的拓扑后缀（如 `:T60,40`）
+    ///
+    /// # 返回值
+    /// 返回拓扑以及后缀中声明的网格尺寸；没有后缀时为有界平面
+    pub fn parse_topology(&self) -> Result<(Topology, Option<(usize, usize)>), RleError> {
+        match split_rule_field(&self.rule) {
+            (_, Some(suffix)) => {
+                Topology::parse_golly_suffix(suffix).map_err(RleError::ParseError)
+            }
+            (_, None) => Ok((Topology::Bounded, None)),
         }
     }
 
-    /// 将RLE图案转换为Grid
-    #[allow(dead_code)]
+    /// 将RLE图案转换为Grid（使用图案头部中的规则和拓扑）
+    ///
+    /// 如果拓扑后缀声明了网格尺寸，则使用该尺寸创建网格；声明的尺寸放不下存活细胞，
+    /// 或拓扑要求正方形而网格不是正方形时返回错误
     pub fn to_grid(&self) -> Result<Grid, RleError> {
         if self.width == 0 || self.height == 0 {
             return Err(RleError::InvalidFormat(
@@ -96,7 +223,17 @@ impl RlePattern {
             ));
         }
 
-        let mut grid = Grid::new(self.width, self.height);
+        let (topology, size) = self.parse_topology()?;
+        let (width, height) = size.unwrap_or((self.width, self.height));
+        if let Some((x, y)) = self.first_cell_outside(width, height) {
+            return Err(RleError::InvalidFormat(format!(
+                "Live cell at ({}, {}) lies outside the {}x{} grid declared by the rule suffix",
+                x, y, width, height
+            )));
+        }
+        let mut grid = Grid::new(width, height);
+        grid.set_rule(self.parse_rule()?);
+        grid.set_topology(topology).map_err(RleError::InvalidFormat)?;
 
         for y in 0..self.height {
             for x in 0..self.width {
@@ -108,18 +245,55 @@ impl RlePattern {
 
         Ok(grid)
     }
+
+    /// 第一个位于 `width` x `height` 范围之外的存活细胞（按行扫描）
+    pub fn first_cell_outside(&self, width: usize, height: usize) -> Option<(usize, usize)> {
+        self.data.iter().enumerate().find_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .find(|&(x, &alive)| alive && (x >= width || y >= height))
+                .map(|(x, _)| (x, y))
+        })
+    }
+}
+
+/// 将规则字段拆分为 B/S 规则部分和可选的拓扑后缀（冒号之后的部分）
+fn split_rule_field(rule: &str) -> (&str, Option<&str>) {
+    match rule.split_once(':') {
+        Some((rule, suffix)) => (rule.trim(), Some(suffix.trim())),
+        None => (rule.trim(), None),
+    }
+}
+
+/// 生成头部中的规则字段，非有界拓扑附加 Golly 风格后缀（如 `B3/S23:T60,40`）
+pub fn format_rule_field(rule: &Rule, topology: Topology, width: usize, height: usize) -> String {
+    match topology.to_golly_suffix(width, height) {
+        Some(suffix) => format!("{}:{}", rule, suffix),
+        None => rule.to_string(),
+    }
 }
 
 /// 将RLE图案导出为RLE格式字符串
 pub fn export_to_rle_string(pattern: &RlePattern) -> String {
     let mut result = String::new();
 
+    // 与 Golly 相同，位置和代数写在第一行的 #CXRLE 中
+    if pattern.position.is_some() || pattern.generation > 0 {
+        let (x, y) = pattern.position.unwrap_or((0, 0));
+        result.push_str(&format!("{} Pos={},{}", CXRLE_PREFIX, x, y));
+        if pattern.generation > 0 {
+            result.push_str(&format!(" Gen={}", pattern.generation));
+        }
+        result.push('\n');
+    }
+
     // 添加注释行
     if !pattern.name.is_empty() {
         result.push_str(&format!("#N {}\n", pattern.name));
     }
-    if !pattern.comment.is_empty() {
-        result.push_str(&format!("#C {}\n", pattern.comment));
+    // 多行注释每行单独输出为一个 #C 行
+    for line in pattern.comment.lines() {
+        result.push_str(&format!("#C {}\n", line));
     }
     if !pattern.author.is_empty() {
         result.push_str(&format!("#O {}\n", pattern.author));
@@ -160,7 +334,7 @@ pub fn export_to_rle_string(pattern: &RlePattern) -> String {
     }
 
     // 移除末尾的空行
-    while encoded_lines.last().map_or(false, |line| line.is_empty()) {
+    while encoded_lines.last().is_some_and(|line| line.is_empty()) {
         encoded_lines.pop();
     }
 
@@ -200,37 +374,53 @@ pub fn save_rle_file<P: AsRef<Path>>(
 ) -> Result<(), RleError> {
     let pattern_name = name.unwrap_or_else(|| "Exported Pattern".to_string());
     let pattern = RlePattern::from_grid(grid, pattern_name);
-    let rle_string = export_to_rle_string(&pattern);
-    fs::write(path, rle_string)?;
+    save_rle_pattern(path, &pattern)
+}
+
+/// 保存已构建好的RLE图案（可带注释、作者等信息）到文件
+pub fn save_rle_pattern<P: AsRef<Path>>(path: P, pattern: &RlePattern) -> Result<(), RleError> {
+    fs::write(path, export_to_rle_string(pattern))?;
     Ok(())
 }
 
-/// 从RLE格式字符串导入图案
+/// 从RLE格式字符串导入图案（宽松模式，忽略警告）
 pub fn import_from_rle_string(rle_data: &str) -> Result<RlePattern, RleError> {
-    let lines: Vec<&str> = rle_data.lines().collect();
+    import_from_rle_string_with_mode(rle_data, ParseMode::Lenient).map(|(pattern, _)| pattern)
+}
+
+/// 按指定模式从RLE格式字符串导入图案
+///
+/// # 返回值
+/// 返回图案以及宽松模式下的警告；严格模式下超出声明尺寸的游程返回 `RleError::OutOfBounds`
+pub fn import_from_rle_string_with_mode(
+    rle_data: &str,
+    mode: ParseMode,
+) -> Result<(RlePattern, Vec<RleWarning>), RleError> {
     let mut pattern = RlePattern::new("Imported Pattern".to_string(), 0, 0);
     let mut header_line = None;
+    // 图案数据行保留原样和行号，以便报告准确的位置
     let mut pattern_lines = Vec::new();
 
     // 解析头部信息和图案数据
-    for line in lines {
-        let line = line.trim();
-        if line.is_empty() {
+    for (index, line) in rle_data.lines().enumerate() {
+        let line_number = index + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
             continue;
         }
 
-        if line.starts_with('#') {
-            parse_comment_line(line, &mut pattern);
-        } else if line.starts_with("x =") || line.starts_with("x=") {
-            header_line = Some(line);
+        if trimmed.starts_with('#') {
+            parse_comment_line(trimmed, line_number, &mut pattern)?;
+        } else if trimmed.starts_with("x =") || trimmed.starts_with("x=") {
+            header_line = Some((line_number, trimmed));
         } else {
-            pattern_lines.push(line);
+            pattern_lines.push((line_number, line));
         }
     }
 
     // 解析头部行
-    if let Some(header) = header_line {
-        parse_header_line(header, &mut pattern)?;
+    if let Some((line_number, header)) = header_line {
+        parse_header_line(header, line_number, &mut pattern)?;
     } else {
         return Err(RleError::InvalidFormat(
             "Missing header line (x = ..., y = ...)".to_string(),
@@ -238,15 +428,55 @@ pub fn import_from_rle_string(rle_data: &str) -> Result<RlePattern, RleError> {
     }
 
     // 解析图案数据
-    let pattern_data = pattern_lines.join("");
-    parse_pattern_data(&pattern_data, &mut pattern)?;
+    let warnings = parse_pattern_data(&pattern_lines, &mut pattern, mode)?;
 
-    Ok(pattern)
+    Ok((pattern, warnings))
 }
 
+/// Golly 扩展 RLE 的注释前缀，记录位置和代数，如 `#CXRLE Pos=-5,3 Gen=120`
+const CXRLE_PREFIX: &str = "#CXRLE";
+
 /// 解析注释行
-fn parse_comment_line(line: &str, pattern: &mut RlePattern) {
-    if let Some(rest) = line.strip_prefix("#N ") {
+///
+/// 除名称、作者和注释外，还支持 `#CXRLE`、`#P`/`#R` 左上角坐标以及旧式的 `#r 存活/出生` 规则行
+/// （头部中的 `rule =` 优先）
+fn parse_comment_line(
+    line: &str,
+    line_number: usize,
+    pattern: &mut RlePattern,
+) -> Result<(), RleError> {
+    let invalid = |message: String| RleError::InvalidHeader {
+        line: line_number,
+        message,
+    };
+
+    if let Some(rest) = line.strip_prefix(CXRLE_PREFIX) {
+        for field in rest.split_whitespace() {
+            if let Some(value) = field.strip_prefix("Pos=") {
+                let position = value
+                    .split_once(',')
+                    .and_then(|(x, y)| Some((x.parse().ok()?, y.parse().ok()?)))
+                    .ok_or_else(|| invalid(format!("Invalid position: {}", value)))?;
+                pattern.position = Some(position);
+            } else if let Some(value) = field.strip_prefix("Gen=") {
+                pattern.generation = value
+                    .parse()
+                    .map_err(|_| invalid(format!("Invalid generation: {}", value)))?;
+            }
+        }
+    } else if let Some(rest) = line.strip_prefix("#P").or_else(|| line.strip_prefix("#R")) {
+        let mut parts = rest.split_whitespace().map(str::parse::<i64>);
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(Ok(x)), Some(Ok(y)), None) => pattern.position = Some((x, y)),
+            _ => return Err(invalid(format!("Invalid coordinates: {}", rest.trim()))),
+        }
+    } else if let Some(rest) = line.strip_prefix("#r ") {
+        // 旧式规则行使用 存活/出生 记法，转换为 B/S 记法保存
+        pattern.rule = match Rule::parse(rest) {
+            Ok(rule) => rule.to_string(),
+            Err(_) => rest.trim().to_string(),
+        };
+    } else if let Some(rest) = line.strip_prefix("#N ") {
         pattern.name = rest.to_string();
     } else if let Some(rest) = line.strip_prefix("#C ") {
         if pattern.comment.is_empty() {
@@ -258,30 +488,46 @@ fn parse_comment_line(line: &str, pattern: &mut RlePattern) {
     } else if let Some(rest) = line.strip_prefix("#O ") {
         pattern.author = rest.to_string();
     }
+    Ok(())
 }
 
 /// 解析头部行
-fn parse_header_line(line: &str, pattern: &mut RlePattern) -> Result<(), RleError> {
+fn parse_header_line(
+    line: &str,
+    line_number: usize,
+    pattern: &mut RlePattern,
+) -> Result<(), RleError> {
+    let invalid = |message: String| RleError::InvalidHeader {
+        line: line_number,
+        message,
+    };
+
     // 移除空格并解析 "x=width,y=height,rule=rule" 格式
     let cleaned = line.replace(' ', "");
-    let parts: Vec<&str> = cleaned.split(',').collect();
 
-    for part in parts {
+    // 规则字段可能带有含逗号的拓扑后缀（如 rule=B3/S23:T60,40），因此单独截取
+    let (fields, rule) = match cleaned.find("rule=") {
+        Some(index) => (&cleaned[..index], Some(&cleaned[index + "rule=".len()..])),
+        None => (cleaned.as_str(), None),
+    };
+    if let Some(rule) = rule {
+        pattern.rule = rule.to_string();
+    }
+
+    for part in fields.split(',') {
         if let Some(value) = part.strip_prefix("x=") {
             pattern.width = value
                 .parse()
-                .map_err(|_| RleError::ParseError(format!("Invalid width: {}", value)))?;
+                .map_err(|_| invalid(format!("Invalid width: {}", value)))?;
         } else if let Some(value) = part.strip_prefix("y=") {
             pattern.height = value
                 .parse()
-                .map_err(|_| RleError::ParseError(format!("Invalid height: {}", value)))?;
-        } else if let Some(value) = part.strip_prefix("rule=") {
-            pattern.rule = value.to_string();
+                .map_err(|_| invalid(format!("Invalid height: {}", value)))?;
         }
     }
 
     if pattern.width == 0 || pattern.height == 0 {
-        return Err(RleError::InvalidFormat(
+        return Err(invalid(
             "Width and height must be greater than 0".to_string(),
         ));
     }
@@ -292,96 +538,160 @@ fn parse_header_line(line: &str, pattern: &mut RlePattern) -> Result<(), RleErro
     Ok(())
 }
 
-/// 解析图案数据
-fn parse_pattern_data(data: &str, pattern: &mut RlePattern) -> Result<(), RleError> {
-    // 移除结束符号!
-    let data = data.trim_end_matches('!').trim();
-
-    let mut x = 0;
-    let mut y = 0;
-    let mut chars = data.chars().peekable();
-
-    while let Some(ch) = chars.next() {
-        match ch {
-            '0'..='9' => {
-                // 读取数字
-                let mut num_str = ch.to_string();
-                while let Some(&next_ch) = chars.peek() {
-                    if next_ch.is_ascii_digit() {
-                        num_str.push(chars.next().unwrap());
-                    } else {
-                        break;
-                    }
-                }
+/// 多状态 RLE 的状态数：`A`..`X` 为状态 1..24，前缀 `p`..`y` 各加 24（`pA` 为 25）
+const STATES_PER_PREFIX: u32 = 24;
 
-                let count: usize = num_str
-                    .parse()
-                    .map_err(|_| RleError::ParseError(format!("Invalid number: {}", num_str)))?;
-
-                // 读取下一个字符来确定类型
-                if let Some(next_ch) = chars.next() {
-                    match next_ch {
-                        'b' => {
-                            // 死细胞
-                            x += count;
-                        }
-                        'o' => {
-                            // 活细胞
-                            for _ in 0..count {
-                                if y < pattern.height && x < pattern.width {
-                                    pattern.data[y][x] = true;
-                                }
-                                x += 1;
-                            }
-                        }
-                        '$' => {
-                            // 换行
-                            y += count;
-                            x = 0;
-                        }
-                        _ => {
-                            return Err(RleError::ParseError(format!(
-                                "Invalid character after number: {}",
-                                next_ch
-                            )));
-                        }
-                    }
-                } else {
-                    return Err(RleError::ParseError(
-                        "Number not followed by character".to_string(),
-                    ));
+/// 多状态 RLE 的最大状态（`yO`），`yP`..`yX` 无效
+const MAX_CELL_STATE: u32 = 255;
+
+/// 两状态引擎中多状态细胞是否存活
+///
+/// 与 Golly 的 LifeHistory 等规则相同，奇数状态为活细胞，偶数状态为死细胞（或历史痕迹）
+fn is_alive_state(state: u32) -> bool {
+    state % 2 == 1
+}
+
+/// 取出正在读取的游程长度，没有长度时为 1
+///
+/// # 返回值
+/// 返回游程开始处的行号、列号（没有长度时使用 `start`）和长度
+fn take_run_count(
+    run: &mut Option<(String, usize, usize)>,
+    start: (usize, usize),
+) -> Result<(usize, usize, usize), RleError> {
+    match run.take() {
+        Some((digits, line, column)) => match digits.parse::<usize>() {
+            Ok(count) => Ok((line, column, count)),
+            Err(_) => Err(RleError::InvalidRunCount {
+                line,
+                column,
+                count: digits,
+            }),
+        },
+        None => Ok((start.0, start.1, 1)),
+    }
+}
+
+/// 解析图案数据，遇到 `!` 时结束
+///
+/// 除 `b`、`o` 外也接受多状态字母：`.` 为状态 0，`A`..`X` 和 `pA`..`yO` 为状态 1..255，
+/// 多状态细胞按 `is_alive_state` 映射为存活或死亡，并记录一条警告。
+/// 游程长度可以与后面的字母或 `$` 跨行；位置按游程开始处（长度的第一个数字）报告
+fn parse_pattern_data(
+    lines: &[(usize, &str)],
+    pattern: &mut RlePattern,
+    mode: ParseMode,
+) -> Result<Vec<RleWarning>, RleError> {
+    let mut warnings = Vec::new();
+    let mut x = 0usize;
+    let mut y = 0usize;
+    // 正在读取的游程长度，以及它开始的行号和列号
+    let mut run: Option<(String, usize, usize)> = None;
+    // 多状态前缀字母（p..y）及其位置，后面必须紧跟 A..X
+    let mut prefix: Option<(char, usize, usize)> = None;
+    let mut multi_state = false;
+
+    let chars = lines.iter().flat_map(|&(line, text)| {
+        text.chars()
+            .enumerate()
+            .map(move |(index, ch)| (line, index + 1, ch))
+    });
+    for (line, column, ch) in chars {
+        let pending = prefix.take();
+        let start = pending.map_or((line, column), |(_, line, column)| (line, column));
+        let state = match (pending, ch) {
+            (None, '0'..='9') => {
+                match &mut run {
+                    Some((digits, _, _)) => digits.push(ch),
+                    None => run = Some((ch.to_string(), line, column)),
                 }
+                continue;
             }
-            'b' => {
-                // 单个死细胞
-                x += 1;
+            (None, 'p'..='y') => {
+                prefix = Some((ch, line, column));
+                continue;
             }
-            'o' => {
-                // 单个活细胞
-                if y < pattern.height && x < pattern.width {
-                    pattern.data[y][x] = true;
-                }
-                x += 1;
-            }
-            '$' => {
+            (None, '$') => {
                 // 换行
-                y += 1;
+                let (_, _, count) = take_run_count(&mut run, start)?;
+                y = y.saturating_add(count);
                 x = 0;
+                continue;
             }
-            '!' => {
-                // 结束符号
-                break;
-            }
-            ' ' | '\t' | '\n' | '\r' => {
+            (None, '!') => break,
+            (None, ' ' | '\t' | '\r') => {
                 // 忽略空白字符
+                continue;
+            }
+            (None, 'b' | '.') => 0,
+            (None, 'o') => 1,
+            (_, 'A'..='X') => {
+                let base = pending.map_or(0, |(prefix, _, _)| prefix as u32 - 'p' as u32 + 1);
+                let state = base * STATES_PER_PREFIX + (ch as u32 - 'A' as u32 + 1);
+                if state > MAX_CELL_STATE {
+                    return Err(RleError::InvalidCharacter {
+                        line,
+                        column,
+                        found: ch,
+                    });
+                }
+                state
             }
             _ => {
-                return Err(RleError::ParseError(format!("Invalid character: {}", ch)));
+                return Err(RleError::InvalidCharacter {
+                    line,
+                    column,
+                    found: ch,
+                });
+            }
+        };
+        let (line, column, count) = take_run_count(&mut run, start)?;
+
+        if state > 1 && !multi_state {
+            multi_state = true;
+            warnings.push(RleWarning {
+                line,
+                column,
+                message: "multi-state cells found, odd states are loaded as alive".to_string(),
+            });
+        }
+
+        // 一段相同状态的细胞
+        let end = x.saturating_add(count);
+        if end > pattern.width || y >= pattern.height {
+            match mode {
+                ParseMode::Strict => {
+                    return Err(RleError::OutOfBounds {
+                        line,
+                        column,
+                        width: pattern.width,
+                        height: pattern.height,
+                    })
+                }
+                ParseMode::Lenient => warnings.push(RleWarning {
+                    line,
+                    column,
+                    message: format!(
+                        "run of {} cells at ({}, {}) extends past the declared size {} x {}, extra cells dropped",
+                        count, x, y, pattern.width, pattern.height
+                    ),
+                }),
+            }
+        }
+        if is_alive_state(state) && y < pattern.height {
+            for cell in &mut pattern.data[y][x.min(pattern.width)..end.min(pattern.width)] {
+                *cell = true;
             }
         }
+        x = end;
     }
 
-    Ok(())
+    match (run, prefix) {
+        (Some((_, line, column)), _) | (None, Some((_, line, column))) => {
+            Err(RleError::MissingRunTag { line, column })
+        }
+        (None, None) => Ok(warnings),
+    }
 }
 
 /// 从RLE文件加载图案
@@ -390,9 +700,469 @@ pub fn load_rle_file<P: AsRef<Path>>(path: P) -> Result<RlePattern, RleError> {
     import_from_rle_string(&content)
 }
 
+/// 支持的图案文件格式
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PatternFormat {
+    /// Run Length Encoded（`.rle`）
+    Rle,
+    /// LifeWiki 纯文本格式（`.cells`）：`!` 开头的注释行，`.` 和 `O` 组成的细胞行
+    Plaintext,
+    /// Life 1.05（`.lif`）：`#P x y` 开头的细胞块，`.` 和 `*` 组成的细胞行
+    Life105,
+    /// Life 1.06（`.life`）：每行一个活细胞的坐标
+    Life106,
+    /// Golly 宏细胞格式（`.mc`）：四叉树节点，适合巨大的图案，见 `macrocell` 模块
+    Macrocell,
+}
+
+impl PatternFormat {
+    /// 所有格式，用于文件对话框
+    pub const ALL: [PatternFormat; 5] = [
+        PatternFormat::Rle,
+        PatternFormat::Plaintext,
+        PatternFormat::Life105,
+        PatternFormat::Life106,
+        PatternFormat::Macrocell,
+    ];
+
+    /// 格式名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            PatternFormat::Rle => "RLE",
+            PatternFormat::Plaintext => "Plaintext",
+            PatternFormat::Life105 => "Life 1.05",
+            PatternFormat::Life106 => "Life 1.06",
+            PatternFormat::Macrocell => "Macrocell",
+        }
+    }
+
+    /// 该格式使用的文件扩展名
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            PatternFormat::Rle => &["rle"],
+            PatternFormat::Plaintext => &["cells"],
+            PatternFormat::Life105 => &["lif"],
+            PatternFormat::Life106 => &["life"],
+            PatternFormat::Macrocell => &["mc"],
+        }
+    }
+
+    /// 根据文件扩展名判断格式（大小写不敏感）
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|format| format.extensions().contains(&extension.as_str()))
+    }
+
+    /// 根据文件内容判断格式
+    ///
+    /// `[M2]` 开头的是宏细胞格式，`#Life 1.05` 或 `#Life 1.06` 开头的是对应的 Life 格式；
+    /// 否则跳过 RLE 的 `#` 注释行，看第一个有内容的行：
+    /// `!` 开头或只由 `.`、`O`、`*` 组成时为纯文本格式，其余按 RLE 处理
+    pub fn detect(content: &str) -> Self {
+        for line in content.lines().map(str::trim) {
+            if line.starts_with(macrocell::MACROCELL_HEADER) {
+                return PatternFormat::Macrocell;
+            }
+            if line.starts_with(LIFE_105_HEADER) {
+                return PatternFormat::Life105;
+            }
+            if line.starts_with(LIFE_106_HEADER) {
+                return PatternFormat::Life106;
+            }
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line.starts_with('!') || line.chars().all(|ch| matches!(ch, '.' | 'O' | '*')) {
+                return PatternFormat::Plaintext;
+            }
+            return PatternFormat::Rle;
+        }
+        PatternFormat::Rle
+    }
+}
+
+/// 纯文本格式中记录规则的注释前缀（非标准，Golly 等程序会忽略）
+const CELLS_RULE_PREFIX: &str = "Rule:";
+
+/// 从纯文本（.cells）格式字符串导入图案
+///
+/// `!Name:` 对应名称，`!Author:` 对应作者，其余 `!` 注释行合并为注释。
+/// 行的长度可以不同，宽度取最长的一行
+pub fn import_from_cells_string(cells_data: &str) -> Result<RlePattern, RleError> {
+    let mut pattern = RlePattern::new("Imported Pattern".to_string(), 0, 0);
+    let mut comments = Vec::new();
+    let mut rows: Vec<Vec<bool>> = Vec::new();
+
+    for (index, line) in cells_data.lines().enumerate() {
+        let line = line.trim_end();
+        if let Some(comment) = line.strip_prefix('!') {
+            let comment = comment.trim();
+            if let Some(name) = comment.strip_prefix("Name:") {
+                pattern.name = name.trim().to_string();
+            } else if let Some(author) = comment.strip_prefix("Author:") {
+                pattern.author = author.trim().to_string();
+            } else if let Some(rule) = comment.strip_prefix(CELLS_RULE_PREFIX) {
+                pattern.rule = rule.trim().to_string();
+            } else {
+                comments.push(comment);
+            }
+            continue;
+        }
+
+        let row = line
+            .chars()
+            .enumerate()
+            .map(|(column, ch)| match ch {
+                '.' => Ok(false),
+                'O' | '*' => Ok(true),
+                _ => Err(RleError::InvalidCharacter {
+                    line: index + 1,
+                    column: column + 1,
+                    found: ch,
+                }),
+            })
+            .collect::<Result<Vec<bool>, RleError>>()?;
+        rows.push(row);
+    }
+
+    // 去掉末尾的空行，中间的空行表示一整行死细胞
+    while rows.last().is_some_and(|row| row.is_empty()) {
+        rows.pop();
+    }
+    if rows.is_empty() {
+        return Err(RleError::InvalidFormat(
+            "Plaintext pattern has no cell rows".to_string(),
+        ));
+    }
+
+    pattern.comment = comments.join("\n");
+    pattern.width = rows.iter().map(Vec::len).max().unwrap_or(0).max(1);
+    pattern.height = rows.len();
+    for row in &mut rows {
+        row.resize(pattern.width, false);
+    }
+    pattern.data = rows;
+    Ok(pattern)
+}
+
+/// 将图案导出为纯文本（.cells）格式字符串
+///
+/// 规则不是 B3/S23 时额外写入一行 `!Rule:` 注释，以便读回时恢复
+pub fn export_to_cells_string(pattern: &RlePattern) -> String {
+    let mut result = String::new();
+    if !pattern.name.is_empty() {
+        result.push_str(&format!("!Name: {}\n", pattern.name));
+    }
+    if !pattern.author.is_empty() {
+        result.push_str(&format!("!Author: {}\n", pattern.author));
+    }
+    if pattern.rule != Rule::conway().to_string() {
+        result.push_str(&format!("!{} {}\n", CELLS_RULE_PREFIX, pattern.rule));
+    }
+    for line in pattern.comment.lines() {
+        result.push_str(&format!("!{}\n", line));
+    }
+    for row in &pattern.data {
+        result.extend(row.iter().map(|&alive| if alive { 'O' } else { '.' }));
+        result.push('\n');
+    }
+    result
+}
+
+/// Life 1.05 文件的第一行
+const LIFE_105_HEADER: &str = "#Life 1.05";
+
+/// Life 1.06 文件的第一行
+const LIFE_106_HEADER: &str = "#Life 1.06";
+
+/// Life 1.05 每行最多的字符数，更宽的图案拆分为多个细胞块
+const LIFE_105_LINE_WIDTH: usize = 80;
+
+/// 由活细胞坐标（可以为负数）构造图案，坐标平移使包围盒左上角位于原点；没有活细胞时为 0x0 的空图案
+///
+/// 包围盒面积超过 [`macrocell::MAX_DENSE_CELLS`] 时返回错误，而不是分配巨大的稠密数组
+fn pattern_from_cells(
+    mut pattern: RlePattern,
+    cells: &[(i64, i64)],
+) -> Result<RlePattern, RleError> {
+    let (min_x, max_x) = match (
+        cells.iter().map(|&(x, _)| x).min(),
+        cells.iter().map(|&(x, _)| x).max(),
+    ) {
+        (Some(min), Some(max)) => (min, max),
+        _ => return Ok(pattern),
+    };
+    let min_y = cells.iter().map(|&(_, y)| y).min().unwrap_or(0);
+    let max_y = cells.iter().map(|&(_, y)| y).max().unwrap_or(0);
+
+    // 坐标相距很远时 max - min 可能溢出 i64
+    let span = |min: i64, max: i64| {
+        max.checked_sub(min)
+            .and_then(|extent| u64::try_from(extent).ok())
+            .and_then(|extent| extent.checked_add(1))
+    };
+    match (span(min_x, max_x), span(min_y, max_y)) {
+        (Some(width), Some(height))
+            if width.saturating_mul(height) <= macrocell::MAX_DENSE_CELLS =>
+        {
+            pattern.width = width as usize;
+            pattern.height = height as usize;
+        }
+        _ => {
+            return Err(RleError::InvalidFormat(format!(
+                "Pattern spans ({}, {}) to ({}, {}), too large to load into a grid",
+                min_x, min_y, max_x, max_y
+            )))
+        }
+    }
+    pattern.data = vec![vec![false; pattern.width]; pattern.height];
+    for &(x, y) in cells {
+        pattern.data[(y - min_y) as usize][(x - min_x) as usize] = true;
+    }
+    Ok(pattern)
+}
+
+/// 解析 Life 格式的 `#D` 描述行：`Name:` 和 `Author:` 对应名称和作者，其余合并为注释
+fn parse_life_description(text: &str, pattern: &mut RlePattern, comments: &mut Vec<String>) {
+    let text = text.trim();
+    if let Some(name) = text.strip_prefix("Name:") {
+        pattern.name = name.trim().to_string();
+    } else if let Some(author) = text.strip_prefix("Author:") {
+        pattern.author = author.trim().to_string();
+    } else {
+        comments.push(text.to_string());
+    }
+}
+
+/// 生成 Life 格式的 `#D` 描述行
+fn life_description(pattern: &RlePattern) -> String {
+    let mut result = String::new();
+    if !pattern.name.is_empty() {
+        result.push_str(&format!("#D Name: {}\n", pattern.name));
+    }
+    if !pattern.author.is_empty() {
+        result.push_str(&format!("#D Author: {}\n", pattern.author));
+    }
+    for line in pattern.comment.lines() {
+        result.push_str(&format!("#D {}\n", line));
+    }
+    result
+}
+
+/// 解析一对整数坐标，如 `-3 12`
+fn parse_coordinates(text: &str, line_number: usize) -> Result<(i64, i64), RleError> {
+    let mut parts = text.split_whitespace().map(str::parse::<i64>);
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(Ok(x)), Some(Ok(y)), None) => Ok((x, y)),
+        _ => Err(RleError::InvalidHeader {
+            line: line_number,
+            message: format!("Invalid coordinates: {}", text.trim()),
+        }),
+    }
+}
+
+/// 从 Life 1.05 格式字符串导入图案
+///
+/// 每个 `#P x y` 块的细胞行从 (x, y) 开始（坐标可以为负数），整个图案平移到网格内。
+/// `#N` 表示标准规则，`#R 存活/出生` 指定其他规则，`#D` 描述行保存为注释
+pub fn import_from_life105_string(life_data: &str) -> Result<RlePattern, RleError> {
+    let mut pattern = RlePattern::new("Imported Pattern".to_string(), 0, 0);
+    let mut comments = Vec::new();
+    let mut cells = Vec::new();
+    // 当前细胞块的左上角和下一行的行号
+    let mut block: Option<(i64, i64)> = None;
+
+    for (index, raw_line) in life_data.lines().enumerate() {
+        let line_number = index + 1;
+        let line = raw_line.trim();
+        // 行首缩进的字符数，报告错误时列号从原始行算起
+        let indent = raw_line.chars().count() - raw_line.trim_start().chars().count();
+        if line.is_empty() || line.starts_with(LIFE_105_HEADER) {
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("#P") {
+            block = Some(parse_coordinates(rest, line_number)?);
+        } else if let Some(rest) = line.strip_prefix("#D") {
+            parse_life_description(rest, &mut pattern, &mut comments);
+        } else if line.starts_with("#N") {
+            pattern.rule = Rule::conway().to_string();
+        } else if let Some(rest) = line.strip_prefix("#R") {
+            let rule = Rule::parse(rest).map_err(|e| RleError::InvalidHeader {
+                line: line_number,
+                message: format!("Invalid rule: {}", e),
+            })?;
+            pattern.rule = rule.to_string();
+        } else if line.starts_with('#') {
+            // 忽略其他未知的 # 行
+        } else {
+            let (x0, y) = block.as_mut().ok_or_else(|| {
+                RleError::InvalidFormat(format!(
+                    "Cell row outside a #P block at line {}",
+                    line_number
+                ))
+            })?;
+            for (column, ch) in line.chars().enumerate() {
+                match ch {
+                    '.' => {}
+                    '*' | 'O' => cells.push((*x0 + column as i64, *y)),
+                    _ => {
+                        return Err(RleError::InvalidCharacter {
+                            line: line_number,
+                            column: indent + column + 1,
+                            found: ch,
+                        })
+                    }
+                }
+            }
+            *y += 1;
+        }
+    }
+
+    pattern.comment = comments.join("\n");
+    pattern_from_cells(pattern, &cells)
+}
+
+/// 将图案导出为 Life 1.05 格式字符串
+///
+/// 图案按 80 列拆分为竖条，每个含有活细胞的竖条裁掉上下的空行后写成一个 `#P` 块。
+/// 规则无法解析时返回错误，而不是写成标准规则
+pub fn export_to_life105_string(pattern: &RlePattern) -> Result<String, RleError> {
+    let mut result = format!("{}\n", LIFE_105_HEADER);
+    result.push_str(&life_description(pattern));
+    let rule = pattern.parse_rule()?;
+    if rule == Rule::conway() {
+        result.push_str("#N\n");
+    } else {
+        // 传统的 存活/出生 记法
+        let notation = rule.to_string();
+        let (birth, survival) = notation.split_once('/').unwrap_or((&notation, ""));
+        result.push_str(&format!("#R {}/{}\n", &survival[1..], &birth[1..]));
+    }
+
+    for x0 in (0..pattern.width).step_by(LIFE_105_LINE_WIDTH) {
+        let x1 = (x0 + LIFE_105_LINE_WIDTH).min(pattern.width);
+        let live_rows: Vec<usize> = (0..pattern.height)
+            .filter(|&y| pattern.data[y][x0..x1].contains(&true))
+            .collect();
+        let (first, last) = match (live_rows.first(), live_rows.last()) {
+            (Some(&first), Some(&last)) => (first, last),
+            _ => continue,
+        };
+        result.push_str(&format!("#P {} {}\n", x0, first));
+        for row in &pattern.data[first..=last] {
+            let cells = &row[x0..x1];
+            // 空行写成一个 `.`，避免被读取时当作空白行跳过
+            let end = cells.iter().rposition(|&alive| alive).map_or(1, |i| i + 1);
+            result.extend(
+                cells[..end]
+                    .iter()
+                    .map(|&alive| if alive { '*' } else { '.' }),
+            );
+            result.push('\n');
+        }
+    }
+    Ok(result)
+}
+
+/// 从 Life 1.06 格式字符串导入图案：每行一个活细胞的 `x y` 坐标（可以为负数）
+pub fn import_from_life106_string(life_data: &str) -> Result<RlePattern, RleError> {
+    let mut pattern = RlePattern::new("Imported Pattern".to_string(), 0, 0);
+    let mut comments = Vec::new();
+    let mut cells = Vec::new();
+
+    for (index, line) in life_data.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(LIFE_106_HEADER) {
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("#D") {
+            parse_life_description(rest, &mut pattern, &mut comments);
+        } else if !line.starts_with('#') {
+            cells.push(parse_coordinates(line, index + 1)?);
+        }
+    }
+
+    pattern.comment = comments.join("\n");
+    pattern_from_cells(pattern, &cells)
+}
+
+/// 将图案导出为 Life 1.06 格式字符串，坐标以图案左上角为原点
+///
+/// Life 1.06 没有规则字段，规则不会被保存
+pub fn export_to_life106_string(pattern: &RlePattern) -> String {
+    let mut result = format!("{}\n", LIFE_106_HEADER);
+    result.push_str(&life_description(pattern));
+    for (y, row) in pattern.data.iter().enumerate() {
+        for (x, &alive) in row.iter().enumerate() {
+            if alive {
+                result.push_str(&format!("{} {}\n", x, y));
+            }
+        }
+    }
+    result
+}
+
+/// 按内容自动识别格式并导入图案
+pub fn import_pattern_string(content: &str) -> Result<RlePattern, RleError> {
+    match PatternFormat::detect(content) {
+        PatternFormat::Rle => import_from_rle_string(content),
+        PatternFormat::Plaintext => import_from_cells_string(content),
+        PatternFormat::Life105 => import_from_life105_string(content),
+        PatternFormat::Life106 => import_from_life106_string(content),
+        PatternFormat::Macrocell => macrocell::import_pattern(content),
+    }
+}
+
+/// 按内容自动识别格式，并按指定模式导入图案
+///
+/// # 返回值
+/// 返回图案以及解析时的警告（目前只有 RLE 会产生警告）
+pub fn import_pattern_string_with_mode(
+    content: &str,
+    mode: ParseMode,
+) -> Result<(RlePattern, Vec<RleWarning>), RleError> {
+    match PatternFormat::detect(content) {
+        PatternFormat::Rle => import_from_rle_string_with_mode(content, mode),
+        _ => import_pattern_string(content).map(|pattern| (pattern, Vec::new())),
+    }
+}
+
+/// 将图案导出为指定格式的字符串
+///
+/// 目前只有 Life 1.05 会在规则无法表示时返回错误
+pub fn export_pattern_string(
+    pattern: &RlePattern,
+    format: PatternFormat,
+) -> Result<String, RleError> {
+    Ok(match format {
+        PatternFormat::Rle => export_to_rle_string(pattern),
+        PatternFormat::Plaintext => export_to_cells_string(pattern),
+        PatternFormat::Life105 => export_to_life105_string(pattern)?,
+        PatternFormat::Life106 => export_to_life106_string(pattern),
+        PatternFormat::Macrocell => macrocell::export_pattern(pattern),
+    })
+}
+
+/// 从文件加载图案，按内容自动识别格式（与扩展名无关）
+pub fn load_pattern_file<P: AsRef<Path>>(path: P) -> Result<RlePattern, RleError> {
+    let content = fs::read_to_string(path)?;
+    import_pattern_string(&content)
+}
+
+/// 保存图案到文件，格式由扩展名决定，无法识别的扩展名使用 RLE
+pub fn save_pattern_file<P: AsRef<Path>>(path: P, pattern: &RlePattern) -> Result<(), RleError> {
+    let path = path.as_ref();
+    let format = PatternFormat::from_path(path).unwrap_or(PatternFormat::Rle);
+    fs::write(path, export_pattern_string(pattern, format)?)?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::random::{SoupSettings, Symmetry};
     use tempfile::NamedTempFile;
 
     #[test]
@@ -486,4 +1256,445 @@ mod tests {
         let valid_pattern = RlePattern::new("Valid".to_string(), 3, 3);
         assert!(valid_pattern.to_grid().is_ok());
     }
+
+    #[test]
+    fn test_rule_round_trip() {
+        let mut grid = Grid::new(3, 3);
+        grid.set_rule(Rule::parse("B36/S23").unwrap());
+        grid.set_cell(1, 1, CellState::Alive);
+
+        let rle_string = export_to_rle_string(&RlePattern::from_grid(&grid, "HL".to_string()));
+        assert!(rle_string.contains("rule = B36/S23"));
+
+        let imported = import_from_rle_string(&rle_string).unwrap();
+        let loaded = imported.to_grid().unwrap();
+        assert_eq!(*loaded.rule(), Rule::parse("B36/S23").unwrap());
+
+        // 无法识别的规则应当报错
+        let mut invalid = imported.clone();
+        invalid.rule = "not a rule".to_string();
+        assert!(invalid.to_grid().is_err());
+    }
+
+    #[test]
+    fn test_topology_round_trip() {
+        let mut grid = Grid::new(6, 4);
+        grid.set_topology(Topology::Torus).unwrap();
+        grid.set_cell(0, 0, CellState::Alive);
+
+        let rle_string = export_to_rle_string(&RlePattern::from_grid(&grid, "Torus".to_string()));
+        assert!(rle_string.contains("rule = B3/S23:T6,4"));
+
+        let imported = import_from_rle_string(&rle_string).unwrap();
+        assert_eq!(imported.parse_rule().unwrap(), Rule::conway());
+        assert_eq!(imported.parse_topology().unwrap(), (Topology::Torus, Some((6, 4))));
+
+        // 有界网格保存时不附加后缀
+        let bounded = RlePattern::from_grid(&Grid::new(3, 3), "Plain".to_string());
+        assert_eq!(bounded.rule, "B3/S23");
+    }
+
+    #[test]
+    fn test_topology_suffix_sets_grid_size() {
+        let rle = "x = 3, y = 1, rule = B3/S23:T10,8\n3o!";
+        let grid = import_from_rle_string(rle).unwrap().to_grid().unwrap();
+        assert_eq!(grid.width(), 10);
+        assert_eq!(grid.height(), 8);
+        assert_eq!(grid.topology(), Topology::Torus);
+        assert_eq!(grid.count_alive_cells(), 3);
+
+        // 声明的尺寸放不下图案，或球面网格不是正方形时报错
+        let truncated = import_from_rle_string("x = 10, y = 1, rule = B3/S23:T4,4\n10o!").unwrap();
+        assert!(truncated.to_grid().is_err());
+        let sphere = import_from_rle_string("x = 20, y = 3, rule = B3/S23:S\n20o!").unwrap();
+        assert!(sphere.to_grid().is_err());
+
+        // 声明的尺寸过大时报错，而不是尝试分配巨大的网格
+        let huge =
+            import_from_rle_string("x = 1, y = 1, rule = B3/S23:T4000000000,4000000000\no!")
+                .unwrap();
+        assert!(matches!(huge.to_grid(), Err(RleError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_rle_error_positions() {
+        let error = import_from_rle_string("#N Bad\nx = 3, y = 2\n3o$\n o2z!").unwrap_err();
+        assert!(matches!(
+            error,
+            RleError::InvalidCharacter {
+                line: 4,
+                column: 4,
+                found: 'z'
+            }
+        ));
+        assert_eq!(
+            error.to_string(),
+            "Invalid character 'z' at line 4, column 4"
+        );
+
+        // 游程长度可以跨行，位置按长度的第一个数字报告
+        assert!(import_from_rle_string("x = 3, y = 1\n  1\n2o!").is_ok());
+        assert!(matches!(
+            import_from_rle_string("x = 3, y = 1\nbo2!"),
+            Err(RleError::MissingRunTag { line: 2, column: 3 })
+        ));
+        assert!(matches!(
+            import_from_rle_string("x = 3, y = 1\n99999999999999999999999o!"),
+            Err(RleError::InvalidRunCount {
+                line: 2,
+                column: 1,
+                ..
+            })
+        ));
+        assert!(matches!(
+            import_from_rle_string("#C\nx = three, y = 1\n3o!"),
+            Err(RleError::InvalidHeader { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_rle_strict_and_lenient_modes() {
+        let rle = "x = 2, y = 2\n2o$\nb3o$\n2o!";
+
+        let (pattern, warnings) =
+            import_from_rle_string_with_mode(rle, ParseMode::Lenient).unwrap();
+        assert_eq!(pattern.data, vec![vec![true, true], vec![false, true]]);
+        assert_eq!(warnings.len(), 2);
+        assert_eq!((warnings[0].line, warnings[0].column), (3, 2));
+        assert_eq!((warnings[1].line, warnings[1].column), (4, 1));
+
+        match import_from_rle_string_with_mode(rle, ParseMode::Strict) {
+            Err(RleError::OutOfBounds {
+                line,
+                column,
+                width,
+                height,
+            }) => assert_eq!((line, column, width, height), (3, 2, 2, 2)),
+            other => panic!("{:?}", other.map(|(pattern, _)| pattern.name)),
+        }
+
+        // 没有越界时两种模式结果相同
+        let exact = "x = 2, y = 2\n2o$bo!";
+        assert!(import_from_rle_string_with_mode(exact, ParseMode::Strict).is_ok());
+        assert!(import_from_rle_string_with_mode(exact, ParseMode::Lenient)
+            .unwrap()
+            .1
+            .is_empty());
+    }
+
+    #[test]
+    fn test_multi_state_rle() {
+        // LifeHistory 风格：奇数状态为活细胞，状态 2 等为死细胞
+        let rle = "x = 4, y = 2, rule = B3/S23\n.A\nBC$pA2.pB!";
+        let (pattern, warnings) = import_from_rle_string_with_mode(rle, ParseMode::Strict).unwrap();
+        assert_eq!(
+            pattern.data,
+            vec![
+                vec![false, true, false, true],
+                vec![true, false, false, false]
+            ]
+        );
+        assert_eq!(warnings.len(), 1);
+        assert_eq!((warnings[0].line, warnings[0].column), (3, 1));
+
+        // yO 是最大的状态 255，yP 之后无效
+        assert!(import_from_rle_string("x = 1, y = 1\nyO!").is_ok());
+        assert!(matches!(
+            import_from_rle_string("x = 1, y = 1\nyP!"),
+            Err(RleError::InvalidCharacter {
+                line: 2,
+                column: 2,
+                found: 'P'
+            })
+        ));
+        assert!(matches!(
+            import_from_rle_string("x = 2, y = 1\npZ!"),
+            Err(RleError::InvalidCharacter { found: 'Z', .. })
+        ));
+        assert!(matches!(
+            import_from_rle_string("x = 2, y = 1\no3p!"),
+            Err(RleError::InvalidCharacter { found: '!', .. })
+        ));
+    }
+
+    #[test]
+    fn test_position_generation_and_old_rule_line() {
+        let rle = "#CXRLE Pos=-5,3 Gen=120\n#N Pair\nx = 2, y = 1, rule = B3/S23\n2o!";
+        let pattern = import_from_rle_string(rle).unwrap();
+        assert_eq!(pattern.position, Some((-5, 3)));
+        assert_eq!(pattern.generation, 120);
+        assert_eq!(pattern.name, "Pair");
+
+        let exported = export_to_rle_string(&pattern);
+        assert!(exported.starts_with("#CXRLE Pos=-5,3 Gen=120\n"));
+        let imported = import_from_rle_string(&exported).unwrap();
+        assert_eq!(
+            (imported.position, imported.generation),
+            (Some((-5, 3)), 120)
+        );
+
+        for (line, position) in [("#P 4 -2", (4, -2)), ("#R -1 7", (-1, 7))] {
+            let rle = format!("{}\nx = 1, y = 1\no!", line);
+            assert_eq!(
+                import_from_rle_string(&rle).unwrap().position,
+                Some(position)
+            );
+        }
+        assert!(import_from_rle_string("#P 4\nx = 1, y = 1\no!").is_err());
+
+        // 旧式 #r 行使用 存活/出生 记法；头部中的规则优先
+        let old = import_from_rle_string("#r 23/36\nx = 1, y = 1\no!").unwrap();
+        assert_eq!(old.parse_rule().unwrap(), Rule::parse("B36/S23").unwrap());
+        let both = import_from_rle_string("#r 23/36\nx = 1, y = 1, rule = B3/S23\no!").unwrap();
+        assert_eq!(both.parse_rule().unwrap(), Rule::conway());
+    }
+
+    #[test]
+    fn test_cells_import() {
+        let cells =
+            "!Name: Glider\n!Author: Richard K. Guy\n!The smallest spaceship.\n.O\n..O\nOOO\n";
+        let pattern = import_from_cells_string(cells).unwrap();
+        assert_eq!(pattern.name, "Glider");
+        assert_eq!(pattern.author, "Richard K. Guy");
+        assert_eq!(pattern.comment, "The smallest spaceship.");
+        assert_eq!((pattern.width, pattern.height), (3, 3));
+        assert_eq!(pattern.data[0], [false, true, false]);
+        assert_eq!(pattern.data[2], [true, true, true]);
+
+        // 中间的空行是一整行死细胞
+        let gap = import_from_cells_string("OO\n\nOO\n").unwrap();
+        assert_eq!((gap.width, gap.height), (2, 3));
+        assert_eq!(gap.data[1], [false, false]);
+
+        assert!(matches!(
+            import_from_cells_string("!Name: Bad\n.O\n.x\n"),
+            Err(RleError::InvalidCharacter {
+                line: 3,
+                column: 2,
+                found: 'x'
+            })
+        ));
+        assert!(import_from_cells_string("!Name: Empty\n").is_err());
+    }
+
+    #[test]
+    fn test_cells_round_trip() {
+        let mut grid = Grid::new(5, 4);
+        grid.set_rule(Rule::parse("B36/S23").unwrap());
+        grid.set_cell(1, 1, CellState::Alive);
+        grid.set_cell(4, 3, CellState::Alive);
+        let mut pattern = RlePattern::from_grid(&grid, "Two cells".to_string());
+        pattern.comment = "first\nsecond".to_string();
+
+        let cells = export_to_cells_string(&pattern);
+        assert!(
+            cells.starts_with("!Name: Two cells\n!Rule: B36/S23\n!first\n!second\n.....\n.O...\n")
+        );
+
+        let imported = import_pattern_string(&cells).unwrap();
+        assert_eq!(imported.comment, pattern.comment);
+        assert_eq!(imported.data, pattern.data);
+        assert_eq!(
+            imported.parse_rule().unwrap(),
+            Rule::parse("B36/S23").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_format_detection() {
+        assert_eq!(
+            PatternFormat::detect("!Name: Block\nOO\nOO\n"),
+            PatternFormat::Plaintext
+        );
+        assert_eq!(PatternFormat::detect("OO\nOO\n"), PatternFormat::Plaintext);
+        assert_eq!(
+            PatternFormat::detect("#N Block\nx = 2, y = 2\n2o$2o!\n"),
+            PatternFormat::Rle
+        );
+        assert_eq!(
+            PatternFormat::from_path(Path::new("glider.CELLS")),
+            Some(PatternFormat::Plaintext)
+        );
+        assert_eq!(PatternFormat::from_path(Path::new("glider.txt")), None);
+
+        // 扩展名与内容不符时按内容读取
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("block.rle");
+        std::fs::write(&path, "!Name: Block\nOO\nOO\n").unwrap();
+        assert_eq!(load_pattern_file(&path).unwrap().name, "Block");
+
+        let cells_path = dir.path().join("block.cells");
+        save_pattern_file(&cells_path, &load_pattern_file(&path).unwrap()).unwrap();
+        assert!(std::fs::read_to_string(&cells_path)
+            .unwrap()
+            .starts_with("!Name: Block"));
+
+        // 宏细胞文件经过 HashLife 转换，图案不变
+        let mc_path = dir.path().join("block.MC");
+        save_pattern_file(&mc_path, &load_pattern_file(&path).unwrap()).unwrap();
+        let block = load_pattern_file(&mc_path).unwrap();
+        assert_eq!((block.name.as_str(), block.width, block.height), ("Block", 2, 2));
+        assert!(block.data.iter().flatten().all(|&alive| alive));
+    }
+
+    #[test]
+    fn test_life105_import() {
+        let life = "#Life 1.05\n#D Glider and block\n#D second line\n#R 23/36\n\
+                    #P -1 -1\n.*\n..*\n***\n#P 4 -2\n**\n**\n";
+        assert_eq!(PatternFormat::detect(life), PatternFormat::Life105);
+        let pattern = import_pattern_string(life).unwrap();
+        assert_eq!(pattern.comment, "Glider and block\nsecond line");
+        assert_eq!(
+            pattern.parse_rule().unwrap(),
+            Rule::parse("B36/S23").unwrap()
+        );
+        // 包围盒从 (-1, -2) 到 (5, 1)
+        assert_eq!((pattern.width, pattern.height), (7, 4));
+        assert!(pattern.data[1][1] && pattern.data[2][2] && pattern.data[3][0]);
+        assert!(pattern.data[0][5] && pattern.data[1][6]);
+        assert_eq!(
+            pattern
+                .data
+                .iter()
+                .flatten()
+                .filter(|&&alive| alive)
+                .count(),
+            9
+        );
+
+        assert!(matches!(
+            import_from_life105_string("#Life 1.05\n#P 0 0\n.*x\n"),
+            Err(RleError::InvalidCharacter {
+                line: 3,
+                column: 3,
+                found: 'x'
+            })
+        ));
+        // 缩进的细胞行按原始行计算列号
+        assert!(matches!(
+            import_from_life105_string("#Life 1.05\n#P 0 0\n  *x\n"),
+            Err(RleError::InvalidCharacter { line: 3, column: 4, .. })
+        ));
+        match import_from_life105_string("#Life 1.05\n#D no block\n**\n") {
+            Err(RleError::InvalidFormat(message)) => {
+                assert!(message.contains("outside a #P block at line 3"), "{}", message)
+            }
+            other => panic!("{:?}", other),
+        }
+        for (life, expected_line) in [
+            ("#Life 1.05\n#P 0 zero\n*\n", 2),
+            ("#Life 1.05\n#R 23/9\n#P 0 0\n*\n", 2),
+        ] {
+            match import_from_life105_string(life) {
+                Err(RleError::InvalidHeader { line, .. }) => assert_eq!(line, expected_line),
+                other => panic!("{:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_life105_round_trip() {
+        // 宽于80列的图案拆分为多个 #P 块
+        let mut pattern = RlePattern::new("Wide".to_string(), 100, 5);
+        pattern.rule = "B36/S23".to_string();
+        pattern.comment = "two blocks".to_string();
+        pattern.data[0][0] = true;
+        pattern.data[4][99] = true;
+        pattern.data[2][79] = true;
+
+        let life = export_to_life105_string(&pattern).unwrap();
+        assert!(life.starts_with("#Life 1.05\n#D Name: Wide\n#D two blocks\n#R 23/36\n"));
+        assert_eq!(life.matches("#P").count(), 2);
+        assert!(life.lines().all(|line| line.len() <= 80));
+
+        let imported = import_from_life105_string(&life).unwrap();
+        assert_eq!(imported.name, "Wide");
+        // 无法解析的规则不会被写成标准规则
+        let unknown = RlePattern {
+            rule: "B3/S23/Q".to_string(),
+            ..pattern.clone()
+        };
+        assert!(export_to_life105_string(&unknown).is_err());
+        assert_eq!(imported.comment, "two blocks");
+        assert_eq!(imported.rule, "B36/S23");
+        assert_eq!(imported.data, pattern.data);
+    }
+
+    #[test]
+    fn test_life106_import_and_round_trip() {
+        let life = "#Life 1.06\n#D R-pentomino\n0 -1\n1 -1\n-1 0\n0 0\n0 1\n";
+        assert_eq!(PatternFormat::detect(life), PatternFormat::Life106);
+        let pattern = import_pattern_string(life).unwrap();
+        assert_eq!(pattern.comment, "R-pentomino");
+        assert_eq!((pattern.width, pattern.height), (3, 3));
+        assert_eq!(pattern.data[0], [false, true, true]);
+        assert_eq!(pattern.data[1], [true, true, false]);
+        assert_eq!(pattern.data[2], [false, true, false]);
+
+        let exported = export_to_life106_string(&pattern);
+        assert_eq!(
+            exported,
+            "#Life 1.06\n#D Name: Imported Pattern\n#D R-pentomino\n1 0\n2 0\n0 1\n1 1\n1 2\n"
+        );
+        assert_eq!(
+            import_from_life106_string(&exported).unwrap().data,
+            pattern.data
+        );
+
+        match import_from_life106_string("#Life 1.06\n0 0\n1 2 3\n") {
+            Err(RleError::InvalidHeader { line: 3, message }) => {
+                assert_eq!(message, "Invalid coordinates: 1 2 3")
+            }
+            other => panic!("{:?}", other),
+        }
+        // 空图案导出后可以读回
+        let empty = import_from_life106_string("#Life 1.06\n").unwrap();
+        assert_eq!((empty.width, empty.height), (0, 0));
+        let life105 = export_to_life105_string(&empty).unwrap();
+        let reloaded = import_from_life105_string(&life105).unwrap();
+        assert_eq!((reloaded.width, reloaded.height), (0, 0));
+        let life106 = export_to_life106_string(&empty);
+        assert!(import_from_life106_string(&life106).unwrap().data.is_empty());
+
+        // 相距很远的坐标不会溢出，也不会分配巨大的网格
+        for life in [
+            "#Life 1.06\n-9223372036854775808 0\n9223372036854775807 0\n",
+            "#Life 1.06\n0 0\n100000 100000\n",
+        ] {
+            assert!(matches!(
+                import_from_life106_string(life),
+                Err(RleError::InvalidFormat(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_soup_seed_in_comment() {
+        let soup = SoupSettings {
+            seed: 987654321,
+            density: 0.35,
+            symmetry: Symmetry::C2,
+        };
+        let mut grid = Grid::new(12, 12);
+        grid.randomize_region(soup.seed, soup.density, soup.symmetry, (0, 0, 12, 12));
+
+        let mut pattern = RlePattern::from_grid(&grid, "Soup".to_string());
+        pattern.comment = format!("Random soup\n{}", soup.to_comment());
+        let rle_string = export_to_rle_string(&pattern);
+        assert_eq!(rle_string.lines().filter(|line| line.starts_with("#C ")).count(), 2);
+
+        let imported = import_from_rle_string(&rle_string).unwrap();
+        let restored = SoupSettings::from_comment(&imported.comment).unwrap();
+        assert_eq!(restored, soup);
+
+        // 用注释中的参数可以复现同样的随机汤
+        let mut replay = Grid::new(12, 12);
+        replay.randomize_region(restored.seed, restored.density, restored.symmetry, (0, 0, 12, 12));
+        let reloaded = imported.to_grid().unwrap();
+        for y in 0..12 {
+            for x in 0..12 {
+                assert_eq!(replay.is_alive(x, y), reloaded.is_alive(x, y));
+            }
+        }
+    }
 }
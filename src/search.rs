@@ -0,0 +1,581 @@
+//! 随机汤搜索模块
+//! 批量运行带种子的随机汤直到稳定，记录寿命最长的随机汤、长寿图案（methuselah）
+//! 和产生稀有对象的随机汤。结果保存在本地 JSON 数据库中（也可以导出 CSV），
+//! 每条记录都带有种子、密度和对称性，可以在界面中重新生成同一个随机汤
+
+use crate::census::{self, Census, UNKNOWN_APGCODE};
+use crate::game::{Grid, LifeEngine};
+use crate::period::{PeriodDetector, Periodicity};
+use crate::random::Symmetry;
+use crate::rules::Rule;
+use crate::universe::SparseUniverse;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// B3/S23 随机汤中最常见的对象，不计为稀有对象（只用于 B3/S23）
+pub const COMMON_OBJECTS: [&str; 15] = [
+    "xs4_33",         // block
+    "xp2_7",          // blinker
+    "xs6_696",        // beehive
+    "xq4_153",        // glider
+    "xs7_2596",       // loaf
+    "xs5_253",        // boat
+    "xs6_356",        // ship
+    "xs4_252",        // tub
+    "xs8_6996",       // pond
+    "xs7_25ac",       // long boat
+    "xp2_7e",         // toad
+    "xp2_318c",       // beacon
+    "xs6_25a4",       // barge
+    "xs8_69ic",       // mango
+    "xs12_g8o653z11", // ship-tie
+];
+
+/// 按人口周期判断稳定时检查的最大周期
+const MAX_POPULATION_PERIOD: usize = 60;
+
+/// 人口至少连续这么多代呈周期变化才视为稳定
+const MIN_POPULATION_WINDOW: usize = 120;
+
+/// 批量搜索的参数
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchSettings {
+    /// 随机汤（正方形）的边长
+    pub soup_size: usize,
+    /// 细胞存活的概率
+    pub density: f32,
+    /// 随机汤的对称性
+    pub symmetry: Symmetry,
+    /// 演化规则
+    pub rule: Rule,
+    /// 最多模拟的代数，超过后记为未稳定
+    pub max_generations: u64,
+    /// 寿命达到该代数的随机汤记为长寿图案
+    pub methuselah_lifespan: u64,
+    /// 保留寿命最长的随机汤个数
+    pub keep_longest: usize,
+}
+
+impl Default for SearchSettings {
+    fn default() -> Self {
+        Self {
+            soup_size: 16,
+            density: 0.5,
+            symmetry: Symmetry::C1,
+            rule: Rule::conway(),
+            max_generations: 50_000,
+            methuselah_lifespan: 5_000,
+            keep_longest: 10,
+        }
+    }
+}
+
+/// 演化到稳定的结果
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Settling {
+    /// 进入最终周期状态（或灭绝）的代数；未稳定时为模拟的代数
+    pub lifespan: u64,
+    /// 是否在限定代数内稳定
+    pub settled: bool,
+}
+
+/// 把引擎演化到稳定：灭绝、整体回到之前的形状，或者飞船飞走后人口呈周期变化
+pub fn run_until_settled(engine: &mut dyn LifeEngine, max_generations: u64) -> Settling {
+    let mut detector = PeriodDetector::default();
+    let mut populations = vec![engine.count_alive_cells()];
+    detector.observe(engine, 0);
+
+    for generation in 1..=max_generations {
+        engine.next_generation();
+        populations.push(engine.count_alive_cells());
+
+        // 每代都观察，第一次重复时的间隔就是周期，最终状态从一个周期之前开始
+        let lifespan = match detector.observe(engine, generation) {
+            Some(Periodicity::Extinct) => Some(generation),
+            Some(Periodicity::StillLife) => Some(generation - 1),
+            Some(Periodicity::Oscillator { period })
+            | Some(Periodicity::Spaceship { period, .. }) => Some(generation - period),
+            None => population_cycle_start(&populations),
+        };
+        if let Some(lifespan) = lifespan {
+            return Settling {
+                lifespan,
+                settled: true,
+            };
+        }
+    }
+    Settling {
+        lifespan: max_generations,
+        settled: false,
+    }
+}
+
+/// 人口序列末尾呈周期变化时返回周期开始的代数
+///
+/// 滑翔机等飞船飞离残骸后整体形状不再重复，但人口仍然是周期的
+fn population_cycle_start(populations: &[usize]) -> Option<u64> {
+    let last = populations.len().checked_sub(1)?;
+    (1..=MAX_POPULATION_PERIOD).find_map(|period| {
+        let window = (period * 4).max(MIN_POPULATION_WINDOW);
+        if last < window {
+            return None;
+        }
+        let periodic = (last - window..last - period)
+            .all(|index| populations[index] == populations[index + period]);
+        if !periodic {
+            return None;
+        }
+        // 向前找到周期开始的位置
+        let mut start = last - window;
+        while start > 0 && populations[start - 1] == populations[start - 1 + period] {
+            start -= 1;
+        }
+        Some(start as u64)
+    })
+}
+
+/// 用与界面随机化相同的方式生成随机汤：边长为 `soup_size` 的正方形网格
+pub fn soup_grid(seed: u64, settings: &SearchSettings) -> Grid {
+    let size = settings.soup_size;
+    let mut grid = Grid::new(size, size);
+    grid.set_rule(settings.rule);
+    grid.randomize_region(
+        seed,
+        settings.density,
+        settings.symmetry,
+        (0, 0, size, size),
+    );
+    grid
+}
+
+/// 一个随机汤的搜索结果
+#[derive(Clone, Debug, PartialEq)]
+pub struct SoupResult {
+    pub record: SoupRecord,
+    /// 最终状态的对象普查；未稳定时为空
+    pub census: Census,
+}
+
+/// 在无界宇宙中运行一个随机汤直到稳定，并对最终状态进行普查
+///
+/// 规则包含 B0 时返回错误
+pub fn run_soup(seed: u64, settings: &SearchSettings) -> Result<SoupResult, String> {
+    let mut universe = SparseUniverse::new();
+    universe.set_rule(settings.rule)?;
+    universe.load_window(&soup_grid(seed, settings), (0, 0));
+
+    let settling = run_until_settled(&mut universe, settings.max_generations);
+    let census = if settling.settled {
+        census::take_census(&universe)
+    } else {
+        Census::default()
+    };
+    let rare_objects = census
+        .entries
+        .iter()
+        .map(|entry| entry.apgcode.clone())
+        .filter(|apgcode| is_rare(apgcode, &settings.rule))
+        .collect();
+
+    Ok(SoupResult {
+        record: SoupRecord {
+            seed,
+            density: settings.density,
+            symmetry: settings.symmetry,
+            soup_size: settings.soup_size,
+            lifespan: settling.lifespan,
+            settled: settling.settled,
+            final_population: universe.count_alive_cells(),
+            rare_objects,
+        },
+        census,
+    })
+}
+
+/// 对象在规则 `rule` 下是否稀有：能被识别，并且不在常见对象列表中
+///
+/// 常见对象列表来自 B3/S23，其他规则下所有能识别的对象都算稀有
+pub fn is_rare(apgcode: &str, rule: &Rule) -> bool {
+    apgcode != UNKNOWN_APGCODE
+        && !(*rule == Rule::conway() && COMMON_OBJECTS.contains(&apgcode))
+}
+
+/// 数据库中的一条随机汤记录
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SoupRecord {
+    pub seed: u64,
+    pub density: f32,
+    pub symmetry: Symmetry,
+    pub soup_size: usize,
+    /// 进入最终状态的代数
+    pub lifespan: u64,
+    /// 是否在限定代数内稳定
+    pub settled: bool,
+    /// 最终状态的人口
+    pub final_population: usize,
+    /// 最终状态中的稀有对象
+    pub rare_objects: Vec<String>,
+}
+
+/// 随机汤被记录的原因
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Category {
+    /// 寿命最长的随机汤之一
+    Longest,
+    /// 寿命达到长寿图案的标准
+    Methuselah,
+    /// 产生了稀有对象
+    Rare,
+    /// 在限定代数内没有稳定（可能无限增长）
+    Unsettled,
+}
+
+impl Category {
+    /// 所有类别，按数据库中的顺序排列
+    pub const ALL: [Category; 4] = [
+        Category::Longest,
+        Category::Methuselah,
+        Category::Rare,
+        Category::Unsettled,
+    ];
+
+    /// 类别名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            Category::Longest => "longest",
+            Category::Methuselah => "methuselah",
+            Category::Rare => "rare",
+            Category::Unsettled => "unsettled",
+        }
+    }
+}
+
+/// 随机汤搜索结果数据库
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchDatabase {
+    /// 搜索使用的规则（B/S 记法）
+    pub rule: String,
+    /// 已搜索的随机汤数量
+    pub soups_searched: u64,
+    /// 继续搜索时使用的第一个种子
+    pub next_seed: u64,
+    /// 寿命最长的随机汤，按寿命降序排列
+    pub longest: Vec<SoupRecord>,
+    /// 长寿图案
+    pub methuselahs: Vec<SoupRecord>,
+    /// 产生稀有对象的随机汤
+    pub rare: Vec<SoupRecord>,
+    /// 没有稳定的随机汤
+    pub unsettled: Vec<SoupRecord>,
+    /// 所有随机汤最终状态中各对象的出现次数
+    pub object_counts: BTreeMap<String, u64>,
+}
+
+impl SearchDatabase {
+    /// 创建使用指定规则的空数据库
+    pub fn new(rule: &Rule) -> Self {
+        Self {
+            rule: rule.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// 从 JSON 文件读取数据库
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        serde_json::from_str(&json)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    /// 把数据库写入 JSON 文件
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        std::fs::write(path, json)
+    }
+
+    /// 某个类别的记录
+    pub fn records(&self, category: Category) -> &[SoupRecord] {
+        match category {
+            Category::Longest => &self.longest,
+            Category::Methuselah => &self.methuselahs,
+            Category::Rare => &self.rare,
+            Category::Unsettled => &self.unsettled,
+        }
+    }
+
+    /// 加入一个随机汤的结果
+    ///
+    /// # 返回值
+    /// 返回该随机汤被记录的类别；普通的随机汤只计入统计，返回空列表
+    pub fn add(&mut self, result: SoupResult, settings: &SearchSettings) -> Vec<Category> {
+        self.soups_searched += 1;
+        self.next_seed = self.next_seed.max(result.record.seed.wrapping_add(1));
+        for entry in &result.census.entries {
+            *self.object_counts.entry(entry.apgcode.clone()).or_insert(0) += entry.count as u64;
+        }
+
+        let record = result.record;
+        let mut categories = Vec::new();
+        if !record.settled {
+            categories.push(Category::Unsettled);
+            self.unsettled.push(record);
+            return categories;
+        }
+
+        // keep_longest 为 0 时不保留寿命最长的随机汤
+        let shortest_kept = self.longest.last().map_or(0, |shortest| shortest.lifespan);
+        if settings.keep_longest > 0
+            && (self.longest.len() < settings.keep_longest || record.lifespan > shortest_kept)
+        {
+            categories.push(Category::Longest);
+            let index = self
+                .longest
+                .partition_point(|kept| kept.lifespan >= record.lifespan);
+            self.longest.insert(index, record.clone());
+            self.longest.truncate(settings.keep_longest);
+        }
+        if record.lifespan >= settings.methuselah_lifespan {
+            categories.push(Category::Methuselah);
+            self.methuselahs.push(record.clone());
+        }
+        if !record.rare_objects.is_empty() {
+            categories.push(Category::Rare);
+            self.rare.push(record);
+        }
+        categories
+    }
+
+    /// 从 `next_seed` 开始连续搜索 `count` 个随机汤
+    ///
+    /// 每记录一个随机汤调用一次 `on_record`。数据库已有结果且规则不同，或者规则包含 B0 时返回错误
+    pub fn search(
+        &mut self,
+        settings: &SearchSettings,
+        count: u64,
+        mut on_record: impl FnMut(&SoupRecord, &[Category]),
+    ) -> Result<(), String> {
+        settings.rule.check_unbounded()?;
+        let rule = settings.rule.to_string();
+        if self.soups_searched == 0 {
+            self.rule = rule;
+        } else if self.rule != rule {
+            return Err(format!(
+                "database was searched with rule {}, not {}",
+                self.rule, rule
+            ));
+        }
+
+        for _ in 0..count {
+            let result = run_soup(self.next_seed, settings)?;
+            let record = result.record.clone();
+            let categories = self.add(result, settings);
+            if !categories.is_empty() {
+                on_record(&record, &categories);
+            }
+        }
+        Ok(())
+    }
+
+    /// 导出所有记录为 CSV 文本，同一个随机汤可能出现在多个类别中
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "category,seed,density,symmetry,size,lifespan,settled,population,rare_objects\n",
+        );
+        for category in Category::ALL {
+            for record in self.records(category) {
+                let _ = writeln!(
+                    csv,
+                    "{},{},{},{},{},{},{},{},{}",
+                    category.name(),
+                    record.seed,
+                    record.density,
+                    record.symmetry,
+                    record.soup_size,
+                    record.lifespan,
+                    record.settled,
+                    record.final_population,
+                    record.rare_objects.join(" ")
+                );
+            }
+        }
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::{miscellaneous, Pattern};
+
+    /// 把预设图案放入无界宇宙
+    fn universe_with(pattern: &Pattern) -> SparseUniverse {
+        let mut universe = SparseUniverse::new();
+        for (y, line) in pattern.data.iter().enumerate() {
+            for (x, ch) in line.chars().enumerate() {
+                if matches!(ch, '*' | '#' | 'O') {
+                    universe.set_cell(x as i64, y as i64, crate::game::CellState::Alive);
+                }
+            }
+        }
+        universe
+    }
+
+    #[test]
+    fn test_methuselah_lifespans() {
+        // Diehard 在第130代消失
+        let mut diehard = universe_with(&miscellaneous::DIEHARD);
+        assert_eq!(
+            run_until_settled(&mut diehard, 1000),
+            Settling {
+                lifespan: 130,
+                settled: true
+            }
+        );
+
+        // R-pentomino 在第1103代稳定，之后滑翔机飞走，只能靠人口周期判断
+        let mut r_pentomino = universe_with(&miscellaneous::R_PENTOMINO);
+        let settling = run_until_settled(&mut r_pentomino, 5000);
+        assert_eq!(
+            settling,
+            Settling {
+                lifespan: 1103,
+                settled: true
+            }
+        );
+
+        let mut unfinished = universe_with(&miscellaneous::R_PENTOMINO);
+        assert_eq!(
+            run_until_settled(&mut unfinished, 200),
+            Settling {
+                lifespan: 200,
+                settled: false
+            }
+        );
+    }
+
+    #[test]
+    fn test_soup_is_reproducible() {
+        let settings = SearchSettings::default();
+        let first = run_soup(7, &settings).unwrap();
+        let second = run_soup(7, &settings).unwrap();
+        assert_eq!(first, second);
+        assert!(first.record.settled);
+        assert_eq!(
+            first.census.total_objects() > 0,
+            first.record.final_population > 0
+        );
+
+        // 与界面在任意位置随机化同样参数的区域得到相同的细胞
+        let soup = soup_grid(7, &settings);
+        let mut grid = Grid::new(40, 40);
+        grid.randomize_region(7, settings.density, settings.symmetry, (10, 12, 16, 16));
+        for y in 0..16 {
+            for x in 0..16 {
+                assert_eq!(soup.is_alive(x, y), grid.is_alive(x + 10, y + 12));
+            }
+        }
+    }
+
+    #[test]
+    fn test_database_categories_and_round_trip() {
+        let settings = SearchSettings {
+            keep_longest: 2,
+            methuselah_lifespan: 300,
+            ..SearchSettings::default()
+        };
+        let record = |seed, lifespan, settled, rare: &[&str]| SoupResult {
+            record: SoupRecord {
+                seed,
+                density: 0.5,
+                symmetry: Symmetry::C1,
+                soup_size: 16,
+                lifespan,
+                settled,
+                final_population: 10,
+                rare_objects: rare.iter().map(|code| code.to_string()).collect(),
+            },
+            census: Census::default(),
+        };
+
+        let mut database = SearchDatabase::new(&Rule::conway());
+        assert_eq!(
+            database.add(record(0, 100, true, &[]), &settings),
+            [Category::Longest]
+        );
+        assert_eq!(
+            database.add(record(1, 400, true, &["xp15_4r4z4r4"]), &settings),
+            [Category::Longest, Category::Methuselah, Category::Rare]
+        );
+        assert_eq!(database.add(record(2, 50, true, &[]), &settings), []);
+        assert_eq!(
+            database.add(record(3, 200, true, &[]), &settings),
+            [Category::Longest]
+        );
+        assert_eq!(
+            database.add(record(4, 900, false, &[]), &settings),
+            [Category::Unsettled]
+        );
+
+        let longest: Vec<u64> = database.longest.iter().map(|soup| soup.seed).collect();
+        assert_eq!(longest, [1, 3]);
+        assert_eq!((database.soups_searched, database.next_seed), (5, 5));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("soups.json");
+        database.save(&path).unwrap();
+        assert_eq!(SearchDatabase::load(&path).unwrap(), database);
+
+        let csv = database.to_csv();
+        assert_eq!(csv.lines().count(), 1 + 2 + 1 + 1 + 1);
+        assert!(csv.contains("rare,1,0.5,C1,16,400,true,10,xp15_4r4z4r4\n"));
+
+        // 不保留寿命最长的随机汤时不记录该类别
+        let settings = SearchSettings {
+            keep_longest: 0,
+            ..settings
+        };
+        let mut database = SearchDatabase::new(&Rule::conway());
+        assert_eq!(
+            database.add(record(0, 400, true, &[]), &settings),
+            [Category::Methuselah]
+        );
+        assert!(database.longest.is_empty());
+    }
+
+    #[test]
+    fn test_common_objects_only_apply_to_conway() {
+        assert!(!is_rare("xs4_33", &Rule::conway()));
+        assert!(is_rare("xp15_4r4z4r4", &Rule::conway()));
+        assert!(is_rare("xs4_33", &Rule::parse("B36/S23").unwrap()));
+        assert!(!is_rare(UNKNOWN_APGCODE, &Rule::parse("B36/S23").unwrap()));
+    }
+
+    #[test]
+    fn test_search_continues_from_next_seed() {
+        let settings = SearchSettings {
+            soup_size: 8,
+            ..SearchSettings::default()
+        };
+        let mut database = SearchDatabase::new(&settings.rule);
+        database.search(&settings, 3, |_, _| {}).unwrap();
+        database.search(&settings, 2, |_, _| {}).unwrap();
+        assert_eq!((database.soups_searched, database.next_seed), (5, 5));
+        assert!(!database.longest.is_empty());
+
+        // 无界宇宙无法模拟 B0 规则
+        let b0 = SearchSettings {
+            rule: Rule::parse("B0/S8").unwrap(),
+            ..settings
+        };
+        assert!(SearchDatabase::new(&b0.rule).search(&b0, 1, |_, _| {}).is_err());
+
+        let highlife = SearchSettings {
+            rule: Rule::parse("B36/S23").unwrap(),
+            ..settings
+        };
+        assert!(database.search(&highlife, 1, |_, _| {}).is_err());
+    }
+}
@@ -0,0 +1,225 @@
+//! 矩形选区模块
+//! 对网格中的矩形区域进行复制、清除、填充、随机填充、翻转、旋转和平移，不依赖图形界面
+
+use crate::game::{CellState, Grid, LifeEngine};
+use crate::random::Symmetry;
+use crate::stamp::{Stamp, StampMode};
+
+/// 网格中的矩形选区：左上角坐标和尺寸（以细胞为单位）
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Selection {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Selection {
+    /// 创建选区
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// 由两个对角细胞（都包含在选区内）确定的选区，对角的先后顺序任意
+    pub fn from_corners(a: (usize, usize), b: (usize, usize)) -> Self {
+        let (x, y) = (a.0.min(b.0), a.1.min(b.1));
+        Self::new(x, y, a.0.max(b.0) - x + 1, a.1.max(b.1) - y + 1)
+    }
+
+    /// 覆盖整个网格的选区
+    pub fn whole(grid: &Grid) -> Self {
+        Self::new(0, 0, grid.width(), grid.height())
+    }
+
+    /// 裁剪到网格范围内的部分（例如网格尺寸改变之后），完全在网格外时返回 `None`
+    pub fn clip_to(&self, grid: &Grid) -> Option<Self> {
+        Self::clipped(self.x as i64, self.y as i64, self.width, self.height, grid)
+    }
+
+    /// 左上角在 (x, y) 的矩形裁剪到网格范围内的部分，完全在网格外时返回 `None`
+    fn clipped(x: i64, y: i64, width: usize, height: usize, grid: &Grid) -> Option<Self> {
+        let (left, top) = (x.max(0), y.max(0));
+        let right = (x + width as i64).min(grid.width() as i64);
+        let bottom = (y + height as i64).min(grid.height() as i64);
+        if right <= left || bottom <= top {
+            return None;
+        }
+        Some(Self::new(
+            left as usize,
+            top as usize,
+            (right - left) as usize,
+            (bottom - top) as usize,
+        ))
+    }
+
+    /// 指定细胞是否在选区内
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        (self.x..self.x + self.width).contains(&x) && (self.y..self.y + self.height).contains(&y)
+    }
+
+    /// (x, y, 宽, 高) 形式的区域，与 `Grid::randomize_region` 的参数一致
+    pub fn region(&self) -> (usize, usize, usize, usize) {
+        (self.x, self.y, self.width, self.height)
+    }
+
+    /// 复制选区内的细胞
+    pub fn copy(&self, grid: &Grid) -> Stamp {
+        let mut stamp = Stamp::new(self.width, self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                stamp.set(x, y, grid.is_alive(self.x + x, self.y + y));
+            }
+        }
+        stamp
+    }
+
+    /// 清除选区内的细胞
+    pub fn clear(&self, grid: &mut Grid) {
+        self.set_all(grid, CellState::Dead);
+    }
+
+    /// 用存活细胞填满选区
+    pub fn fill(&self, grid: &mut Grid) {
+        self.set_all(grid, CellState::Alive);
+    }
+
+    fn set_all(&self, grid: &mut Grid, state: CellState) {
+        for y in self.y..self.y + self.height {
+            for x in self.x..self.x + self.width {
+                grid.set_cell(x, y, state);
+            }
+        }
+    }
+
+    /// 清除选区外的所有细胞
+    pub fn clear_outside(&self, grid: &mut Grid) {
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                if !self.contains(x, y) {
+                    grid.set_cell(x, y, CellState::Dead);
+                }
+            }
+        }
+    }
+
+    /// 按给定种子和密度随机填充选区
+    pub fn randomize(&self, grid: &mut Grid, seed: u64, density: f32) {
+        grid.randomize_region(seed, density, Symmetry::C1, self.region());
+    }
+
+    /// 左右翻转选区内的细胞
+    pub fn flip_horizontal(&self, grid: &mut Grid) {
+        let stamp = self.copy(grid).flipped_horizontal();
+        grid.stamp(&stamp, self.x as i64, self.y as i64, StampMode::Replace);
+    }
+
+    /// 上下翻转选区内的细胞
+    pub fn flip_vertical(&self, grid: &mut Grid) {
+        let stamp = self.copy(grid).flipped_vertical();
+        grid.stamp(&stamp, self.x as i64, self.y as i64, StampMode::Replace);
+    }
+
+    /// 绕选区中心顺时针旋转 90°，返回旋转后的选区（裁剪到网格内）
+    pub fn rotate_clockwise(&self, grid: &mut Grid) -> Option<Self> {
+        self.rotate(grid, 1)
+    }
+
+    /// 绕选区中心逆时针旋转 90°，返回旋转后的选区（裁剪到网格内）
+    pub fn rotate_counterclockwise(&self, grid: &mut Grid) -> Option<Self> {
+        self.rotate(grid, 3)
+    }
+
+    fn rotate(&self, grid: &mut Grid, quarter_turns: usize) -> Option<Self> {
+        let mut stamp = self.copy(grid);
+        for _ in 0..quarter_turns {
+            stamp = stamp.rotated_clockwise();
+        }
+        // 宽高互换后保持中心不变；来回旋转时取整误差相互抵消
+        let (width, height) = (self.width as i64, self.height as i64);
+        let x = self.x as i64 + (width - height) / 2;
+        let y = self.y as i64 + (height - width) / 2;
+        self.move_to(grid, &stamp, x, y)
+    }
+
+    /// 把选区内的细胞移动 (dx, dy)，原位置清空、目标位置被覆盖，返回移动后的选区
+    pub fn shift(&self, grid: &mut Grid, dx: i64, dy: i64) -> Option<Self> {
+        let stamp = self.copy(grid);
+        self.move_to(grid, &stamp, self.x as i64 + dx, self.y as i64 + dy)
+    }
+
+    /// 清空原选区，把图案以替换方式放到 (x, y) 处，返回新的选区
+    fn move_to(&self, grid: &mut Grid, stamp: &Stamp, x: i64, y: i64) -> Option<Self> {
+        self.clear(grid);
+        grid.stamp(stamp, x, y, StampMode::Replace);
+        Self::clipped(x, y, stamp.width(), stamp.height(), grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::tests::rows;
+
+    #[test]
+    fn test_copy_clear_and_fill() {
+        let mut grid = Grid::new(6, 4);
+        grid.load_pattern(&["*.....", ".**...", "...*..", ".....*"], 0, 0);
+        let selection = Selection::from_corners((3, 2), (1, 1));
+        assert_eq!(selection, Selection::new(1, 1, 3, 2));
+        assert_eq!(selection.copy(&grid), Stamp::from_lines(&["**.", "..*"]));
+
+        let mut cleared = grid.clone();
+        selection.clear(&mut cleared);
+        assert_eq!(rows(&cleared), ["*.....", "......", "......", ".....*"]);
+
+        let mut outside = grid.clone();
+        selection.clear_outside(&mut outside);
+        assert_eq!(rows(&outside), ["......", ".**...", "...*..", "......"]);
+
+        selection.fill(&mut grid);
+        assert_eq!(grid.count_alive_cells(), 2 + 6);
+    }
+
+    #[test]
+    fn test_random_fill_stays_inside() {
+        let mut grid = Grid::new(20, 20);
+        let selection = Selection::new(5, 6, 4, 3);
+        selection.randomize(&mut grid, 7, 1.0);
+        assert_eq!(grid.count_alive_cells(), 12);
+        assert_eq!(selection.copy(&grid).population(), 12);
+    }
+
+    #[test]
+    fn test_flip_rotate_and_shift() {
+        let mut grid = Grid::new(6, 5);
+        grid.load_pattern(&["......", ".**...", ".*....", "......"], 0, 0);
+        let selection = Selection::new(1, 1, 3, 2);
+
+        selection.flip_horizontal(&mut grid);
+        assert_eq!(rows(&grid)[1..3], ["..**..", "...*.."]);
+        selection.flip_vertical(&mut grid);
+        assert_eq!(rows(&grid)[1..3], ["...*..", "..**.."]);
+
+        // 3x2 旋转后变为 2x3，中心不变；再逆时针旋转回到原处
+        let rotated = selection.rotate_clockwise(&mut grid).unwrap();
+        assert_eq!(rotated, Selection::new(1, 1, 2, 3));
+        assert_eq!(rotated.copy(&grid), Stamp::from_lines(&["..", "*.", "**"]));
+        let back = rotated.rotate_counterclockwise(&mut grid).unwrap();
+        assert_eq!(back, selection);
+        assert_eq!(back.copy(&grid), Stamp::from_lines(&["..*", ".**"]));
+
+        // 平移出网格边界的部分被裁掉
+        let shifted = back.shift(&mut grid, 3, 2).unwrap();
+        assert_eq!(shifted, Selection::new(4, 3, 2, 2));
+        assert_eq!(
+            rows(&grid),
+            ["......", "......", "......", "......", ".....*"]
+        );
+        assert_eq!(back.shift(&mut grid, -10, 0), None);
+    }
+}
@@ -0,0 +1,337 @@
+//! 图形绘制模块
+//! 在网格上绘制 Bresenham 直线、空心和实心矩形、椭圆，以及泛洪填充和方形笔刷；
+//! 图形的细胞列表可以单独计算，用于在界面上预览
+
+use crate::game::{CellState, Grid};
+
+/// 由两个对角细胞确定的图形
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Shape {
+    /// 两个细胞之间的线段
+    Line,
+    /// 空心矩形
+    Rectangle,
+    /// 实心矩形
+    FilledRectangle,
+    /// 内切于矩形的空心椭圆
+    Ellipse,
+    /// 内切于矩形的实心椭圆
+    FilledEllipse,
+}
+
+impl Shape {
+    /// 所有图形，用于界面列表
+    pub const ALL: [Shape; 5] = [
+        Shape::Line,
+        Shape::Rectangle,
+        Shape::FilledRectangle,
+        Shape::Ellipse,
+        Shape::FilledEllipse,
+    ];
+
+    /// 图形的显示名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            Shape::Line => "Line",
+            Shape::Rectangle => "Rectangle",
+            Shape::FilledRectangle => "Filled Rectangle",
+            Shape::Ellipse => "Ellipse",
+            Shape::FilledEllipse => "Filled Ellipse",
+        }
+    }
+
+    /// 图形覆盖的细胞（可能有重复）
+    pub fn cells(&self, a: (usize, usize), b: (usize, usize)) -> Vec<(usize, usize)> {
+        let (a, b) = ((a.0 as i64, a.1 as i64), (b.0 as i64, b.1 as i64));
+        let cells = match self {
+            Shape::Line => line_cells(a, b),
+            Shape::Rectangle => rectangle_cells(a, b),
+            Shape::FilledRectangle => fill_rows(&rectangle_cells(a, b)),
+            Shape::Ellipse => ellipse_cells(a, b),
+            Shape::FilledEllipse => fill_rows(&ellipse_cells(a, b)),
+        };
+        // 图形总在两个角确定的矩形之内，坐标不会为负
+        cells
+            .into_iter()
+            .map(|(x, y)| (x as usize, y as usize))
+            .collect()
+    }
+}
+
+/// Bresenham 直线：包含两个端点
+fn line_cells((x0, y0): (i64, i64), (x1, y1): (i64, i64)) -> Vec<(i64, i64)> {
+    let (dx, dy) = ((x1 - x0).abs(), -(y1 - y0).abs());
+    let (sx, sy) = ((x1 - x0).signum(), (y1 - y0).signum());
+    let (mut x, mut y, mut err) = (x0, y0, dx + dy);
+    let mut cells = Vec::with_capacity((dx - dy + 1) as usize);
+    loop {
+        cells.push((x, y));
+        if (x, y) == (x1, y1) {
+            return cells;
+        }
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+    }
+}
+
+/// 矩形的边框
+fn rectangle_cells(a: (i64, i64), b: (i64, i64)) -> Vec<(i64, i64)> {
+    let (left, right) = (a.0.min(b.0), a.0.max(b.0));
+    let (top, bottom) = (a.1.min(b.1), a.1.max(b.1));
+    let mut cells = Vec::new();
+    for x in left..=right {
+        cells.push((x, top));
+        cells.push((x, bottom));
+    }
+    for y in top..=bottom {
+        cells.push((left, y));
+        cells.push((right, y));
+    }
+    cells
+}
+
+/// 内切于矩形的椭圆边框（Zingl 的矩形椭圆 Bresenham 算法，偶数尺寸时同样对称）
+fn ellipse_cells(a: (i64, i64), b: (i64, i64)) -> Vec<(i64, i64)> {
+    let (mut x0, mut x1) = (a.0.min(b.0), a.0.max(b.0));
+    let (top, bottom) = (a.1.min(b.1), a.1.max(b.1));
+    let (width, height) = (x1 - x0, bottom - top);
+    if width == 0 || height == 0 {
+        // 只有一行或一列时椭圆就是线段
+        return rectangle_cells(a, b);
+    }
+    let odd = height & 1;
+
+    let mut dx = 4 * (1 - width) * height * height;
+    let mut dy = 4 * (odd + 1) * width * width;
+    let mut err = dx + dy + odd * width * width;
+    let mut y0 = top + (height + 1) / 2;
+    let mut y1 = y0 - odd;
+    let (step_x, step_y) = (8 * height * height, 8 * width * width);
+
+    let mut cells = Vec::new();
+    loop {
+        cells.extend([(x1, y0), (x0, y0), (x0, y1), (x1, y1)]);
+        let e2 = 2 * err;
+        if e2 <= dy {
+            y0 += 1;
+            y1 -= 1;
+            dy += step_y;
+            err += dy;
+        }
+        if e2 >= dx || 2 * err > dy {
+            x0 += 1;
+            x1 -= 1;
+            dx += step_x;
+            err += dx;
+        }
+        if x0 > x1 {
+            break;
+        }
+    }
+    // 很窄的椭圆（两列宽）提前结束时补齐上下两端
+    while y0 - y1 <= height {
+        cells.extend([(x0 - 1, y0), (x1 + 1, y0), (x0 - 1, y1), (x1 + 1, y1)]);
+        y0 += 1;
+        y1 -= 1;
+    }
+    cells
+}
+
+/// 把凸形边框的每一行从最左填到最右
+fn fill_rows(outline: &[(i64, i64)]) -> Vec<(i64, i64)> {
+    let mut spans = std::collections::BTreeMap::new();
+    for &(x, y) in outline {
+        let span = spans.entry(y).or_insert((x, x));
+        span.0 = span.0.min(x);
+        span.1 = span.1.max(x);
+    }
+    spans
+        .into_iter()
+        .flat_map(|(y, (left, right))| (left..=right).map(move |x| (x, y)))
+        .collect()
+}
+
+/// 网格上的绘图操作
+impl Grid {
+    /// 绘制由两个细胞确定的图形，超出网格的部分被裁掉
+    pub fn draw_shape(
+        &mut self,
+        shape: Shape,
+        a: (usize, usize),
+        b: (usize, usize),
+        state: CellState,
+    ) {
+        for (x, y) in shape.cells(a, b) {
+            self.set_cell(x, y, state);
+        }
+    }
+
+    /// 以 (x, y) 为中心绘制边长为 `size` 的方形笔刷（偶数边长时中心偏左上）
+    pub fn paint_brush(&mut self, x: usize, y: usize, size: usize, state: CellState) {
+        let size = size.max(1);
+        let (left, top) = (
+            x.saturating_sub((size - 1) / 2),
+            y.saturating_sub((size - 1) / 2),
+        );
+        let (right, bottom) = (x + size / 2, y + size / 2);
+        for cy in top..=bottom.min(self.height().saturating_sub(1)) {
+            for cx in left..=right.min(self.width().saturating_sub(1)) {
+                self.set_cell(cx, cy, state);
+            }
+        }
+    }
+
+    /// 用方形笔刷沿线段连续绘制，拖动时鼠标移动较快也不会留下空隙
+    pub fn paint_brush_line(
+        &mut self,
+        from: (usize, usize),
+        to: (usize, usize),
+        size: usize,
+        state: CellState,
+    ) {
+        for (x, y) in Shape::Line.cells(from, to) {
+            self.paint_brush(x, y, size, state);
+        }
+    }
+
+    /// 从 (x, y) 开始，把与该细胞状态相同的四连通区域设为 `state`，返回改变的细胞数
+    ///
+    /// 填充只在网格范围内进行，不跨越环面等边界拓扑
+    pub fn flood_fill(&mut self, x: usize, y: usize, state: CellState) -> usize {
+        let target = self.is_alive(x, y);
+        if x >= self.width() || y >= self.height() || target == (state == CellState::Alive) {
+            return 0;
+        }
+        let mut changed = 0;
+        let mut stack = vec![(x, y)];
+        while let Some((x, y)) = stack.pop() {
+            if self.is_alive(x, y) != target {
+                continue;
+            }
+            self.set_cell(x, y, state);
+            changed += 1;
+            if x > 0 {
+                stack.push((x - 1, y));
+            }
+            if x + 1 < self.width() {
+                stack.push((x + 1, y));
+            }
+            if y > 0 {
+                stack.push((x, y - 1));
+            }
+            if y + 1 < self.height() {
+                stack.push((x, y + 1));
+            }
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::tests::rows;
+
+    fn draw(shape: Shape, width: usize, height: usize) -> Grid {
+        let mut grid = Grid::new(width, height);
+        grid.draw_shape(shape, (width - 1, height - 1), (0, 0), CellState::Alive);
+        grid
+    }
+
+    #[test]
+    fn test_bresenham_line() {
+        let mut grid = Grid::new(6, 3);
+        grid.draw_shape(Shape::Line, (0, 0), (5, 2), CellState::Alive);
+        assert_eq!(rows(&grid), ["**....", "..**..", "....**"]);
+
+        // 端点顺序不影响结果，单个细胞的线段只画一个细胞
+        let mut reversed = Grid::new(6, 3);
+        reversed.draw_shape(Shape::Line, (5, 2), (0, 0), CellState::Alive);
+        assert_eq!(reversed.count_alive_cells(), 6);
+        assert_eq!(Shape::Line.cells((4, 4), (4, 4)), vec![(4, 4)]);
+    }
+
+    #[test]
+    fn test_rectangles() {
+        assert_eq!(
+            rows(&draw(Shape::Rectangle, 4, 3)),
+            ["****", "*..*", "****"]
+        );
+        assert_eq!(draw(Shape::FilledRectangle, 4, 3).count_alive_cells(), 12);
+    }
+
+    #[test]
+    fn test_ellipse_is_symmetric_and_fits_its_box() {
+        for (width, height) in [
+            (7, 5),
+            (6, 4),
+            (9, 9),
+            (1, 5),
+            (5, 1),
+            (2, 2),
+            (2, 6),
+            (12, 3),
+        ] {
+            for shape in [Shape::Ellipse, Shape::FilledEllipse] {
+                let cells = shape.cells((0, 0), (width - 1, height - 1));
+                assert!(cells.iter().all(|&(x, y)| x < width && y < height));
+                let grid = draw(shape, width, height);
+                let image = rows(&grid);
+                let mirrored: Vec<String> = image
+                    .iter()
+                    .map(|row| row.chars().rev().collect())
+                    .collect();
+                let flipped: Vec<String> = image.iter().rev().cloned().collect();
+                assert_eq!(image, mirrored, "{:?} {}x{}", shape, width, height);
+                assert_eq!(image, flipped, "{:?} {}x{}", shape, width, height);
+                // 椭圆接触外接矩形的四条边
+                assert!(image[0].contains('*') && image[height - 1].contains('*'));
+                assert!(image.iter().any(|row| row.starts_with('*')));
+            }
+        }
+        assert_eq!(
+            rows(&draw(Shape::Ellipse, 7, 5)),
+            ["..***..", ".*...*.", "*.....*", ".*...*.", "..***.."]
+        );
+        assert_eq!(
+            rows(&draw(Shape::FilledEllipse, 7, 5)),
+            ["..***..", ".*****.", "*******", ".*****.", "..***.."]
+        );
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let mut grid = Grid::new(6, 5);
+        grid.draw_shape(Shape::Rectangle, (1, 1), (4, 4), CellState::Alive);
+        // 填充矩形内部
+        assert_eq!(grid.flood_fill(2, 2, CellState::Alive), 4);
+        assert_eq!(grid.count_alive_cells(), 16);
+        // 填充外部的死细胞区域：四连通，不会穿过边框
+        assert_eq!(grid.flood_fill(0, 0, CellState::Alive), 30 - 16);
+        // 已经是目标状态时不改变
+        assert_eq!(grid.flood_fill(0, 0, CellState::Alive), 0);
+        // 擦除整个连通的存活区域
+        assert_eq!(grid.flood_fill(3, 3, CellState::Dead), 30);
+    }
+
+    #[test]
+    fn test_brush() {
+        let mut grid = Grid::new(8, 6);
+        grid.paint_brush(0, 0, 3, CellState::Alive);
+        assert_eq!(grid.count_alive_cells(), 4);
+        grid.paint_brush(4, 3, 3, CellState::Alive);
+        assert_eq!(grid.count_alive_cells(), 4 + 9);
+        assert!(grid.is_alive(3, 2) && grid.is_alive(5, 4) && !grid.is_alive(6, 4));
+
+        let mut grid = Grid::new(10, 3);
+        grid.paint_brush_line((0, 1), (9, 1), 2, CellState::Alive);
+        assert_eq!(rows(&grid), ["..........", "**********", "**********"]);
+    }
+}
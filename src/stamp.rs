@@ -0,0 +1,381 @@
+//! 图案放置模块
+//! 把预设或文件中的图案旋转、镜像后，按指定的合并方式放到已有网格或无界宇宙的某个位置，而不是替换整个网格
+
+use crate::rules::Rule;
+use crate::save_load::RlePattern;
+
+/// 放置图案时与已有细胞的合并方式
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum StampMode {
+    /// 图案中的存活细胞加入网格，其余保持不变
+    #[default]
+    Or,
+    /// 图案中的存活细胞翻转网格中对应细胞的状态
+    Xor,
+    /// 图案中的存活细胞擦除网格中对应的细胞
+    AndNot,
+    /// 图案覆盖的矩形区域完全替换为图案内容
+    Replace,
+}
+
+impl StampMode {
+    /// 所有合并方式，用于界面列表
+    pub const ALL: [StampMode; 4] = [
+        StampMode::Or,
+        StampMode::Xor,
+        StampMode::AndNot,
+        StampMode::Replace,
+    ];
+
+    /// 合并方式的显示名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            StampMode::Or => "OR",
+            StampMode::Xor => "XOR",
+            StampMode::AndNot => "AND-NOT",
+            StampMode::Replace => "Replace",
+        }
+    }
+
+    /// 根据已有细胞和图案细胞计算合并后的细胞状态
+    pub fn apply(&self, existing: bool, stamp: bool) -> bool {
+        match self {
+            StampMode::Or => existing || stamp,
+            StampMode::Xor => existing != stamp,
+            StampMode::AndNot => existing && !stamp,
+            StampMode::Replace => stamp,
+        }
+    }
+}
+
+/// 顺时针旋转的角度
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Rotation {
+    #[default]
+    None,
+    Clockwise90,
+    Clockwise180,
+    Clockwise270,
+}
+
+impl Rotation {
+    /// 所有旋转角度，按顺时针顺序排列
+    pub const ALL: [Rotation; 4] = [
+        Rotation::None,
+        Rotation::Clockwise90,
+        Rotation::Clockwise180,
+        Rotation::Clockwise270,
+    ];
+
+    /// 旋转角度的显示名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rotation::None => "0°",
+            Rotation::Clockwise90 => "90°",
+            Rotation::Clockwise180 => "180°",
+            Rotation::Clockwise270 => "270°",
+        }
+    }
+
+    /// 顺时针旋转的四分之一圈数
+    fn quarter_turns(&self) -> usize {
+        *self as usize
+    }
+
+    fn from_quarter_turns(turns: usize) -> Self {
+        Self::ALL[turns % 4]
+    }
+
+    /// 再顺时针旋转 90° 后的角度
+    pub fn clockwise(&self) -> Self {
+        Self::from_quarter_turns(self.quarter_turns() + 1)
+    }
+
+    /// 再逆时针旋转 90° 后的角度
+    pub fn counterclockwise(&self) -> Self {
+        Self::from_quarter_turns(self.quarter_turns() + 3)
+    }
+
+    /// 反方向的旋转
+    fn inverse(&self) -> Self {
+        Self::from_quarter_turns(4 - self.quarter_turns())
+    }
+}
+
+/// 放置前对图案的变换：先左右镜像（可选），再顺时针旋转
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Transform {
+    pub rotation: Rotation,
+    /// 是否先左右镜像
+    pub reflect: bool,
+}
+
+impl Transform {
+    /// 在当前变换之后再顺时针旋转 90°
+    pub fn rotate_clockwise(&mut self) {
+        self.rotation = self.rotation.clockwise();
+    }
+
+    /// 在当前变换之后再逆时针旋转 90°
+    pub fn rotate_counterclockwise(&mut self) {
+        self.rotation = self.rotation.counterclockwise();
+    }
+
+    /// 在当前变换之后再左右翻转
+    pub fn flip_horizontal(&mut self) {
+        // 镜像 ∘ 旋转θ = 旋转(-θ) ∘ 镜像
+        self.rotation = self.rotation.inverse();
+        self.reflect = !self.reflect;
+    }
+
+    /// 在当前变换之后再上下翻转
+    pub fn flip_vertical(&mut self) {
+        // 上下翻转 = 旋转180° ∘ 左右翻转
+        self.flip_horizontal();
+        self.rotation = Rotation::from_quarter_turns(self.rotation.quarter_turns() + 2);
+    }
+
+    /// 变换的简短描述，例如 "90° mirrored"
+    pub fn describe(&self) -> String {
+        if self.reflect {
+            format!("{} mirrored", self.rotation.name())
+        } else {
+            self.rotation.name().to_string()
+        }
+    }
+}
+
+/// 待放置的矩形图案，按行存储细胞是否存活
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stamp {
+    width: usize,
+    height: usize,
+    cells: Vec<bool>,
+}
+
+impl Stamp {
+    /// 创建全部为死细胞的图案
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![false; width * height],
+        }
+    }
+
+    /// 从字符串行创建图案，'*'、'#'、'O' 表示存活细胞（与 `Grid::load_pattern` 一致）
+    pub fn from_lines(lines: &[&str]) -> Self {
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut stamp = Self::new(width, lines.len());
+        for (y, line) in lines.iter().enumerate() {
+            for (x, ch) in line.chars().enumerate() {
+                if matches!(ch, '*' | '#' | 'O') {
+                    stamp.set(x, y, true);
+                }
+            }
+        }
+        stamp
+    }
+
+    /// 从 RLE 图案创建
+    pub fn from_rle(pattern: &RlePattern) -> Self {
+        let mut stamp = Self::new(pattern.width, pattern.height);
+        for (y, row) in pattern.data.iter().enumerate().take(pattern.height) {
+            for (x, &cell) in row.iter().enumerate().take(pattern.width) {
+                if cell {
+                    stamp.set(x, y, true);
+                }
+            }
+        }
+        stamp
+    }
+
+    /// 转换为 RLE 图案，例如写入剪贴板
+    pub fn to_rle(&self, rule: &Rule) -> RlePattern {
+        let mut pattern = RlePattern::new(String::new(), self.width, self.height);
+        pattern.rule = rule.to_string();
+        self.for_each_cell(|x, y, alive| pattern.data[y][x] = alive);
+        pattern
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// 指定位置的细胞是否存活，超出范围时视为死亡
+    pub fn is_alive(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height && self.cells[y * self.width + x]
+    }
+
+    /// 设置指定位置的细胞，超出范围时忽略
+    pub fn set(&mut self, x: usize, y: usize, alive: bool) {
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x] = alive;
+        }
+    }
+
+    /// 存活细胞数量
+    pub fn population(&self) -> usize {
+        self.cells.iter().filter(|&&cell| cell).count()
+    }
+
+    /// 应用变换后的新图案
+    pub fn transformed(&self, transform: Transform) -> Self {
+        let mut result = if transform.reflect {
+            self.flipped_horizontal()
+        } else {
+            self.clone()
+        };
+        for _ in 0..transform.rotation.quarter_turns() {
+            result = result.rotated_clockwise();
+        }
+        result
+    }
+
+    /// 左右翻转后的图案
+    pub fn flipped_horizontal(&self) -> Self {
+        let mut result = Self::new(self.width, self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                result.set(self.width - 1 - x, y, self.is_alive(x, y));
+            }
+        }
+        result
+    }
+
+    /// 上下翻转后的图案
+    pub fn flipped_vertical(&self) -> Self {
+        let mut result = Self::new(self.width, self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                result.set(x, self.height - 1 - y, self.is_alive(x, y));
+            }
+        }
+        result
+    }
+
+    /// 顺时针旋转 90° 后的图案（宽高互换）
+    pub fn rotated_clockwise(&self) -> Self {
+        let mut result = Self::new(self.height, self.width);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                result.set(self.height - 1 - y, x, self.is_alive(x, y));
+            }
+        }
+        result
+    }
+
+    /// 遍历所有细胞（包括死细胞），参数为相对左上角的坐标和是否存活
+    pub fn for_each_cell(&self, mut visit: impl FnMut(usize, usize, bool)) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                visit(x, y, self.cells[y * self.width + x]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::save_load;
+
+    fn glider() -> Stamp {
+        Stamp::from_lines(&[".*.", "..*", "***"])
+    }
+
+    fn rows(stamp: &Stamp) -> Vec<String> {
+        (0..stamp.height())
+            .map(|y| {
+                (0..stamp.width())
+                    .map(|x| if stamp.is_alive(x, y) { '*' } else { '.' })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_stamp_modes() {
+        let table = [(false, false), (false, true), (true, false), (true, true)];
+        let expected = [
+            (StampMode::Or, [false, true, true, true]),
+            (StampMode::Xor, [false, true, true, false]),
+            (StampMode::AndNot, [false, false, true, false]),
+            (StampMode::Replace, [false, true, false, true]),
+        ];
+        for (mode, results) in expected {
+            for ((existing, stamp), result) in table.iter().zip(results) {
+                assert_eq!(mode.apply(*existing, *stamp), result, "{}", mode.name());
+            }
+        }
+    }
+
+    #[test]
+    fn test_rotation_and_reflection() {
+        let stamp = Stamp::from_lines(&["**.", "*.."]);
+        let rotate = |rotation| Transform {
+            rotation,
+            reflect: false,
+        };
+        assert_eq!(
+            rows(&stamp.transformed(rotate(Rotation::Clockwise90))),
+            ["**", ".*", ".."]
+        );
+        assert_eq!(
+            rows(&stamp.transformed(rotate(Rotation::Clockwise180))),
+            ["..*", ".**"]
+        );
+        assert_eq!(
+            rows(&stamp.transformed(rotate(Rotation::Clockwise270))),
+            ["..", "*.", "**"]
+        );
+        let mirrored = Transform {
+            rotation: Rotation::None,
+            reflect: true,
+        };
+        assert_eq!(rows(&stamp.transformed(mirrored)), [".**", "..*"]);
+        assert_eq!(stamp.transformed(mirrored).population(), stamp.population());
+    }
+
+    #[test]
+    fn test_rle_round_trip() {
+        let stamp = glider();
+        let text = save_load::export_to_rle_string(&stamp.to_rle(&Rule::conway()));
+        let pattern = save_load::import_from_rle_string(&text).unwrap();
+        assert_eq!(Stamp::from_rle(&pattern), stamp);
+    }
+
+    #[test]
+    fn test_transform_composition() {
+        // 逐步组合的变换应与直接对图案依次操作的结果一致
+        let stamp = glider();
+        let mut transform = Transform::default();
+        let mut expected = stamp.clone();
+
+        transform.rotate_clockwise();
+        expected = expected.rotated_clockwise();
+        assert_eq!(stamp.transformed(transform), expected);
+
+        transform.flip_horizontal();
+        expected = expected.flipped_horizontal();
+        assert_eq!(stamp.transformed(transform), expected);
+
+        transform.flip_vertical();
+        expected = expected.flipped_vertical();
+        assert_eq!(stamp.transformed(transform), expected);
+
+        transform.rotate_counterclockwise();
+        for _ in 0..3 {
+            expected = expected.rotated_clockwise();
+        }
+        assert_eq!(stamp.transformed(transform), expected);
+    }
+}
@@ -37,6 +37,13 @@ impl PopulationStatistics {
         self.history.clear();
     }
 
+    /// 用给定的人口序列替换历史（只保留最后的指定长度），用于时间线跳转后同步
+    pub fn set_history(&mut self, populations: impl IntoIterator<Item = usize>) {
+        self.history = populations.into_iter().collect();
+        let excess = self.history.len().saturating_sub(self.max_history_length);
+        self.history.drain(..excess);
+    }
+
     /// 获取人口历史记录的引用
     pub fn get_history(&self) -> &Vec<usize> {
         &self.history
@@ -47,7 +54,6 @@ impl PopulationStatistics {
         self.history.len()
     }
     /// 获取当前人口数（最后一个数据点）
-    #[allow(dead_code)]
     pub fn get_current_population(&self) -> Option<usize> {
         self.history.last().copied()
     }
@@ -181,6 +187,14 @@ mod tests {
         assert!(!stats.has_data());
     }
 
+    #[test]
+    fn test_set_history() {
+        let mut stats = PopulationStatistics::new(3);
+        stats.add_population(99);
+        stats.set_history(1..=5);
+        assert_eq!(*stats.get_history(), vec![3, 4, 5]);
+    }
+
     #[test]
     fn test_average_population() {
         let mut stats = PopulationStatistics::new(10);
@@ -0,0 +1,353 @@
+//! 时间线模块
+//! 保存最近若干代的网格，定期存一个关键帧，其余各代只存与上一代的差异，
+//! 可以跳回任意一个记录过的代数
+
+use crate::game::{Grid, GridDiff, GridSnapshot};
+use std::collections::VecDeque;
+
+/// 默认保存的代数
+pub const DEFAULT_TIMELINE_LENGTH: usize = 1000;
+
+/// 两个关键帧之间最多的差异帧数
+const KEYFRAME_INTERVAL: usize = 32;
+
+/// 帧的内容
+enum FrameData {
+    /// 完整的细胞快照
+    Keyframe(GridSnapshot),
+    /// 与上一帧的差异
+    Delta(GridDiff),
+}
+
+/// 时间线中的一帧
+struct Frame {
+    /// 该帧的代数
+    generation: u64,
+    /// 记录时窗口左上角在无界宇宙中的位置
+    origin: (i64, i64),
+    /// 该帧的人口
+    population: usize,
+    /// 记录时网格的修订号，用于判断网格是否仍停留在该帧
+    revision: u64,
+    data: FrameData,
+}
+
+impl Frame {
+    /// 估算占用的内存（字节）
+    fn memory_usage(&self) -> usize {
+        match &self.data {
+            FrameData::Keyframe(snapshot) => snapshot.memory_usage(),
+            FrameData::Delta(diff) => diff.memory_usage(),
+        }
+    }
+}
+
+/// 最近若干代的模拟时间线
+pub struct Timeline {
+    /// 按代数递增排列的帧，第一帧总是关键帧
+    frames: VecDeque<Frame>,
+    /// 最多保存的帧数
+    capacity: usize,
+    /// 最后一帧的快照，用于计算下一帧的差异
+    head: Option<GridSnapshot>,
+    /// 最近一次跳转的位置：(帧序号, 跳转后网格的修订号)
+    position: Option<(usize, u64)>,
+}
+
+impl Timeline {
+    /// 创建最多保存 `capacity` 帧的空时间线
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            frames: VecDeque::new(),
+            capacity: capacity.max(1),
+            head: None,
+            position: None,
+        }
+    }
+
+    /// 记录网格当前的状态，`origin` 是窗口左上角在无界宇宙中的位置
+    ///
+    /// 网格与最后一帧相同时不做任何事。如果网格停留在跳转到的帧上，丢弃该帧之后的帧，
+    /// 从这里开始新的分支；否则丢弃代数不小于 `generation` 的帧（它们已被编辑或撤销覆盖）
+    pub fn record(
+        &mut self,
+        grid: &Grid,
+        generation: u64,
+        origin: (i64, i64),
+        population: usize,
+    ) {
+        if self.frames.back().is_some_and(|frame| frame.revision == grid.revision()) {
+            return;
+        }
+
+        if let Some((index, revision)) = self.position.take() {
+            if revision == grid.revision() && index < self.frames.len() {
+                self.frames.truncate(index + 1);
+                if let Some(frame) = self.frames.back_mut() {
+                    frame.revision = revision;
+                }
+                self.head = Some(grid.snapshot());
+                return;
+            }
+        }
+
+        let keep = self
+            .frames
+            .iter()
+            .position(|frame| frame.generation >= generation)
+            .unwrap_or(self.frames.len());
+        if keep < self.frames.len() {
+            self.frames.truncate(keep);
+            self.head = keep.checked_sub(1).and_then(|last| self.snapshot_at(last));
+        }
+
+        let delta = match &self.head {
+            Some(head) if self.frames_since_keyframe() < KEYFRAME_INTERVAL => head.diff(grid),
+            _ => None,
+        };
+        let data = match delta {
+            Some(diff) => FrameData::Delta(diff),
+            None => FrameData::Keyframe(grid.snapshot()),
+        };
+        self.frames.push_back(Frame {
+            generation,
+            origin,
+            population,
+            revision: grid.revision(),
+            data,
+        });
+        self.head = Some(grid.snapshot());
+
+        while self.frames.len() > self.capacity {
+            self.evict_front();
+        }
+    }
+
+    /// 最后一帧之前连续的差异帧数
+    fn frames_since_keyframe(&self) -> usize {
+        self.frames
+            .iter()
+            .rev()
+            .take_while(|frame| matches!(frame.data, FrameData::Delta(_)))
+            .count()
+    }
+
+    /// 丢弃最早的一帧，并把下一帧转换为关键帧
+    fn evict_front(&mut self) {
+        let front = match self.frames.pop_front() {
+            Some(frame) => frame,
+            None => return,
+        };
+        if let (FrameData::Keyframe(mut snapshot), Some(next)) =
+            (front.data, self.frames.front_mut())
+        {
+            if let FrameData::Delta(diff) = &next.data {
+                snapshot.apply_diff(diff);
+                next.data = FrameData::Keyframe(snapshot);
+            }
+        }
+        self.position = match self.position {
+            Some((index, revision)) if index > 0 => Some((index - 1, revision)),
+            _ => None,
+        };
+    }
+
+    /// 重建第 `index` 帧的网格
+    pub fn grid_at(&self, index: usize) -> Option<Grid> {
+        let snapshot = self.snapshot_at(index)?;
+        let mut grid = Grid::new(0, 0);
+        grid.restore(&snapshot);
+        Some(grid)
+    }
+
+    /// 重建第 `index` 帧的细胞快照
+    fn snapshot_at(&self, index: usize) -> Option<GridSnapshot> {
+        if index >= self.frames.len() {
+            return None;
+        }
+        let start = (0..=index)
+            .rev()
+            .find(|&i| matches!(self.frames[i].data, FrameData::Keyframe(_)))?;
+        let mut snapshot = match &self.frames[start].data {
+            FrameData::Keyframe(snapshot) => snapshot.clone(),
+            FrameData::Delta(_) => return None,
+        };
+        for frame in self.frames.range(start + 1..=index) {
+            if let FrameData::Delta(diff) = &frame.data {
+                snapshot.apply_diff(diff);
+            }
+        }
+        Some(snapshot)
+    }
+
+    /// 把网格恢复到第 `index` 帧，保留网格的线程设置
+    ///
+    /// # 返回值
+    /// 返回该帧的代数和窗口位置；序号无效时返回 `None`，网格保持不变
+    pub fn jump(&mut self, index: usize, grid: &mut Grid) -> Option<(u64, (i64, i64))> {
+        grid.restore(&self.snapshot_at(index)?);
+        self.position = Some((index, grid.revision()));
+        let frame = &self.frames[index];
+        Some((frame.generation, frame.origin))
+    }
+
+    /// 网格当前所在的帧；网格在记录之后被修改过时返回 `None`
+    pub fn current_index(&self, grid: &Grid) -> Option<usize> {
+        match self.position {
+            Some((index, revision)) if revision == grid.revision() => Some(index),
+            _ => self
+                .frames
+                .back()
+                .filter(|frame| frame.revision == grid.revision())
+                .map(|_| self.frames.len() - 1),
+        }
+    }
+
+    /// 第 `index` 帧的代数
+    pub fn generation_at(&self, index: usize) -> Option<u64> {
+        self.frames.get(index).map(|frame| frame.generation)
+    }
+
+    /// 从第一帧到第 `index` 帧（包括）的人口
+    pub fn populations(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        self.frames
+            .iter()
+            .take(index.saturating_add(1))
+            .map(|frame| frame.population)
+    }
+
+    /// 记录的帧数
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// 时间线是否为空
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// 最多保存的帧数
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// 设置最多保存的帧数，超出时立即丢弃最早的帧
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        while self.frames.len() > self.capacity {
+            self.evict_front();
+        }
+    }
+
+    /// 所有帧占用的内存（字节）
+    pub fn memory_usage(&self) -> usize {
+        self.frames.iter().map(Frame::memory_usage).sum()
+    }
+}
+
+impl Default for Timeline {
+    fn default() -> Self {
+        Self::new(DEFAULT_TIMELINE_LENGTH)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::CellState;
+
+    /// 从一个滑翔机开始记录 `steps` 代，返回每一代的网格
+    fn record_glider(timeline: &mut Timeline, steps: u64) -> Vec<Grid> {
+        let mut grid = Grid::new(20, 20);
+        grid.load_pattern(&[".O.", "..O", "OOO"], 5, 5);
+        let mut grids = Vec::new();
+        for generation in 0..=steps {
+            if generation > 0 {
+                grid.next_generation();
+            }
+            timeline.record(&grid, generation, (0, 0), grid.count_alive_cells());
+            grids.push(grid.clone());
+        }
+        grids
+    }
+
+    /// 比较两个网格的细胞
+    fn same_cells(a: &Grid, b: &Grid) -> bool {
+        a.diff(b).is_some_and(|diff| diff.is_empty())
+    }
+
+    #[test]
+    fn test_reconstruct_every_frame() {
+        let mut timeline = Timeline::default();
+        let grids = record_glider(&mut timeline, 80);
+        assert_eq!(timeline.len(), 81);
+        for (index, expected) in grids.iter().enumerate() {
+            let grid = timeline.grid_at(index).unwrap();
+            assert!(same_cells(&grid, expected), "frame {}", index);
+            assert_eq!(timeline.generation_at(index), Some(index as u64));
+        }
+        assert!(timeline.populations(20).all(|population| population == 5));
+    }
+
+    #[test]
+    fn test_capacity_drops_oldest_frames() {
+        let mut timeline = Timeline::new(10);
+        let grids = record_glider(&mut timeline, 50);
+        assert_eq!(timeline.len(), 10);
+        assert_eq!(timeline.generation_at(0), Some(41));
+        for index in 0..10 {
+            let grid = timeline.grid_at(index).unwrap();
+            assert!(same_cells(&grid, &grids[41 + index]));
+        }
+
+        timeline.set_capacity(3);
+        assert_eq!(timeline.generation_at(0), Some(48));
+        assert!(same_cells(&timeline.grid_at(0).unwrap(), &grids[48]));
+
+        // 关键帧只保存细胞，不包含网格的演化缓冲区
+        timeline.set_capacity(1);
+        assert!(timeline.memory_usage() < grids[50].memory_usage());
+    }
+
+    #[test]
+    fn test_jump_and_branch() {
+        let mut timeline = Timeline::default();
+        let grids = record_glider(&mut timeline, 20);
+        let mut grid = grids[20].clone();
+        grid.set_threads(3);
+        assert_eq!(timeline.current_index(&grid), Some(20));
+
+        assert_eq!(timeline.jump(5, &mut grid), Some((5, (0, 0))));
+        assert!(same_cells(&grid, &grids[5]));
+        assert_eq!(grid.threads(), 3);
+        assert_eq!(timeline.current_index(&grid), Some(5));
+
+        // 从跳转的位置继续演化会丢弃之后的帧
+        timeline.record(&grid, 5, (0, 0), 5);
+        grid.next_generation();
+        timeline.record(&grid, 6, (0, 0), 5);
+        assert_eq!(timeline.len(), 7);
+        assert_eq!(timeline.current_index(&grid), Some(6));
+    }
+
+    #[test]
+    fn test_edit_replaces_later_frames() {
+        let mut timeline = Timeline::default();
+        let grids = record_glider(&mut timeline, 10);
+        let mut grid = grids[10].clone();
+
+        // 在第10代编辑网格后记录，第10代的旧帧被替换
+        grid.set_cell(0, 0, CellState::Alive);
+        assert_eq!(timeline.current_index(&grid), None);
+        timeline.record(&grid, 10, (0, 0), 6);
+        assert_eq!(timeline.len(), 11);
+        assert!(timeline.grid_at(10).unwrap().is_alive(0, 0));
+        assert!(same_cells(&timeline.grid_at(9).unwrap(), &grids[9]));
+
+        // 尺寸变化时记录一个新的关键帧
+        let resized = Grid::new(30, 30);
+        timeline.record(&resized, 11, (0, 0), 0);
+        assert_eq!(timeline.grid_at(11).unwrap().width(), 30);
+        assert!(timeline.memory_usage() > 0);
+    }
+}
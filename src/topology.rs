@@ -0,0 +1,319 @@
+//! 网格边界拓扑模块
+//! 负责描述网格边缘如何相接，以及与 Golly 规则后缀（如 `:T60,40`）之间的转换
+
+/// 拓扑后缀中允许声明的最大网格面积（细胞数），与转换为稠密图案时的上限相同
+pub const MAX_SUFFIX_CELLS: u64 = crate::macrocell::MAX_DENSE_CELLS;
+
+/// 克莱因瓶中带扭转的一对边
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum KleinTwist {
+    /// 上下边缘扭转相接（Golly 中写作 `K60*,40`）
+    TopBottom,
+    /// 左右边缘扭转相接（Golly 中写作 `K60,40*`）
+    LeftRight,
+}
+
+/// 网格边界拓扑
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Default)]
+pub enum Topology {
+    /// 有界平面：超出边缘的邻居视为死亡细胞
+    #[default]
+    Bounded,
+    /// 环面：左右、上下边缘分别直接相接
+    Torus,
+    /// 克莱因瓶：一对边直接相接，另一对边扭转相接
+    KleinBottle(KleinTwist),
+    /// 交叉曲面（射影平面）：两对边都扭转相接
+    CrossSurface,
+    /// 球面：上边缘与左边缘相接，下边缘与右边缘相接（要求正方形网格）
+    Sphere,
+}
+
+impl Topology {
+    /// 所有可选的拓扑，用于界面列表
+    pub const ALL: [Topology; 6] = [
+        Topology::Bounded,
+        Topology::Torus,
+        Topology::KleinBottle(KleinTwist::TopBottom),
+        Topology::KleinBottle(KleinTwist::LeftRight),
+        Topology::CrossSurface,
+        Topology::Sphere,
+    ];
+
+    /// 拓扑的显示名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            Topology::Bounded => "Bounded",
+            Topology::Torus => "Torus",
+            Topology::KleinBottle(KleinTwist::TopBottom) => "Klein Bottle (top/bottom twist)",
+            Topology::KleinBottle(KleinTwist::LeftRight) => "Klein Bottle (left/right twist)",
+            Topology::CrossSurface => "Cross-Surface",
+            Topology::Sphere => "Sphere",
+        }
+    }
+
+    /// 该拓扑是否要求正方形网格
+    pub fn requires_square(&self) -> bool {
+        matches!(self, Topology::Sphere)
+    }
+
+    /// 将可能越界的坐标映射回网格内部
+    ///
+    /// # 参数
+    /// * `x`, `y` - 可能位于网格之外的坐标
+    /// * `width`, `height` - 网格尺寸
+    ///
+    /// # 返回值
+    /// 返回对应的网格内坐标；有界平面上越界的坐标返回 `None`
+    pub fn wrap(
+        &self,
+        mut x: i64,
+        mut y: i64,
+        width: usize,
+        height: usize,
+    ) -> Option<(usize, usize)> {
+        let (w, h) = (width as i64, height as i64);
+        if w == 0 || h == 0 {
+            return None;
+        }
+
+        // 每次折叠一个方向；角落处的坐标最多需要折叠几次
+        for _ in 0..4 {
+            let x_out = x < 0 || x >= w;
+            let y_out = y < 0 || y >= h;
+            if !x_out && !y_out {
+                return Some((x as usize, y as usize));
+            }
+
+            match self {
+                Topology::Bounded => return None,
+                Topology::Torus => {
+                    x = x.rem_euclid(w);
+                    y = y.rem_euclid(h);
+                }
+                Topology::KleinBottle(_) | Topology::CrossSurface => {
+                    // 穿过左右边缘时是否上下翻转，穿过上下边缘时是否左右翻转
+                    let twist_left_right = matches!(
+                        self,
+                        Topology::CrossSurface | Topology::KleinBottle(KleinTwist::LeftRight)
+                    );
+                    let twist_top_bottom = matches!(
+                        self,
+                        Topology::CrossSurface | Topology::KleinBottle(KleinTwist::TopBottom)
+                    );
+                    if x_out {
+                        x = x.rem_euclid(w);
+                        if twist_left_right {
+                            y = h - 1 - y;
+                        }
+                    } else {
+                        y = y.rem_euclid(h);
+                        if twist_top_bottom {
+                            x = w - 1 - x;
+                        }
+                    }
+                }
+                Topology::Sphere if x_out => {
+                    // 左边缘与上边缘相接，右边缘与下边缘相接
+                    (x, y) = if x < 0 { (y, -x - 1) } else { (y, h - (x - w + 1)) };
+                }
+                Topology::Sphere => {
+                    (x, y) = if y < 0 { (-y - 1, x) } else { (w - (y - h + 1), x) };
+                }
+            }
+        }
+        None
+    }
+
+    /// 生成 Golly 风格的规则后缀（不含冒号），有界平面返回 `None`
+    ///
+    /// 例如环面返回 `T60,40`，球面返回 `S60`
+    pub fn to_golly_suffix(self, width: usize, height: usize) -> Option<String> {
+        match self {
+            Topology::Bounded => None,
+            Topology::Torus => Some(format!("T{},{}", width, height)),
+            Topology::KleinBottle(KleinTwist::TopBottom) => {
+                Some(format!("K{}*,{}", width, height))
+            }
+            Topology::KleinBottle(KleinTwist::LeftRight) => {
+                Some(format!("K{},{}*", width, height))
+            }
+            Topology::CrossSurface => Some(format!("C{},{}", width, height)),
+            Topology::Sphere => Some(format!("S{}", width)),
+        }
+    }
+
+    /// 解析 Golly 风格的规则后缀（不含冒号），如 `T60,40`、`K60*,40`、`S60`
+    ///
+    /// # 返回值
+    /// 返回拓扑以及后缀中声明的网格尺寸（如果有）；面积超过 [`MAX_SUFFIX_CELLS`] 时返回错误
+    pub fn parse_golly_suffix(suffix: &str) -> Result<(Topology, Option<(usize, usize)>), String> {
+        let suffix = suffix.trim();
+        let mut chars = suffix.chars();
+        let kind = chars
+            .next()
+            .ok_or_else(|| "Empty topology suffix".to_string())?
+            .to_ascii_uppercase();
+        let dims = chars.as_str();
+
+        // 解析单个尺寸，返回 (尺寸, 是否带扭转标记 '*')
+        let parse_dim = |text: &str| -> Result<(usize, bool), String> {
+            let (digits, twisted) = match text.strip_suffix('*') {
+                Some(digits) => (digits, true),
+                None => (text, false),
+            };
+            let size: usize = digits
+                .trim()
+                .parse()
+                .map_err(|_| format!("Invalid grid size '{}' in topology {}", text, suffix))?;
+            if size == 0 {
+                return Err(format!("Unbounded dimensions are not supported: {}", suffix));
+            }
+            Ok((size, twisted))
+        };
+        // 拒绝过大的网格，避免按文件声明的尺寸分配巨大的内存
+        let check_area = |width: usize, height: usize| -> Result<(), String> {
+            if (width as u64).saturating_mul(height as u64) > MAX_SUFFIX_CELLS {
+                return Err(format!(
+                    "Grid size {}x{} in topology {} exceeds the maximum of {} cells",
+                    width, height, suffix, MAX_SUFFIX_CELLS
+                ));
+            }
+            Ok(())
+        };
+
+        if kind == 'S' {
+            if dims.is_empty() {
+                return Ok((Topology::Sphere, None));
+            }
+            let (size, _) = parse_dim(dims)?;
+            check_area(size, size)?;
+            return Ok((Topology::Sphere, Some((size, size))));
+        }
+
+        let size = if dims.is_empty() {
+            None
+        } else {
+            let (w, h) = dims
+                .split_once(',')
+                .ok_or_else(|| format!("Expected <width>,<height> in topology {}", suffix))?;
+            let (w, h) = (parse_dim(w)?, parse_dim(h)?);
+            check_area(w.0, h.0)?;
+            Some((w, h))
+        };
+        let twist = match size {
+            Some(((_, true), _)) => Some(KleinTwist::TopBottom),
+            Some((_, (_, true))) => Some(KleinTwist::LeftRight),
+            _ => None,
+        };
+
+        let topology = match kind {
+            'P' => Topology::Bounded,
+            'T' => Topology::Torus,
+            'K' => Topology::KleinBottle(twist.unwrap_or(KleinTwist::TopBottom)),
+            'C' => Topology::CrossSurface,
+            _ => return Err(format!("Unknown topology '{}' in {}", kind, suffix)),
+        };
+        if twist.is_some() && !matches!(topology, Topology::KleinBottle(_)) {
+            return Err(format!("Twist marker '*' is only valid for Klein bottles: {}", suffix));
+        }
+
+        Ok((topology, size.map(|((w, _), (h, _))| (w, h))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounded_wrap() {
+        let topology = Topology::Bounded;
+        assert_eq!(topology.wrap(2, 3, 5, 5), Some((2, 3)));
+        assert_eq!(topology.wrap(-1, 0, 5, 5), None);
+        assert_eq!(topology.wrap(0, 5, 5, 5), None);
+    }
+
+    #[test]
+    fn test_torus_wrap() {
+        let topology = Topology::Torus;
+        assert_eq!(topology.wrap(-1, 0, 6, 4), Some((5, 0)));
+        assert_eq!(topology.wrap(6, 4, 6, 4), Some((0, 0)));
+        assert_eq!(topology.wrap(-1, -1, 6, 4), Some((5, 3)));
+    }
+
+    #[test]
+    fn test_klein_bottle_wrap() {
+        // 上下扭转：穿过上边缘时左右镜像
+        let top_bottom = Topology::KleinBottle(KleinTwist::TopBottom);
+        assert_eq!(top_bottom.wrap(1, -1, 6, 4), Some((4, 3)));
+        assert_eq!(top_bottom.wrap(-1, 1, 6, 4), Some((5, 1)));
+
+        // 左右扭转：穿过左边缘时上下镜像
+        let left_right = Topology::KleinBottle(KleinTwist::LeftRight);
+        assert_eq!(left_right.wrap(-1, 0, 6, 4), Some((5, 3)));
+        assert_eq!(left_right.wrap(1, 4, 6, 4), Some((1, 0)));
+    }
+
+    #[test]
+    fn test_cross_surface_wrap() {
+        let topology = Topology::CrossSurface;
+        assert_eq!(topology.wrap(-1, 0, 6, 4), Some((5, 3)));
+        assert_eq!(topology.wrap(1, -1, 6, 4), Some((4, 3)));
+        assert_eq!(topology.wrap(-1, -1, 6, 4), Some((0, 0)));
+    }
+
+    #[test]
+    fn test_sphere_wrap() {
+        let topology = Topology::Sphere;
+        // 上边缘之外的格子对应左边缘上的格子
+        assert_eq!(topology.wrap(2, -1, 5, 5), Some((0, 2)));
+        assert_eq!(topology.wrap(-1, 2, 5, 5), Some((2, 0)));
+        // 下边缘之外的格子对应右边缘上的格子
+        assert_eq!(topology.wrap(2, 5, 5, 5), Some((4, 2)));
+        assert_eq!(topology.wrap(5, 2, 5, 5), Some((2, 4)));
+    }
+
+    #[test]
+    fn test_golly_suffix_round_trip() {
+        for topology in Topology::ALL {
+            let (width, height) = if topology.requires_square() { (30, 30) } else { (60, 40) };
+            match topology.to_golly_suffix(width, height) {
+                Some(suffix) => {
+                    let parsed = Topology::parse_golly_suffix(&suffix).unwrap();
+                    assert_eq!(parsed, (topology, Some((width, height))));
+                }
+                None => assert_eq!(topology, Topology::Bounded),
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_golly_suffix() {
+        assert_eq!(
+            Topology::parse_golly_suffix("T60,40").unwrap(),
+            (Topology::Torus, Some((60, 40)))
+        );
+        assert_eq!(
+            Topology::parse_golly_suffix("K20,30*").unwrap(),
+            (Topology::KleinBottle(KleinTwist::LeftRight), Some((20, 30)))
+        );
+        assert_eq!(
+            Topology::parse_golly_suffix("P50,50").unwrap(),
+            (Topology::Bounded, Some((50, 50)))
+        );
+        assert_eq!(Topology::parse_golly_suffix("T").unwrap(), (Topology::Torus, None));
+
+        assert!(Topology::parse_golly_suffix("").is_err());
+        assert!(Topology::parse_golly_suffix("X10,10").is_err());
+        assert!(Topology::parse_golly_suffix("T0,40").is_err());
+        assert!(Topology::parse_golly_suffix("T60*,40").is_err());
+        assert!(Topology::parse_golly_suffix("T60").is_err());
+
+        // 面积上限
+        assert!(Topology::parse_golly_suffix("T8192,8192").is_ok());
+        assert!(Topology::parse_golly_suffix("T8192,8193").is_err());
+        assert!(Topology::parse_golly_suffix("T4000000000,4000000000").is_err());
+        assert!(Topology::parse_golly_suffix("S100000").is_err());
+    }
+}
@@ -1,6 +1,45 @@
 /// UI状态管理模块
 /// 负责管理用户界面的各种状态信息
-use crate::game::CellState;
+use game_of_life::game::CellState;
+use game_of_life::shapes::Shape;
+
+/// 鼠标在网格上使用的编辑工具
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Tool {
+    /// 点击切换细胞，拖动用笔刷绘制
+    #[default]
+    Draw,
+    /// 拖动确定两个角，松开时绘制图形
+    Shape(Shape),
+    /// 点击填充连通区域
+    FloodFill,
+    /// 拖出矩形选区
+    Select,
+}
+
+impl Tool {
+    /// 所有工具，用于工具面板
+    pub const ALL: [Tool; 8] = [
+        Tool::Draw,
+        Tool::Shape(Shape::Line),
+        Tool::Shape(Shape::Rectangle),
+        Tool::Shape(Shape::FilledRectangle),
+        Tool::Shape(Shape::Ellipse),
+        Tool::Shape(Shape::FilledEllipse),
+        Tool::FloodFill,
+        Tool::Select,
+    ];
+
+    /// 工具的显示名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tool::Draw => "Draw",
+            Tool::Shape(shape) => shape.name(),
+            Tool::FloodFill => "Flood Fill",
+            Tool::Select => "Select",
+        }
+    }
+}
 
 /// UI状态管理器
 #[derive(Clone, Debug)]
@@ -9,12 +48,26 @@ pub struct UiStateManager {
     cell_size: f32,
     /// 缩放级别（1.0为默认大小）
     zoom_level: f32,
+    /// 视口平移偏移：网格左上角相对视口左上角的屏幕位置（像素）
+    view_offset: egui::Vec2,
+    /// 最近一次绘制时视口的大小（像素），用于适应窗口等命令
+    viewport_size: egui::Vec2,
+    /// 按住空格拖动时是否发生了平移（此时松开空格不切换运行状态）
+    space_panned: bool,
     /// 是否显示网格线
     show_grid_lines: bool,
     /// 跟踪是否正在拖动绘制
     is_dragging: bool,
     /// 拖动时绘制的细胞状态（存活或死亡）
     drag_state: Option<CellState>,
+    /// 当前的编辑工具
+    tool: Tool,
+    /// 拖出选区或图形时起点所在的细胞
+    drag_anchor: Option<(usize, usize)>,
+    /// 拖动过程中鼠标最近所在的细胞
+    last_drag_cell: Option<(usize, usize)>,
+    /// 自由绘制时方形笔刷的边长（细胞数）
+    brush_size: usize,
     /// 保存/加载状态信息
     status_message: Option<String>,
     /// 状态信息显示的时间戳
@@ -27,9 +80,16 @@ impl UiStateManager {
         Self {
             cell_size: 10.0,
             zoom_level: 1.0,
+            view_offset: egui::Vec2::ZERO,
+            viewport_size: egui::Vec2::ZERO,
+            space_panned: false,
             show_grid_lines: true,
             is_dragging: false,
             drag_state: None,
+            tool: Tool::default(),
+            drag_anchor: None,
+            last_drag_cell: None,
+            brush_size: 1,
             status_message: None,
             status_timestamp: None,
         }
@@ -44,7 +104,7 @@ impl UiStateManager {
     /// 设置细胞大小
     #[allow(dead_code)]
     pub fn set_cell_size(&mut self, size: f32) {
-        self.cell_size = size.max(1.0).min(50.0); // 限制在合理范围内
+        self.cell_size = size.clamp(1.0, 50.0); // 限制在合理范围内
     }
 
     /// 获取缩放级别
@@ -63,10 +123,64 @@ impl UiStateManager {
     }
 
     /// 处理缩放操作
-    pub fn handle_zoom(&mut self, delta: f32, _mouse_pos: Option<egui::Pos2>) {
-        let old_zoom = self.zoom_level;
-        self.set_zoom_level(old_zoom + delta * 1.0);
-        // 可以在这里添加基于鼠标位置的智能缩放中心点
+    ///
+    /// `mouse_pos` 是鼠标相对视口左上角的位置，缩放时保持其下方的细胞不动；
+    /// 没有鼠标位置时以视口中心为锚点
+    pub fn handle_zoom(&mut self, delta: f32, mouse_pos: Option<egui::Pos2>) {
+        let anchor = mouse_pos.map_or(self.viewport_size / 2.0, |pos| pos.to_vec2());
+        self.zoom_around(self.zoom_level + delta, anchor);
+    }
+
+    /// 缩放到指定级别，保持视口中 `anchor`（相对视口左上角）处的点不动
+    pub fn zoom_around(&mut self, zoom: f32, anchor: egui::Vec2) {
+        let old_size = self.effective_cell_size();
+        self.set_zoom_level(zoom);
+        let scale = self.effective_cell_size() / old_size;
+        self.view_offset = anchor - (anchor - self.view_offset) * scale;
+    }
+
+    /// 获取视口平移偏移
+    pub fn view_offset(&self) -> egui::Vec2 {
+        self.view_offset
+    }
+
+    /// 平移视口（像素）
+    pub fn pan(&mut self, delta: egui::Vec2) {
+        self.view_offset += delta;
+    }
+
+    /// 恢复默认缩放，网格左上角回到视口左上角
+    pub fn reset_view(&mut self) {
+        self.set_zoom_level(1.0);
+        self.view_offset = egui::Vec2::ZERO;
+    }
+
+    /// 记录视口大小（每帧绘制网格时调用）
+    pub fn set_viewport_size(&mut self, size: egui::Vec2) {
+        self.viewport_size = size;
+    }
+
+    /// 缩放并平移，使以细胞为单位的矩形 (x, y, 宽, 高) 居中并完整显示在视口中（留出少量边距）
+    pub fn fit_cells(&mut self, (x, y, width, height): (usize, usize, usize, usize)) {
+        let size = self.viewport_size;
+        if size.x <= 0.0 || size.y <= 0.0 {
+            return;
+        }
+        let (width, height) = (width.max(1) as f32, height.max(1) as f32);
+        let cell_size = (size.x / width).min(size.y / height) * 0.9;
+        self.set_zoom_level(cell_size / self.cell_size);
+        let center = egui::Vec2::new(x as f32 + width / 2.0, y as f32 + height / 2.0);
+        self.view_offset = size / 2.0 - center * self.effective_cell_size();
+    }
+
+    /// 记录按住空格时发生了平移
+    pub fn set_space_panned(&mut self) {
+        self.space_panned = true;
+    }
+
+    /// 取出并清除"按住空格时发生了平移"的标记
+    pub fn take_space_panned(&mut self) -> bool {
+        std::mem::take(&mut self.space_panned)
     }
 
     /// 获取网格线显示状态
@@ -102,6 +216,48 @@ impl UiStateManager {
         self.drag_state = Some(state);
     }
 
+    /// 获取当前的编辑工具
+    pub fn tool(&self) -> Tool {
+        self.tool
+    }
+
+    /// 切换编辑工具
+    pub fn set_tool(&mut self, tool: Tool) {
+        self.tool = tool;
+        self.drag_anchor = None;
+        self.last_drag_cell = None;
+    }
+
+    /// 拖出选区或图形时的起点细胞
+    pub fn drag_anchor(&self) -> Option<(usize, usize)> {
+        self.drag_anchor
+    }
+
+    /// 设置（或清除）拖动的起点细胞
+    pub fn set_drag_anchor(&mut self, anchor: Option<(usize, usize)>) {
+        self.drag_anchor = anchor;
+    }
+
+    /// 拖动过程中鼠标最近所在的细胞
+    pub fn last_drag_cell(&self) -> Option<(usize, usize)> {
+        self.last_drag_cell
+    }
+
+    /// 记录（或清除）拖动过程中鼠标最近所在的细胞
+    pub fn set_last_drag_cell(&mut self, cell: Option<(usize, usize)>) {
+        self.last_drag_cell = cell;
+    }
+
+    /// 获取笔刷边长
+    pub fn brush_size(&self) -> usize {
+        self.brush_size
+    }
+
+    /// 设置笔刷边长
+    pub fn set_brush_size(&mut self, size: usize) {
+        self.brush_size = size.clamp(1, 16); // 限制在合理范围内
+    }
+
     /// 设置状态信息
     pub fn set_status(&mut self, message: String) {
         self.status_message = Some(message);
@@ -168,6 +324,41 @@ mod tests {
         assert_eq!(ui_state.zoom_level(), 0.1); // 应该被限制到最小值
     }
 
+    #[test]
+    fn test_zoom_keeps_cell_under_cursor() {
+        let mut ui_state = UiStateManager::new();
+        ui_state.pan(egui::vec2(-35.0, 12.0));
+        let cursor = egui::pos2(200.0, 150.0);
+        let cell_under = |state: &UiStateManager| {
+            (cursor.to_vec2() - state.view_offset()) / state.effective_cell_size()
+        };
+        let before = cell_under(&ui_state);
+
+        ui_state.handle_zoom(1.5, Some(cursor));
+        assert_eq!(ui_state.zoom_level(), 2.5);
+        let after = cell_under(&ui_state);
+        assert!((before - after).length() < 1e-3, "{:?} {:?}", before, after);
+
+        // 达到缩放上限后锚点仍然不动
+        ui_state.handle_zoom(10.0, Some(cursor));
+        assert!((cell_under(&ui_state) - before).length() < 1e-3);
+    }
+
+    #[test]
+    fn test_fit_cells_centres_region() {
+        let mut ui_state = UiStateManager::new();
+        ui_state.set_viewport_size(egui::vec2(400.0, 300.0));
+        ui_state.fit_cells((10, 20, 40, 10));
+        // 宽度是限制因素：400 / 40 * 0.9 = 9 像素每细胞
+        assert!((ui_state.effective_cell_size() - 9.0).abs() < 1e-4);
+        let centre = ui_state.view_offset() + egui::vec2(30.0, 25.0) * ui_state.effective_cell_size();
+        assert!((centre - egui::vec2(200.0, 150.0)).length() < 1e-3);
+
+        ui_state.reset_view();
+        assert_eq!(ui_state.zoom_level(), 1.0);
+        assert_eq!(ui_state.view_offset(), egui::Vec2::ZERO);
+    }
+
     #[test]
     fn test_cell_size_limits() {
         let mut ui_state = UiStateManager::new();
@@ -204,4 +395,28 @@ mod tests {
         assert!(!ui_state.is_dragging());
         assert_eq!(ui_state.drag_state(), None);
     }
+
+    #[test]
+    fn test_tool_switch_drops_drag_anchor() {
+        let mut ui_state = UiStateManager::new();
+        assert_eq!(ui_state.tool(), Tool::Draw);
+        ui_state.set_tool(Tool::Select);
+        ui_state.set_drag_anchor(Some((3, 4)));
+        ui_state.set_last_drag_cell(Some((5, 6)));
+        assert_eq!(ui_state.drag_anchor(), Some((3, 4)));
+
+        ui_state.set_tool(Tool::Shape(Shape::Line));
+        assert_eq!(ui_state.drag_anchor(), None);
+        assert_eq!(ui_state.last_drag_cell(), None);
+    }
+
+    #[test]
+    fn test_brush_size_limits() {
+        let mut ui_state = UiStateManager::new();
+        assert_eq!(ui_state.brush_size(), 1);
+        ui_state.set_brush_size(0);
+        assert_eq!(ui_state.brush_size(), 1);
+        ui_state.set_brush_size(100);
+        assert_eq!(ui_state.brush_size(), 16);
+    }
 }
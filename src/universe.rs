@@ -0,0 +1,384 @@
+//! 稀疏无界宇宙模块
+//! 宇宙被划分为 64x64 的瓦片，只存储包含存活细胞的瓦片，坐标可以为负数，
+//! 图案可以无限扩展而不会碰到边界
+
+use crate::game::{BoundingBox, CellState, LifeEngine};
+use crate::rules::{self, Rule};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+/// 瓦片边长（细胞数），与一个 `u64` 的位数相同
+const TILE_SIZE: i64 = 64;
+
+/// 一个瓦片：每行一个 `u64`，第 i 位对应该行第 i 列的细胞
+type Tile = [u64; TILE_SIZE as usize];
+
+/// 空瓦片，用于访问不存在的相邻瓦片
+const EMPTY_TILE: Tile = [0; TILE_SIZE as usize];
+
+/// 由稀疏瓦片组成的无界宇宙
+#[derive(Clone, Default)]
+pub struct SparseUniverse {
+    /// 瓦片坐标 -> 瓦片，只保留包含存活细胞的瓦片
+    tiles: FxHashMap<(i64, i64), Tile>,
+    /// 当前使用的演化规则
+    rule: Rule,
+}
+
+impl SparseUniverse {
+    /// 创建一个空宇宙
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 当前存储的瓦片数量
+    pub fn tile_count(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// 细胞坐标 -> (瓦片坐标, 瓦片内的列, 瓦片内的行)
+    fn locate(x: i64, y: i64) -> ((i64, i64), usize, usize) {
+        (
+            (x.div_euclid(TILE_SIZE), y.div_euclid(TILE_SIZE)),
+            x.rem_euclid(TILE_SIZE) as usize,
+            y.rem_euclid(TILE_SIZE) as usize,
+        )
+    }
+
+    /// 判断指定位置的细胞是否存活
+    pub fn is_alive(&self, x: i64, y: i64) -> bool {
+        let (key, column, row) = Self::locate(x, y);
+        self.tiles
+            .get(&key)
+            .is_some_and(|tile| tile[row] >> column & 1 == 1)
+    }
+
+    /// 需要参与下一代计算的瓦片：所有非空瓦片，以及边缘有存活细胞一侧的相邻瓦片
+    fn candidate_tiles(&self) -> FxHashSet<(i64, i64)> {
+        let mut candidates = FxHashSet::default();
+        for (&(tx, ty), tile) in &self.tiles {
+            let top = tile[0] != 0;
+            let bottom = tile[TILE_SIZE as usize - 1] != 0;
+            let left = tile.iter().any(|&row| row & 1 != 0);
+            let right = tile.iter().any(|&row| row >> (TILE_SIZE - 1) != 0);
+
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let horizontal = match dx {
+                        -1 => left,
+                        1 => right,
+                        _ => true,
+                    };
+                    let vertical = match dy {
+                        -1 => top,
+                        1 => bottom,
+                        _ => true,
+                    };
+                    if horizontal && vertical {
+                        candidates.insert((tx + dx, ty + dy));
+                    }
+                }
+            }
+        }
+        candidates
+    }
+
+    /// 计算一个瓦片的下一代
+    ///
+    /// `around[dy][dx]` 为以该瓦片为中心的 3x3 瓦片
+    fn step_tile(&self, around: &[[&Tile; 3]; 3]) -> Tile {
+        let last = TILE_SIZE as usize - 1;
+        // 第 row 行左、中、右三个瓦片中的字；row 可以越过上下边缘
+        let line = |row: isize| -> [u64; 3] {
+            let (band, index) = match row {
+                -1 => (0, last),
+                r if r as usize > last => (2, 0),
+                r => (1, r as usize),
+            };
+            [around[band][0][index], around[band][1][index], around[band][2][index]]
+        };
+        // 每个细胞左侧、右侧邻居组成的字
+        let west = |[left, center, _]: [u64; 3]| (center << 1) | (left >> (TILE_SIZE - 1));
+        let east = |[_, center, right]: [u64; 3]| (center >> 1) | (right << (TILE_SIZE - 1));
+
+        let conway = self.rule.is_conway();
+        let mut next = EMPTY_TILE;
+        for (row, out) in next.iter_mut().enumerate() {
+            let row = row as isize;
+            let (above, current, below) = (line(row - 1), line(row), line(row + 1));
+            let neighbors = [
+                west(above),
+                above[1],
+                east(above),
+                west(current),
+                east(current),
+                west(below),
+                below[1],
+                east(below),
+            ];
+            *out = if conway {
+                rules::conway_next_word(current[1], &neighbors)
+            } else {
+                self.rule.next_word(current[1], &neighbors)
+            };
+        }
+        next
+    }
+}
+
+impl LifeEngine for SparseUniverse {
+    fn rule(&self) -> &Rule {
+        &self.rule
+    }
+
+    /// 出生条件包含 B0 的规则会让整个无限平面复活，稀疏宇宙无法表示，这类规则返回错误
+    fn set_rule(&mut self, rule: Rule) -> Result<(), String> {
+        rule.check_unbounded()?;
+        self.rule = rule;
+        Ok(())
+    }
+
+    fn get_cell(&self, x: i64, y: i64) -> &CellState {
+        if self.is_alive(x, y) {
+            &CellState::Alive
+        } else {
+            &CellState::Dead
+        }
+    }
+
+    fn set_cell(&mut self, x: i64, y: i64, state: CellState) {
+        let (key, column, row) = Self::locate(x, y);
+        match state {
+            CellState::Alive => self.tiles.entry(key).or_insert(EMPTY_TILE)[row] |= 1 << column,
+            CellState::Dead => {
+                if let Some(tile) = self.tiles.get_mut(&key) {
+                    tile[row] &= !(1 << column);
+                    if tile.iter().all(|&word| word == 0) {
+                        self.tiles.remove(&key);
+                    }
+                }
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        self.tiles.clear();
+    }
+
+    fn next_generation(&mut self) {
+        let candidates = self.candidate_tiles();
+        let mut next = FxHashMap::with_capacity_and_hasher(candidates.len(), Default::default());
+        for (tx, ty) in candidates {
+            let tile = |dx: i64, dy: i64| self.tiles.get(&(tx + dx, ty + dy)).unwrap_or(&EMPTY_TILE);
+            let around = [
+                [tile(-1, -1), tile(0, -1), tile(1, -1)],
+                [tile(-1, 0), tile(0, 0), tile(1, 0)],
+                [tile(-1, 1), tile(0, 1), tile(1, 1)],
+            ];
+            let stepped = self.step_tile(&around);
+            // 丢弃演化后变空的瓦片
+            if stepped.iter().any(|&word| word != 0) {
+                next.insert((tx, ty), stepped);
+            }
+        }
+        self.tiles = next;
+    }
+
+    fn count_alive_cells(&self) -> usize {
+        self.tiles
+            .values()
+            .flat_map(|tile| tile.iter())
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    fn bounding_box(&self) -> Option<BoundingBox> {
+        let mut bounds: Option<BoundingBox> = None;
+        for (&(tx, ty), tile) in &self.tiles {
+            let first_row = match tile.iter().position(|&word| word != 0) {
+                Some(row) => row as i64,
+                None => continue,
+            };
+            let last_row = tile.iter().rposition(|&word| word != 0).unwrap_or(0) as i64;
+            let columns = tile.iter().fold(0u64, |acc, &word| acc | word);
+            let first_column = columns.trailing_zeros() as i64;
+            let last_column = TILE_SIZE - 1 - columns.leading_zeros() as i64;
+
+            let (x, y) = (tx * TILE_SIZE, ty * TILE_SIZE);
+            let corners = [(x + first_column, y + first_row), (x + last_column, y + last_row)];
+            for (cx, cy) in corners {
+                match bounds.as_mut() {
+                    Some(bounds) => bounds.include(cx, cy),
+                    None => bounds = Some(BoundingBox::around(cx, cy)),
+                }
+            }
+        }
+        bounds
+    }
+
+    fn for_each_alive_in(&self, min: (i64, i64), max: (i64, i64), visit: &mut dyn FnMut(i64, i64)) {
+        for (&(tx, ty), tile) in &self.tiles {
+            let (x, y) = (tx * TILE_SIZE, ty * TILE_SIZE);
+            // 跳过与矩形不相交的瓦片
+            if x >= max.0 || y >= max.1 || x + TILE_SIZE <= min.0 || y + TILE_SIZE <= min.1 {
+                continue;
+            }
+            for (row, &word) in tile.iter().enumerate() {
+                let cy = y + row as i64;
+                if cy < min.1 || cy >= max.1 {
+                    continue;
+                }
+                let mut bits = word;
+                while bits != 0 {
+                    let cx = x + bits.trailing_zeros() as i64;
+                    bits &= bits - 1;
+                    if cx >= min.0 && cx < max.0 {
+                        visit(cx, cy);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Grid;
+    use crate::hashlife::HashLife;
+    use crate::patterns::spaceships;
+
+    /// 收集所有存活细胞（排序后便于比较）
+    fn alive_cells(engine: &dyn LifeEngine) -> Vec<(i64, i64)> {
+        let mut cells = Vec::new();
+        let bound = 1i64 << 40;
+        engine.for_each_alive_in((-bound, -bound), (bound, bound), &mut |x, y| cells.push((x, y)));
+        cells.sort();
+        cells
+    }
+
+    /// 在指定区域内填充伪随机细胞
+    fn fill_soup(engine: &mut dyn LifeEngine, x: i64, y: i64, size: i64, mut state: u64) {
+        for dy in 0..size {
+            for dx in 0..size {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                if state >> 63 == 1 {
+                    engine.set_cell(x + dx, y + dy, CellState::Alive);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_negative_coordinates() {
+        let mut universe = SparseUniverse::new();
+        universe.set_cell(-1, -1, CellState::Alive);
+        universe.set_cell(-64, 63, CellState::Alive);
+        universe.set_cell(64, 0, CellState::Alive);
+
+        assert!(universe.is_alive(-1, -1));
+        assert!(universe.is_alive(-64, 63));
+        assert!(!universe.is_alive(0, 0));
+        assert_eq!(universe.count_alive_cells(), 3);
+        assert_eq!(universe.tile_count(), 3);
+
+        universe.set_cell(-1, -1, CellState::Dead);
+        assert_eq!(universe.tile_count(), 2);
+    }
+
+    #[test]
+    fn test_blinker_across_tile_corner() {
+        // 竖直闪烁器跨越四个瓦片的交汇处
+        let mut universe = SparseUniverse::new();
+        for y in -1..=1 {
+            universe.set_cell(0, y, CellState::Alive);
+        }
+        universe.next_generation();
+        assert_eq!(alive_cells(&universe), vec![(-1, 0), (0, 0), (1, 0)]);
+        universe.next_generation();
+        assert_eq!(alive_cells(&universe), vec![(0, -1), (0, 0), (0, 1)]);
+    }
+
+    #[test]
+    fn test_glider_travels_through_tiles() {
+        let mut universe = SparseUniverse::new();
+        for (dy, line) in spaceships::GLIDER.data.iter().enumerate() {
+            for (dx, ch) in line.chars().enumerate() {
+                if ch == 'O' {
+                    universe.set_cell(dx as i64 - 100, dy as i64 - 100, CellState::Alive);
+                }
+            }
+        }
+        let start = alive_cells(&universe);
+
+        // 滑翔机每4代沿对角线移动一格，800代穿过多个瓦片
+        universe.advance(800);
+        let moved: Vec<(i64, i64)> = start.iter().map(|&(x, y)| (x + 200, y + 200)).collect();
+        assert_eq!(alive_cells(&universe), moved);
+        assert!(universe.tile_count() <= 4);
+    }
+
+    #[test]
+    fn test_matches_grid_and_hashlife() {
+        let mut universe = SparseUniverse::new();
+        fill_soup(&mut universe, -10, -10, 20, 3);
+        let mut hashlife = HashLife::new();
+        fill_soup(&mut hashlife, -10, -10, 20, 3);
+
+        // 网格足够大，保证演化期间不会碰到边界
+        let mut window = Grid::new(200, 200);
+        universe.render_window(&mut window, (-100, -100));
+        assert_eq!(window.count_alive_cells(), universe.count_alive_cells());
+
+        for _ in 0..60 {
+            universe.next_generation();
+            window.next_generation();
+        }
+        hashlife.advance(60);
+
+        assert_eq!(alive_cells(&universe), alive_cells(&hashlife));
+        let mut rendered = Grid::new(200, 200);
+        universe.render_window(&mut rendered, (-100, -100));
+        assert_eq!(rendered.count_alive_cells(), universe.count_alive_cells());
+        for y in 0..200 {
+            for x in 0..200 {
+                assert_eq!(rendered.is_alive(x, y), window.is_alive(x, y));
+            }
+        }
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let mut universe = SparseUniverse::new();
+        assert_eq!(universe.bounding_box(), None);
+
+        universe.set_cell(-70, 5, CellState::Alive);
+        universe.set_cell(3, -130, CellState::Alive);
+        universe.set_cell(200, 64, CellState::Alive);
+        let expected = BoundingBox {
+            min_x: -70,
+            min_y: -130,
+            max_x: 200,
+            max_y: 64,
+        };
+        assert_eq!(universe.bounding_box(), Some(expected));
+        assert_eq!((expected.width(), expected.height()), (271, 195));
+
+        let mut hashlife = HashLife::new();
+        for (x, y) in alive_cells(&universe) {
+            hashlife.set_cell(x, y, CellState::Alive);
+        }
+        assert_eq!(hashlife.bounding_box(), Some(expected));
+    }
+
+    #[test]
+    fn test_b0_rules_are_rejected() {
+        let b0 = Rule::parse("B0/S8").unwrap();
+        let highlife = Rule::parse("B36/S23").unwrap();
+        let engines: [&mut dyn LifeEngine; 2] = [&mut SparseUniverse::new(), &mut HashLife::new()];
+        for engine in engines {
+            engine.set_rule(highlife).unwrap();
+            assert!(engine.set_rule(b0).is_err());
+            assert_eq!(*engine.rule(), highlife);
+        }
+    }
+}